@@ -0,0 +1,249 @@
+//! Aligning lines into columns, used by the `:align` command.
+//!
+//! Each line is split into fields on the matches of a delimiter regex. Fields are trimmed and
+//! padded to the width of the widest field in their column, with a single space on both sides
+//! of each delimiter. Lines without a delimiter are left unchanged.
+
+use regex::Regex;
+use unicode_width::UnicodeWidthStr;
+
+/// How the fields of a column are padded.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Justify {
+    #[default]
+    Left,
+    Right,
+    Center,
+}
+
+impl Justify {
+    /// Parses a justification from its first letter: `l`, `r` or `c`.
+    pub fn from_char(c: char) -> Option<Self> {
+        match c {
+            'l' => Some(Self::Left),
+            'r' => Some(Self::Right),
+            'c' => Some(Self::Center),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct AlignOptions {
+    /// The justification of each column. The last justification is used for the remaining
+    /// columns, columns are left justified if this is empty.
+    pub justify: Vec<Justify>,
+    /// The maximum number of columns. The rest of a line with more delimiters is kept as the
+    /// last field.
+    pub max_columns: Option<usize>,
+    /// Treat the lines as a Markdown table: separator rows like `|:--|--:|` are normalized to
+    /// the width of their columns and their colons justify the columns unless `justify` is set.
+    pub markdown: bool,
+}
+
+struct Row<'a> {
+    indent: &'a str,
+    fields: Vec<&'a str>,
+    delimiters: Vec<&'a str>,
+    /// Whether this is the separator row of a Markdown table.
+    separator: bool,
+}
+
+impl<'a> Row<'a> {
+    fn parse(line: &'a str, delimiter: &Regex, options: &AlignOptions) -> Option<Self> {
+        let max_delimiters = options.max_columns.map_or(usize::MAX, |max| max - 1);
+        let content = line.trim_start();
+        let indent = &line[..line.len() - content.len()];
+
+        let mut fields = Vec::new();
+        let mut delimiters = Vec::new();
+        let mut start = 0;
+        for m in delimiter
+            .find_iter(content)
+            .filter(|m| !m.is_empty())
+            .take(max_delimiters)
+        {
+            fields.push(content[start..m.start()].trim());
+            delimiters.push(m.as_str());
+            start = m.end();
+        }
+        if delimiters.is_empty() {
+            return None;
+        }
+        fields.push(content[start..].trim());
+
+        let separator = options.markdown
+            && fields.iter().any(|field| !field.is_empty())
+            && fields
+                .iter()
+                .all(|field| field.is_empty() || separator_cell(field).is_some());
+        Some(Self {
+            indent,
+            fields,
+            delimiters,
+            separator,
+        })
+    }
+}
+
+/// Returns the justification of a separator cell of a Markdown table like `:---:`, `None` if it
+/// has no colons.
+fn separator_cell(field: &str) -> Option<Option<Justify>> {
+    let dashes = field.trim_start_matches(':').trim_end_matches(':');
+    if dashes.is_empty() || !dashes.chars().all(|c| c == '-') {
+        return None;
+    }
+    let justify = match (field.starts_with(':'), field.ends_with(':')) {
+        (true, true) => Some(Justify::Center),
+        (false, true) => Some(Justify::Right),
+        (true, false) => Some(Justify::Left),
+        (false, false) => None,
+    };
+    Some(justify)
+}
+
+/// Aligns `lines`, which must not contain line endings, into columns on the matches of
+/// `delimiter`. Returns the aligned lines.
+pub fn align(lines: &[&str], delimiter: &Regex, options: &AlignOptions) -> Vec<String> {
+    let rows: Vec<_> = lines
+        .iter()
+        .map(|line| Row::parse(line, delimiter, options))
+        .collect();
+
+    let columns = rows
+        .iter()
+        .flatten()
+        .map(|row| row.fields.len())
+        .max()
+        .unwrap_or_default();
+    let mut widths = vec![0; columns];
+    let mut justify: Vec<_> = (0..columns)
+        .map(|column| {
+            let index = column.min(options.justify.len().saturating_sub(1));
+            options.justify.get(index).copied()
+        })
+        .collect();
+    // Separator rows of Markdown tables have at least three dashes per cell.
+    const MIN_SEPARATOR_WIDTH: usize = 3;
+    for row in rows.iter().flatten() {
+        for (column, field) in row.fields.iter().enumerate() {
+            let width = if row.separator && !field.is_empty() {
+                if options.justify.is_empty() {
+                    justify[column] = separator_cell(field).flatten();
+                }
+                MIN_SEPARATOR_WIDTH
+            } else {
+                field.width()
+            };
+            widths[column] = widths[column].max(width);
+        }
+    }
+
+    lines
+        .iter()
+        .zip(&rows)
+        .map(|(line, row)| {
+            let Some(row) = row else {
+                return line.to_string();
+            };
+            let mut aligned = row.indent.to_string();
+            for (column, field) in row.fields.iter().enumerate() {
+                if column > 0 {
+                    // Delimiters at the start of a line are not preceded by a space.
+                    if column > 1 || widths[0] > 0 {
+                        aligned.push(' ');
+                    }
+                    aligned.push_str(row.delimiters[column - 1]);
+                    aligned.push(' ');
+                }
+                let width = widths[column];
+                let justify = justify[column];
+                if row.separator && !field.is_empty() {
+                    aligned.push_str(&separator(width, justify));
+                } else {
+                    pad(&mut aligned, field, width, justify.unwrap_or_default());
+                }
+            }
+            aligned.truncate(aligned.trim_end().len());
+            aligned
+        })
+        .collect()
+}
+
+fn pad(out: &mut String, field: &str, width: usize, justify: Justify) {
+    let padding = width.saturating_sub(field.width());
+    let (before, after) = match justify {
+        Justify::Left => (0, padding),
+        Justify::Right => (padding, 0),
+        Justify::Center => (padding / 2, padding - padding / 2),
+    };
+    out.extend(std::iter::repeat_n(' ', before));
+    out.push_str(field);
+    out.extend(std::iter::repeat_n(' ', after));
+}
+
+fn separator(width: usize, justify: Option<Justify>) -> String {
+    let (left, right) = match justify {
+        None => ("", ""),
+        Some(Justify::Left) => (":", ""),
+        Some(Justify::Right) => ("", ":"),
+        Some(Justify::Center) => (":", ":"),
+    };
+    let dashes = width.saturating_sub(left.len() + right.len()).max(1);
+    format!("{left}{}{right}", "-".repeat(dashes))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn aligned(text: &str, delimiter: &str, options: AlignOptions) -> String {
+        let lines: Vec<_> = text.lines().collect();
+        align(&lines, &Regex::new(delimiter).unwrap(), &options).join("\n")
+    }
+
+    #[test]
+    fn align_assignments() {
+        let text = "    a = 1\n    long_name=2\n\n    bb  =  3 = 4";
+        assert_eq!(
+            aligned(text, "=", AlignOptions::default()),
+            "    a         = 1\n    long_name = 2\n\n    bb        = 3 = 4"
+        );
+        let options = AlignOptions {
+            justify: vec![Justify::Right, Justify::Left],
+            max_columns: Some(2),
+            markdown: false,
+        };
+        assert_eq!(
+            aligned(text, "=", options),
+            "            a = 1\n    long_name = 2\n\n           bb = 3 = 4"
+        );
+    }
+
+    #[test]
+    fn align_markdown_table() {
+        let text = "| Key | Description |\n|:-|-:|\n| `a` | Append |\n| `Space` | Space mode |";
+        let options = AlignOptions {
+            markdown: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            aligned(text, r"\|", options),
+            "\
+| Key     | Description |
+| :------ | ----------: |
+| `a`     |      Append |
+| `Space` |  Space mode |"
+        );
+
+        let options = AlignOptions {
+            justify: vec![Justify::Center],
+            markdown: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            aligned("|a|bbbbb|\n|---|---|\n", r"\|", options),
+            "|  a  | bbbbb |\n| :-: | :---: |"
+        );
+    }
+}
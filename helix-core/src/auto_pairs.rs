@@ -1,8 +1,12 @@
 //! When typing the opening character of one of the possible pairs defined below,
 //! this module provides the functionality to insert the paired closing character.
+//!
+//! Pairs may consist of multiple characters like `"""` or `{%` and `%}`. These are closed when
+//! the last character of the opening string is typed after the rest of it.
 
-use crate::{graphemes, movement::Direction, Range, Rope, Selection, Tendril, Transaction};
-use std::collections::HashMap;
+use crate::{
+    graphemes, movement::Direction, Range, Rope, RopeSlice, Selection, Syntax, Tendril, Transaction,
+};
 
 use smallvec::SmallVec;
 
@@ -16,33 +20,52 @@
     ('`', '`'),
 ];
 
-/// The type that represents the collection of auto pairs,
-/// keyed by both opener and closer.
+/// The type that represents the collection of auto pairs.
 #[derive(Debug, Clone)]
-pub struct AutoPairs(HashMap<char, Pair>);
+pub struct AutoPairs(Vec<Pair>);
 
 /// Represents the config for a particular pairing.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Pair {
-    pub open: char,
-    pub close: char,
+    pub open: String,
+    pub close: String,
+    /// The kinds of syntax nodes in which the pair is not closed, like `comment` or `string`.
+    pub not_in: Vec<String>,
 }
 
 impl Pair {
+    pub fn new(open: impl Into<String>, close: impl Into<String>) -> Self {
+        Self {
+            open: open.into(),
+            close: close.into(),
+            not_in: Vec::new(),
+        }
+    }
+
     /// true if open == close
     pub fn same(&self) -> bool {
         self.open == self.close
     }
 
+    /// The opening and closing character if both are single characters.
+    pub fn chars(&self) -> Option<(char, char)> {
+        let mut open = self.open.chars();
+        let mut close = self.close.chars();
+        match (open.next(), open.next(), close.next(), close.next()) {
+            (Some(open), None, Some(close), None) => Some((open, close)),
+            _ => None,
+        }
+    }
+
     /// true if all of the pair's conditions hold for the given document and range
-    pub fn should_close(&self, doc: &Rope, range: &Range) -> bool {
+    pub fn should_close(&self, doc: &Rope, syntax: Option<&Syntax>, range: &Range) -> bool {
         let mut should_close = Self::next_is_not_alpha(doc, range);
 
         if self.same() {
             should_close &= Self::prev_is_not_alpha(doc, range);
         }
 
-        should_close
+        should_close && self.allowed_at(doc, syntax, range.cursor(doc.slice(..)))
     }
 
     pub fn next_is_not_alpha(doc: &Rope, range: &Range) -> bool {
@@ -56,20 +79,40 @@ pub fn prev_is_not_alpha(doc: &Rope, range: &Range) -> bool {
         let prev_char = prev_char(doc, cursor);
         prev_char.map(|c| !c.is_alphanumeric()).unwrap_or(true)
     }
+
+    /// true unless the insertion point `pos` is inside one of the syntax nodes of `not_in`.
+    ///
+    /// The node at `pos` and the node ending at `pos` are checked along with the nodes enclosing
+    /// them, since typing at the end of a node like a line comment extends it. The inserted text
+    /// is not parsed yet, so a pair is skipped where the text it starts would be written, like
+    /// the types in which Rust lifetimes are written, rather than in the node it starts.
+    pub fn allowed_at(&self, doc: &Rope, syntax: Option<&Syntax>, pos: usize) -> bool {
+        let Some(syntax) = syntax.filter(|_| !self.not_in.is_empty()) else {
+            return true;
+        };
+        let text = doc.slice(..);
+        let start = text.char_to_byte(pos.saturating_sub(1)) as u32;
+        let end = text.char_to_byte(pos) as u32;
+        let nodes = [
+            syntax.descendant_for_byte_range(end, end),
+            syntax.descendant_for_byte_range(start, end),
+        ];
+        !nodes.into_iter().flatten().any(|node| {
+            std::iter::successors(Some(node), |node| node.parent())
+                .any(|node| self.not_in.iter().any(|kind| kind == node.kind()))
+        })
+    }
 }
 
 impl From<&(char, char)> for Pair {
     fn from(&(open, close): &(char, char)) -> Self {
-        Self { open, close }
+        Self::new(open, close)
     }
 }
 
 impl From<(&char, &char)> for Pair {
     fn from((open, close): (&char, &char)) -> Self {
-        Self {
-            open: *open,
-            close: *close,
-        }
+        Self::new(*open, *close)
     }
 }
 
@@ -80,23 +123,41 @@ pub fn new<'a, V, A>(pairs: V) -> Self
         V: IntoIterator<Item = A> + 'a,
         A: Into<Pair>,
     {
-        let mut auto_pairs = HashMap::new();
-
-        for pair in pairs.into_iter() {
-            let auto_pair = pair.into();
-
-            auto_pairs.insert(auto_pair.open, auto_pair);
-
-            if auto_pair.open != auto_pair.close {
-                auto_pairs.insert(auto_pair.close, auto_pair);
-            }
-        }
+        let mut auto_pairs: Vec<Pair> = pairs
+            .into_iter()
+            .map(Into::into)
+            .filter(|pair| !pair.open.is_empty() && !pair.close.is_empty())
+            .collect();
+        // Longer pairs are tried first.
+        auto_pairs.sort_by(|a, b| {
+            b.open
+                .chars()
+                .count()
+                .cmp(&a.open.chars().count())
+                .then_with(|| a.open.cmp(&b.open))
+        });
 
         Self(auto_pairs)
     }
 
+    /// Returns the pair of single characters which opens or closes with `ch`.
     pub fn get(&self, ch: char) -> Option<&Pair> {
-        self.0.get(&ch)
+        let single = || self.0.iter().filter_map(|pair| Some((pair, pair.chars()?)));
+        single()
+            .find(|(_, (open, _))| *open == ch)
+            .or_else(|| single().find(|(_, (_, close))| *close == ch))
+            .map(|(pair, _)| pair)
+    }
+
+    /// Returns the longest pair which opens right before `pos` and closes right after it.
+    pub fn pair_at(&self, text: RopeSlice, pos: usize) -> Option<&Pair> {
+        self.0.iter().find(|pair| {
+            ends_with_at(text, pos, &pair.open) && starts_with_at(text, pos, &pair.close)
+        })
+    }
+
+    fn multi_char(&self) -> impl Iterator<Item = &Pair> {
+        self.0.iter().filter(|pair| pair.chars().is_none())
     }
 }
 
@@ -106,6 +167,17 @@ fn default() -> Self {
     }
 }
 
+fn starts_with_at(text: RopeSlice, pos: usize, prefix: &str) -> bool {
+    text.get_slice(pos..pos + prefix.chars().count())
+        .is_some_and(|slice| slice == prefix)
+}
+
+fn ends_with_at(text: RopeSlice, pos: usize, suffix: &str) -> bool {
+    pos.checked_sub(suffix.chars().count())
+        .and_then(|start| text.get_slice(start..pos))
+        .is_some_and(|slice| slice == suffix)
+}
+
 // insert hook:
 // Fn(doc, selection, char) => Option<Transaction>
 // problem is, we want to do this per range, so we can call default handler for some ranges
@@ -114,23 +186,29 @@ fn default() -> Self {
 //
 // to simplify, maybe return Option<Transaction> and just reimplement the default
 
-// [TODO]
-// * delete implementation where it erases the whole bracket (|) -> |
-// * change to multi character pairs to handle cases like placing the cursor in the
-//   middle of triple quotes, and more exotic pairs like Jinja's {% %}
-
 #[must_use]
-pub fn hook(doc: &Rope, selection: &Selection, ch: char, pairs: &AutoPairs) -> Option<Transaction> {
+pub fn hook(
+    doc: &Rope,
+    selection: &Selection,
+    ch: char,
+    pairs: &AutoPairs,
+    syntax: Option<&Syntax>,
+) -> Option<Transaction> {
     log::trace!("autopairs hook selection: {:#?}", selection);
 
+    if let Some(transaction) = handle_multi_char(doc, selection, ch, pairs, syntax) {
+        return Some(transaction);
+    }
+
     if let Some(pair) = pairs.get(ch) {
+        let (open, close) = pair.chars()?;
         if pair.same() {
-            return Some(handle_same(doc, selection, pair));
-        } else if pair.open == ch {
-            return Some(handle_open(doc, selection, pair));
-        } else if pair.close == ch {
+            return Some(handle_same(doc, selection, pair, ch, syntax));
+        } else if open == ch {
+            return Some(handle_open(doc, selection, pair, (open, close), syntax));
+        } else if close == ch {
             // && char_at pos == close
-            return Some(handle_close(doc, selection, pair));
+            return Some(handle_close(doc, selection, close));
         }
     }
 
@@ -262,7 +340,13 @@ fn get_next_range(doc: &Rope, start_range: &Range, offset: usize, len_inserted:
     Range::new(end_anchor, end_head)
 }
 
-fn handle_open(doc: &Rope, selection: &Selection, pair: &Pair) -> Transaction {
+fn handle_open(
+    doc: &Rope,
+    selection: &Selection,
+    pair: &Pair,
+    (open, close): (char, char),
+    syntax: Option<&Syntax>,
+) -> Transaction {
     let mut end_ranges = SmallVec::with_capacity(selection.len());
     let mut offs = 0;
 
@@ -271,19 +355,18 @@ fn handle_open(doc: &Rope, selection: &Selection, pair: &Pair) -> Transaction {
         let next_char = doc.get_char(cursor);
         let len_inserted;
 
-        // Since auto pairs are currently limited to single chars, we're either
-        // inserting exactly one or two chars. When arbitrary length pairs are
-        // added, these will need to be changed.
+        // Pairs of multiple chars are handled by `handle_multi_char`, so we're
+        // either inserting exactly one or two chars.
         let change = match next_char {
-            Some(_) if !pair.should_close(doc, start_range) => {
+            Some(_) if !pair.should_close(doc, syntax, start_range) => {
                 len_inserted = 1;
                 let mut tendril = Tendril::new();
-                tendril.push(pair.open);
+                tendril.push(open);
                 (cursor, cursor, Some(tendril))
             }
             _ => {
                 // insert open & close
-                let pair_str = Tendril::from_iter([pair.open, pair.close]);
+                let pair_str = Tendril::from_iter([open, close]);
                 len_inserted = 2;
                 (cursor, cursor, Some(pair_str))
             }
@@ -301,7 +384,7 @@ fn handle_open(doc: &Rope, selection: &Selection, pair: &Pair) -> Transaction {
     t
 }
 
-fn handle_close(doc: &Rope, selection: &Selection, pair: &Pair) -> Transaction {
+fn handle_close(doc: &Rope, selection: &Selection, close: char) -> Transaction {
     let mut end_ranges = SmallVec::with_capacity(selection.len());
     let mut offs = 0;
 
@@ -310,13 +393,13 @@ fn handle_close(doc: &Rope, selection: &Selection, pair: &Pair) -> Transaction {
         let next_char = doc.get_char(cursor);
         let mut len_inserted = 0;
 
-        let change = if next_char == Some(pair.close) {
+        let change = if next_char == Some(close) {
             // return transaction that moves past close
             (cursor, cursor, None) // no-op
         } else {
             len_inserted = 1;
             let mut tendril = Tendril::new();
-            tendril.push(pair.close);
+            tendril.push(close);
             (cursor, cursor, Some(tendril))
         };
 
@@ -332,8 +415,14 @@ fn handle_close(doc: &Rope, selection: &Selection, pair: &Pair) -> Transaction {
     t
 }
 
-/// handle cases where open and close is the same, or in triples ("""docstring""")
-fn handle_same(doc: &Rope, selection: &Selection, pair: &Pair) -> Transaction {
+/// handle cases where open and close is the same
+fn handle_same(
+    doc: &Rope,
+    selection: &Selection,
+    pair: &Pair,
+    ch: char,
+    syntax: Option<&Syntax>,
+) -> Transaction {
     let mut end_ranges = SmallVec::with_capacity(selection.len());
 
     let mut offs = 0;
@@ -343,17 +432,17 @@ fn handle_same(doc: &Rope, selection: &Selection, pair: &Pair) -> Transaction {
         let mut len_inserted = 0;
         let next_char = doc.get_char(cursor);
 
-        let change = if next_char == Some(pair.open) {
+        let change = if next_char == Some(ch) {
             //  return transaction that moves past close
             (cursor, cursor, None) // no-op
         } else {
             let mut pair_str = Tendril::new();
-            pair_str.push(pair.open);
+            pair_str.push(ch);
 
             // for equal pairs, don't insert both open and close if either
             // side has a non-pair char
-            if pair.should_close(doc, start_range) {
-                pair_str.push(pair.close);
+            if pair.should_close(doc, syntax, start_range) {
+                pair_str.push(ch);
             }
 
             len_inserted += pair_str.chars().count();
@@ -371,3 +460,109 @@ fn handle_same(doc: &Rope, selection: &Selection, pair: &Pair) -> Transaction {
     log::debug!("auto pair transaction: {:#?}", t);
     t
 }
+
+/// Handles pairs of multiple characters: closes a pair when `ch` completes its opening string
+/// and skips over the closing string of a pair while it is typed. Returns `None` unless this
+/// applies to every range.
+fn handle_multi_char(
+    doc: &Rope,
+    selection: &Selection,
+    ch: char,
+    pairs: &AutoPairs,
+    syntax: Option<&Syntax>,
+) -> Option<Transaction> {
+    let text = doc.slice(..);
+    let mut buf = [0; 4];
+    let typed: &str = ch.encode_utf8(&mut buf);
+    let mut end_ranges = SmallVec::with_capacity(selection.len());
+    let mut changes = Vec::with_capacity(selection.len());
+    let mut offs = 0;
+
+    for start_range in selection {
+        if !start_range.is_empty() && !start_range.is_single_grapheme(text) {
+            return None;
+        }
+        let cursor = start_range.cursor(text);
+
+        // Typing the last char of an opening string, unless the char is being skipped over.
+        let open = (text.get_char(cursor) != Some(ch))
+            .then(|| {
+                pairs.multi_char().find(|pair| {
+                    pair.open
+                        .strip_suffix(typed)
+                        .is_some_and(|prefix| ends_with_at(text, cursor, prefix))
+                        && pair.allowed_at(doc, syntax, cursor)
+                })
+            })
+            .flatten();
+
+        if let Some(pair) = open {
+            // The opening string may start with a pair which was closed already, like `{`
+            // in `{%`. Its closing string is replaced by the closing string of the new pair.
+            let prefix = &pair.open[..pair.open.len() - typed.len()];
+            let replaced = pairs
+                .0
+                .iter()
+                .filter(|inner| inner != &pair && prefix.ends_with(&inner.open))
+                .find(|inner| starts_with_at(text, cursor, &inner.close))
+                .map_or(0, |inner| inner.close.chars().count());
+
+            let mut insert = Tendril::from(typed);
+            insert.push_str(&pair.close);
+            end_ranges.push(get_next_range(doc, start_range, offs, 2));
+            offs += insert.chars().count() - replaced;
+            changes.push((cursor, cursor + replaced, Some(insert)));
+            continue;
+        }
+
+        // Typing the next char of a closing string skips over it.
+        let skip = pairs.multi_char().any(|pair| {
+            pair.close.char_indices().any(|(i, c)| {
+                c == ch
+                    && ends_with_at(text, cursor, &pair.close[..i])
+                    && starts_with_at(text, cursor, &pair.close[i..])
+            })
+        });
+        if !skip {
+            return None;
+        }
+        end_ranges.push(get_next_range(doc, start_range, offs, 0));
+        changes.push((cursor, cursor, None));
+    }
+
+    let t = Transaction::change(doc, changes.into_iter())
+        .with_selection(Selection::new(end_ranges, selection.primary_index()));
+    log::debug!("auto pair transaction: {:#?}", t);
+    Some(t)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Types `'` at `pos` in a Rust document and returns the resulting text.
+    fn type_quote(text: &str, pos: usize) -> String {
+        let loader = crate::config::default_lang_loader();
+        let rust = loader.language_for_name("rust".to_string()).unwrap();
+        let pairs = loader.language(rust).config().auto_pairs.clone().unwrap();
+        let mut doc = Rope::from(text);
+        let syntax = Syntax::new(doc.slice(..), rust, &loader).unwrap();
+        let transaction = hook(&doc, &Selection::point(pos), '\'', &pairs, Some(&syntax)).unwrap();
+        transaction.apply(&mut doc);
+        doc.to_string()
+    }
+
+    #[test]
+    fn pair_allowed_by_syntax() {
+        assert_eq!(type_quote("fn main() { f(); }", 14), "fn main() { f(''); }");
+    }
+
+    #[test]
+    fn pair_blocked_by_syntax() {
+        // Lifetimes in types.
+        assert_eq!(type_quote("fn f(x: & u8) {}", 9), "fn f(x: &' u8) {}");
+        // Comments, including their end.
+        assert_eq!(type_quote("// a  b\nfn f() {}", 5), "// a ' b\nfn f() {}");
+        assert_eq!(type_quote("// a \nfn f() {}", 5), "// a '\nfn f() {}");
+    }
+}
@@ -0,0 +1,213 @@
+//! Rectangular (block) selections.
+//!
+//! A block selection is described by two corners in visual coordinates, which take the tab width
+//! and wide graphemes into account. It is turned into a regular [`Selection`] with one range per
+//! line, each clipped to the visual columns between the two corners.
+
+use std::ops::RangeInclusive;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    graphemes::{next_grapheme_boundary, prev_grapheme_boundary},
+    line_ending::line_end_char_index,
+    movement::Direction,
+    Change, Position, Range, RopeSlice, Selection, Tendril,
+};
+// Block selections work on the visual columns of the text and ignore softwrap and decorations,
+// which is what the deprecated functions compute.
+#[allow(deprecated)]
+use crate::{pos_at_visual_coords, visual_coords_at_pos};
+
+/// How lines which end before the left edge of a block selection are treated.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum VirtualSpace {
+    /// Short lines are not part of the selection.
+    #[default]
+    Skip,
+    /// Short lines get a cursor at their end and are padded with spaces when inserting or
+    /// appending across the block.
+    Pad,
+}
+
+/// A rectangular selection between two corners given in visual coordinates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlockSelection {
+    pub anchor: Position,
+    pub head: Position,
+}
+
+impl BlockSelection {
+    pub fn new(anchor: Position, head: Position) -> Self {
+        Self { anchor, head }
+    }
+
+    /// Creates a block spanning from the first to the last grapheme of `range`, keeping its
+    /// direction.
+    #[allow(deprecated)]
+    pub fn from_range(text: RopeSlice, range: Range, tab_width: usize) -> Self {
+        let last = prev_grapheme_boundary(text, range.to()).max(range.from());
+        let (anchor, head) = match range.direction() {
+            Direction::Forward => (range.from(), last),
+            Direction::Backward => (last, range.from()),
+        };
+        Self::new(
+            visual_coords_at_pos(text, anchor, tab_width),
+            visual_coords_at_pos(text, head, tab_width),
+        )
+    }
+
+    /// The lines covered by the block.
+    pub fn lines(&self) -> RangeInclusive<usize> {
+        self.anchor.row.min(self.head.row)..=self.anchor.row.max(self.head.row)
+    }
+
+    /// The visual columns covered by the block.
+    pub fn columns(&self) -> RangeInclusive<usize> {
+        self.anchor.col.min(self.head.col)..=self.anchor.col.max(self.head.col)
+    }
+
+    /// Converts the block into a selection with one range per line.
+    ///
+    /// Graphemes which are only partially covered by the block, like tabs or wide characters,
+    /// are included completely. The range on the line of the head is the primary range and is
+    /// always part of the selection, even if the line is too short to reach the block.
+    #[allow(deprecated)]
+    pub fn to_selection(
+        &self,
+        text: RopeSlice,
+        tab_width: usize,
+        virtual_space: VirtualSpace,
+    ) -> Selection {
+        let last_line = text.len_lines() - 1;
+        let head_line = self.head.row.min(last_line);
+        let columns = self.columns();
+        let backward = self.head.col < self.anchor.col;
+
+        let mut ranges = Vec::new();
+        let mut primary_index = 0;
+        for line in self.lines() {
+            if line > last_line {
+                break;
+            }
+            let line_end = line_end_char_index(&text, line);
+            let start =
+                pos_at_visual_coords(text, Position::new(line, *columns.start()), tab_width);
+            let range = if start == line_end {
+                if line != head_line && virtual_space == VirtualSpace::Skip {
+                    continue;
+                }
+                Range::point(line_end)
+            } else {
+                let end =
+                    pos_at_visual_coords(text, Position::new(line, *columns.end()), tab_width);
+                let end = if end == line_end {
+                    line_end
+                } else {
+                    next_grapheme_boundary(text, end)
+                };
+                if backward {
+                    Range::new(end, start)
+                } else {
+                    Range::new(start, end)
+                }
+            };
+            if line == head_line {
+                primary_index = ranges.len();
+            }
+            ranges.push(range);
+        }
+
+        Selection::new(ranges.into(), primary_index)
+    }
+
+    /// Returns the changes which pad every line of the block with spaces until it reaches the
+    /// visual column `col`.
+    #[allow(deprecated)]
+    pub fn padding(&self, text: RopeSlice, tab_width: usize, col: usize) -> Vec<Change> {
+        let last_line = text.len_lines() - 1;
+        self.lines()
+            .filter(|&line| line <= last_line)
+            .filter_map(|line| {
+                let line_end = line_end_char_index(&text, line);
+                let width = visual_coords_at_pos(text, line_end, tab_width).col;
+                (width < col).then(|| {
+                    let padding = Tendril::from(" ".repeat(col - width));
+                    (line_end, line_end, Some(padding))
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Rope, Transaction};
+
+    fn fragments(text: &Rope, selection: &Selection) -> Vec<String> {
+        selection
+            .fragments(text.slice(..))
+            .map(|fragment| fragment.into_owned())
+            .collect()
+    }
+
+    #[test]
+    fn block_to_selection() {
+        let text = Rope::from("hello world\nab\nfoo bar baz\n");
+        let block = BlockSelection::new(Position::new(0, 2), Position::new(2, 5));
+        let selection = block.to_selection(text.slice(..), 4, VirtualSpace::Skip);
+        assert_eq!(fragments(&text, &selection), ["llo ", "o ba"]);
+        assert_eq!(selection.primary_index(), 1);
+        assert_eq!(selection.primary().direction(), Direction::Forward);
+
+        let selection = block.to_selection(text.slice(..), 4, VirtualSpace::Pad);
+        assert_eq!(selection.ranges()[1], Range::point(14));
+        assert_eq!(fragments(&text, &selection), ["llo ", "", "o ba"]);
+        assert_eq!(selection.primary_index(), 2);
+    }
+
+    #[test]
+    fn block_direction_and_short_head() {
+        let text = Rope::from("hello world\nab\nfoo bar baz\n");
+        let block = BlockSelection::new(Position::new(2, 6), Position::new(1, 3));
+        let selection = block.to_selection(text.slice(..), 4, VirtualSpace::Skip);
+        // The head line is too short but still holds the primary cursor.
+        assert_eq!(selection.ranges(), [Range::point(14), Range::new(22, 18)]);
+        assert_eq!(selection.primary_index(), 0);
+    }
+
+    #[test]
+    fn block_with_tabs_and_wide_graphemes() {
+        let text = Rope::from("a\tb\n12345678\n日本語\n");
+        let block = BlockSelection::new(Position::new(0, 2), Position::new(2, 4));
+        let selection = block.to_selection(text.slice(..), 4, VirtualSpace::Skip);
+        assert_eq!(fragments(&text, &selection), ["\tb", "345", "本語"]);
+    }
+
+    #[test]
+    fn block_from_range() {
+        let text = Rope::from("\tfoo\nbar\n");
+        let block = BlockSelection::from_range(text.slice(..), Range::new(1, 3), 4);
+        assert_eq!(
+            block,
+            BlockSelection::new(Position::new(0, 4), Position::new(0, 5))
+        );
+        let block = BlockSelection::from_range(text.slice(..), Range::new(7, 2), 4);
+        assert_eq!(
+            block,
+            BlockSelection::new(Position::new(1, 1), Position::new(0, 5))
+        );
+    }
+
+    #[test]
+    fn block_padding() {
+        let mut text = Rope::from("hello\nab\n\tc\n");
+        let block = BlockSelection::new(Position::new(0, 3), Position::new(2, 4));
+        let changes = block.padding(text.slice(..), 4, 6);
+        let transaction = Transaction::change(&text, changes.into_iter());
+        assert!(transaction.apply(&mut text));
+        assert_eq!(text, "hello \nab    \n\tc \n");
+    }
+}
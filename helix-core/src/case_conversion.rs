@@ -2,6 +2,93 @@
 
 // todo: should this be grapheme aware?
 
+/// Splits `text` into the words of an identifier or phrase.
+///
+/// Words are separated by any character which is not alphanumeric, by a lowercase letter
+/// followed by an uppercase letter (`fooBar`) and before the last letter of an acronym which is
+/// followed by a lowercase letter (`HTTPServer`). Digits never start a new word: they belong to
+/// the word they follow, and an uppercase letter after them starts a new word like after an
+/// acronym (`SHA256Sum`) or a lowercase letter (`utf8Decoder`). A single lowercase letter
+/// followed by a digit after an acronym belongs to the acronym (`IPv6Address`).
+pub fn split_words(text: impl Iterator<Item = char>) -> Vec<String> {
+    let chars: Vec<char> = text.collect();
+    let mut words = Vec::new();
+    let mut word = String::new();
+    // Whether the last letter of the current word was lowercase, ignoring digits.
+    let mut after_lowercase = false;
+    for (i, &c) in chars.iter().enumerate() {
+        if !c.is_alphanumeric() {
+            if !word.is_empty() {
+                words.push(std::mem::take(&mut word));
+            }
+            after_lowercase = false;
+            continue;
+        }
+        if c.is_uppercase() && !word.is_empty() {
+            let prev = chars[i - 1];
+            let next_lowercase = chars.get(i + 1).is_some_and(|next| next.is_lowercase());
+            let version_suffix = prev.is_uppercase()
+                && next_lowercase
+                && chars.get(i + 2).is_some_and(|next| next.is_numeric());
+            if after_lowercase
+                || ((prev.is_uppercase() || prev.is_numeric()) && next_lowercase && !version_suffix)
+            {
+                words.push(std::mem::take(&mut word));
+            }
+        }
+        if c.is_lowercase() {
+            after_lowercase = true;
+        } else if c.is_uppercase() {
+            after_lowercase = false;
+        }
+        word.push(c);
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+#[derive(Debug, Clone, Copy)]
+enum WordCase {
+    Lower,
+    Upper,
+    Capitalized,
+}
+
+fn push_word(word: &str, case: WordCase, buf: &mut Tendril) {
+    match case {
+        WordCase::Lower => to_lower_case_with(word.chars(), buf),
+        WordCase::Upper => to_upper_case_with(word.chars(), buf),
+        WordCase::Capitalized => {
+            let mut chars = word.chars();
+            if let Some(first) = chars.next() {
+                buf.extend(first.to_uppercase());
+            }
+            to_lower_case_with(chars, buf);
+        }
+    }
+}
+
+/// Joins the words of `text` with `separator`, converting the first word to `first_case` and
+/// all other words to `case`.
+fn join_words_with(
+    text: impl Iterator<Item = char>,
+    buf: &mut Tendril,
+    separator: &str,
+    first_case: WordCase,
+    case: WordCase,
+) {
+    for (i, word) in split_words(text).iter().enumerate() {
+        if i == 0 {
+            push_word(word, first_case, buf);
+        } else {
+            buf.push_str(separator);
+            push_word(word, case, buf);
+        }
+    }
+}
+
 pub fn to_pascal_case(text: impl Iterator<Item = char>) -> Tendril {
     let mut res = Tendril::new();
     to_pascal_case_with(text, &mut res);
@@ -67,3 +154,121 @@ pub fn to_camel_case_with(mut text: impl Iterator<Item = char>, buf: &mut Tendri
         }
     }
 }
+
+/// Converts the words of `text`, as split by [`split_words`], to PascalCase. Unlike
+/// [`to_pascal_case_with`], which implements the `/pascalcase` snippet transform and keeps the
+/// rest of each word as written, the rest of each word is lowercased.
+pub fn to_pascal_case_words_with(text: impl Iterator<Item = char>, buf: &mut Tendril) {
+    join_words_with(text, buf, "", WordCase::Capitalized, WordCase::Capitalized)
+}
+
+/// Converts the words of `text`, as split by [`split_words`], to camelCase. Unlike
+/// [`to_camel_case_with`], which implements the `/camelcase` snippet transform, the rest of
+/// each word is lowercased.
+pub fn to_camel_case_words_with(text: impl Iterator<Item = char>, buf: &mut Tendril) {
+    join_words_with(text, buf, "", WordCase::Lower, WordCase::Capitalized)
+}
+
+pub fn to_snake_case_with(text: impl Iterator<Item = char>, buf: &mut Tendril) {
+    join_words_with(text, buf, "_", WordCase::Lower, WordCase::Lower)
+}
+
+pub fn to_screaming_snake_case_with(text: impl Iterator<Item = char>, buf: &mut Tendril) {
+    join_words_with(text, buf, "_", WordCase::Upper, WordCase::Upper)
+}
+
+pub fn to_kebab_case_with(text: impl Iterator<Item = char>, buf: &mut Tendril) {
+    join_words_with(text, buf, "-", WordCase::Lower, WordCase::Lower)
+}
+
+pub fn to_title_case_with(text: impl Iterator<Item = char>, buf: &mut Tendril) {
+    join_words_with(text, buf, " ", WordCase::Capitalized, WordCase::Capitalized)
+}
+
+pub fn to_dot_case_with(text: impl Iterator<Item = char>, buf: &mut Tendril) {
+    join_words_with(text, buf, ".", WordCase::Lower, WordCase::Lower)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn convert(text: &'static str, f: fn(std::str::Chars<'static>, &mut Tendril)) -> String {
+        let mut buf = Tendril::new();
+        f(text.chars(), &mut buf);
+        buf.to_string()
+    }
+
+    #[test]
+    fn word_splitting() {
+        let cases = [
+            ("foo_bar", &["foo", "bar"][..]),
+            ("fooBar", &["foo", "Bar"]),
+            ("FooBar", &["Foo", "Bar"]),
+            ("HTTPServer", &["HTTP", "Server"]),
+            ("parseHTTPResponse", &["parse", "HTTP", "Response"]),
+            ("IOError", &["IO", "Error"]),
+            ("utf8Decoder", &["utf8", "Decoder"]),
+            ("SHA256", &["SHA256"]),
+            ("SHA256Sum", &["SHA256", "Sum"]),
+            ("IPv6Address", &["IPv6", "Address"]),
+            ("parseIPv6Address", &["parse", "IPv6", "Address"]),
+            ("ID3v2Tag", &["ID3v2", "Tag"]),
+            ("sha256_sum", &["sha256", "sum"]),
+            ("  foo--bar.baz qux  ", &["foo", "bar", "baz", "qux"]),
+            ("", &[]),
+        ];
+        for (text, words) in cases {
+            assert_eq!(split_words(text.chars()), words, "{text:?}");
+        }
+    }
+
+    #[test]
+    fn conversions() {
+        let text = "parseHTTPResponse2";
+        assert_eq!(convert(text, to_snake_case_with), "parse_http_response2");
+        assert_eq!(
+            convert(text, to_screaming_snake_case_with),
+            "PARSE_HTTP_RESPONSE2"
+        );
+        assert_eq!(convert(text, to_kebab_case_with), "parse-http-response2");
+        assert_eq!(
+            convert(text, to_camel_case_words_with),
+            "parseHttpResponse2"
+        );
+        assert_eq!(
+            convert(text, to_pascal_case_words_with),
+            "ParseHttpResponse2"
+        );
+        assert_eq!(convert(text, to_title_case_with), "Parse Http Response2");
+        assert_eq!(convert(text, to_dot_case_with), "parse.http.response2");
+
+        // Converting between cases preserves the words.
+        assert_eq!(
+            convert("Parse Http Response2", to_camel_case_words_with),
+            "parseHttpResponse2"
+        );
+        assert_eq!(
+            convert("parseHttpResponse2", to_snake_case_with),
+            "parse_http_response2"
+        );
+        assert_eq!(
+            convert("PARSE_HTTP_RESPONSE2", to_camel_case_words_with),
+            "parseHttpResponse2"
+        );
+        assert_eq!(convert("IPv6Address", to_snake_case_with), "ipv6_address");
+        assert_eq!(convert("SHA256Sum", to_kebab_case_with), "sha256-sum");
+    }
+
+    #[test]
+    fn snippet_transforms_keep_words_as_written() {
+        assert_eq!(
+            convert("parse_HTTP_response", to_pascal_case_with),
+            "ParseHTTPResponse"
+        );
+        assert_eq!(
+            convert("parseHTTPResponse", to_pascal_case_with),
+            "ParseHTTPResponse"
+        );
+    }
+}
@@ -4,8 +4,11 @@
 use smallvec::SmallVec;
 
 use crate::{
-    syntax::config::BlockCommentToken, Change, Range, Rope, RopeSlice, Selection, Tendril,
-    Transaction,
+    syntax::{
+        config::{BlockCommentToken, LanguageConfiguration},
+        Loader,
+    },
+    Change, ChangeSet, Language, Range, Rope, RopeSlice, Selection, Syntax, Tendril, Transaction,
 };
 use helix_stdx::rope::RopeSliceExt;
 use std::borrow::Cow;
@@ -295,6 +298,105 @@ pub fn create_block_comment_transaction(
     (Transaction::change(doc, changes.into_iter()), ranges)
 }
 
+/// The comment tokens of a language.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CommentTokens<'a> {
+    /// The preferred line comment token.
+    pub line: Option<&'a str>,
+    pub block: Option<&'a [BlockCommentToken]>,
+}
+
+impl<'a> CommentTokens<'a> {
+    pub fn new(config: Option<&'a LanguageConfiguration>) -> Self {
+        Self {
+            line: config
+                .and_then(|config| config.comment_tokens.as_ref())
+                .and_then(|tokens| tokens.first())
+                .map(|token| token.as_str()),
+            block: config
+                .and_then(|config| config.block_comment_tokens.as_ref())
+                .map(|tokens| &tokens[..]),
+        }
+    }
+}
+
+/// Toggles comments with the comment tokens of the innermost language layer at each range of
+/// the selection, so that a `<style>` block in HTML is commented with CSS comments. Ranges in
+/// the same language are toggled together by `toggle`. `config` is the language of the document,
+/// which is used when there is no syntax tree.
+pub fn toggle_injected_comments(
+    doc: &Rope,
+    selection: &Selection,
+    syntax: Option<&Syntax>,
+    loader: &Loader,
+    config: Option<&LanguageConfiguration>,
+    toggle: impl Fn(CommentTokens, &Rope, &Selection) -> Transaction,
+) -> Transaction {
+    let Some(syntax) = syntax else {
+        return toggle(CommentTokens::new(config), doc, selection);
+    };
+
+    let text = doc.slice(..);
+    let mut groups: Vec<(Language, SmallVec<[Range; 1]>, usize)> = Vec::new();
+    for (i, &range) in selection.iter().enumerate() {
+        let start = text.char_to_byte(range.from()) as u32;
+        let end = text.char_to_byte(range.to()) as u32;
+        let layer = syntax
+            .layers_for_byte_range(start, end)
+            .last()
+            .unwrap_or_else(|| syntax.root_layer());
+        let language = syntax.layer(layer).language;
+        let group = match groups.iter().position(|(lang, _, _)| *lang == language) {
+            Some(group) => group,
+            None => {
+                groups.push((language, SmallVec::new(), 0));
+                groups.len() - 1
+            }
+        };
+        let (_, ranges, primary_index) = &mut groups[group];
+        if i == selection.primary_index() {
+            *primary_index = ranges.len();
+        }
+        ranges.push(range);
+    }
+
+    let groups = groups
+        .into_iter()
+        .map(|(language, ranges, primary_index)| {
+            (
+                CommentTokens::new(Some(loader.language(language).config())),
+                Selection::new(ranges, primary_index),
+            )
+        })
+        .collect();
+    toggle_comment_groups(doc, groups, toggle)
+}
+
+/// Toggles comments for each group of ranges with its comment tokens and combines the changes.
+///
+/// The groups are toggled one after another so that toggles on the same line, which may insert
+/// or remove text at the same place, are all applied. The ranges of each group are mapped
+/// through the changes of the groups before it.
+fn toggle_comment_groups(
+    doc: &Rope,
+    mut groups: Vec<(CommentTokens, Selection)>,
+    toggle: impl Fn(CommentTokens, &Rope, &Selection) -> Transaction,
+) -> Transaction {
+    if groups.len() == 1 {
+        let (tokens, selection) = groups.pop().unwrap();
+        return toggle(tokens, doc, &selection);
+    }
+
+    let mut text = doc.clone();
+    let mut changes = ChangeSet::new(doc.slice(..));
+    for (tokens, selection) in groups {
+        let transaction = toggle(tokens, &text, &selection.map(&changes));
+        transaction.apply(&mut text);
+        changes = changes.compose(transaction.changes().clone());
+    }
+    Transaction::from(changes)
+}
+
 #[must_use]
 pub fn toggle_block_comments(
     doc: &Rope,
@@ -484,4 +586,109 @@ fn test_use_longest_comment() {
             Some("///")
         );
     }
+
+    #[test]
+    fn test_toggle_injected_comments_without_syntax() {
+        let loader = crate::config::default_lang_loader();
+        let rust = loader.language_for_name("rust".to_string()).unwrap();
+        let config = loader.language(rust).config();
+        let doc = Rope::from("fn main() {}\n");
+        let selection = Selection::single(0, 1);
+
+        let mut text = doc.clone();
+        let transaction = toggle_injected_comments(
+            &doc,
+            &selection,
+            None,
+            &loader,
+            Some(config),
+            |tokens, doc, selection| toggle_line_comments(doc, selection, tokens.line),
+        );
+        transaction.apply(&mut text);
+        assert_eq!(text, "// fn main() {}\n");
+    }
+
+    fn toggle_line_or_block_comments(
+        tokens: CommentTokens,
+        doc: &Rope,
+        selection: &Selection,
+    ) -> Transaction {
+        match tokens.block {
+            Some(block) if tokens.line.is_none() => toggle_block_comments(doc, selection, block),
+            _ => toggle_line_comments(doc, selection, tokens.line),
+        }
+    }
+
+    #[test]
+    fn test_toggle_comment_groups_on_same_line() {
+        let html = [BlockCommentToken {
+            start: "<!--".to_string(),
+            end: "-->".to_string(),
+        }];
+        let groups = |html_range: Range, js_range: Range| {
+            vec![
+                (
+                    CommentTokens {
+                        line: None,
+                        block: Some(&html),
+                    },
+                    Selection::single(html_range.anchor, html_range.head),
+                ),
+                (
+                    CommentTokens {
+                        line: Some("//"),
+                        block: None,
+                    },
+                    Selection::single(js_range.anchor, js_range.head),
+                ),
+            ]
+        };
+
+        let mut doc = Rope::from("<p>x</p> let a;\n");
+        let transaction = toggle_comment_groups(
+            &doc,
+            groups(Range::new(0, 8), Range::new(9, 15)),
+            toggle_line_or_block_comments,
+        );
+        transaction.apply(&mut doc);
+        assert_eq!(doc, "// <!-- <p>x</p> --> let a;\n");
+
+        // Uncommenting removes the comment tokens of both languages from the line.
+        let transaction = toggle_comment_groups(
+            &doc,
+            groups(Range::new(3, 20), Range::new(21, 27)),
+            toggle_line_or_block_comments,
+        );
+        transaction.apply(&mut doc);
+        assert_eq!(doc, "<p>x</p> let a;\n");
+    }
+
+    #[test]
+    fn test_toggle_injected_comments() {
+        let loader = crate::config::default_lang_loader();
+        let html = loader.language_for_name("html".to_string()).unwrap();
+        let config = loader.language(html).config();
+        let doc = Rope::from("<p>x</p>\n<style>\na { color: red; }\n</style>\n");
+        let syntax = Syntax::new(doc.slice(..), html, &loader).unwrap();
+        // The paragraph and the CSS rule.
+        let selection = Selection::new(
+            SmallVec::from_slice(&[Range::new(0, 8), Range::new(17, 34)]),
+            0,
+        );
+
+        let mut text = doc.clone();
+        let transaction = toggle_injected_comments(
+            &doc,
+            &selection,
+            Some(&syntax),
+            &loader,
+            Some(config),
+            toggle_line_or_block_comments,
+        );
+        transaction.apply(&mut text);
+        assert_eq!(
+            text,
+            "<!-- <p>x</p> -->\n<style>\n/* a { color: red; } */\n</style>\n"
+        );
+    }
 }
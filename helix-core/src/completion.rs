@@ -1,6 +1,6 @@
 use std::borrow::Cow;
 
-use crate::{diagnostic::LanguageServerId, Transaction};
+use crate::{diagnostic::LanguageServerId, snippets::RenderedSnippet, Transaction};
 
 #[derive(Debug, PartialEq, Clone)]
 pub struct CompletionItem {
@@ -10,6 +10,9 @@ pub struct CompletionItem {
     /// Containing Markdown
     pub documentation: Option<String>,
     pub provider: CompletionProvider,
+    /// The snippet inserted by `transaction`, its tabstops become active when the item is
+    /// accepted.
+    pub snippet: Option<RenderedSnippet>,
 }
 
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
@@ -17,6 +20,7 @@ pub enum CompletionProvider {
     Lsp(LanguageServerId),
     Path,
     Word,
+    Snippet,
 }
 
 impl From<LanguageServerId> for CompletionProvider {
@@ -68,6 +68,8 @@ pub enum DiagnosticProvider {
         /// not clear the pull diagnostics and vice-versa.
         identifier: Option<Arc<str>>,
     },
+    /// Misspelled words found by the built-in spell checker.
+    Spelling,
     // Future internal features can go here...
 }
 
@@ -75,7 +77,7 @@ impl DiagnosticProvider {
     pub fn language_server_id(&self) -> Option<LanguageServerId> {
         match self {
             Self::Lsp { server_id, .. } => Some(*server_id),
-            // _ => None,
+            Self::Spelling => None,
         }
     }
 }
@@ -168,6 +168,18 @@ pub fn compare_ropes(before: &Rope, after: &Rope) -> Transaction {
     res
 }
 
+/// Compares `before` and `after` line by line. Returns the ranges of lines in `before` which were
+/// replaced by the corresponding ranges of lines in `after`.
+pub fn compare_lines(before: &Rope, after: &Rope) -> Vec<(Range<u32>, Range<u32>)> {
+    let file = InternedInput::new(RopeLines(before.slice(..)), RopeLines(after.slice(..)));
+    let mut diff = Diff::compute(Algorithm::Histogram, &file);
+    diff.postprocess_with_heuristic(
+        &file,
+        IndentHeuristic::new(|token| IndentLevel::for_ascii_line(file.interner[token].bytes(), 4)),
+    );
+    diff.hunks().map(|hunk| (hunk.before, hunk.after)).collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -208,4 +220,14 @@ fn new_file() {
     fn deleted_file() {
         test_identity("foo", "");
     }
+
+    #[test]
+    fn line_hunks() {
+        let before = Rope::from("a\nb\nc\nd\n");
+        let after = Rope::from("a\nx\ny\nc\n");
+        assert_eq!(
+            compare_lines(&before, &after),
+            vec![(1..2, 1..3), (3..4, 4..4)]
+        );
+    }
 }
@@ -1,8 +1,10 @@
+use crate::transaction::Operation;
 use crate::{Assoc, ChangeSet, Range, Rope, Selection, Transaction};
 use once_cell::sync::Lazy;
 use regex::Regex;
+use serde::{Deserialize, Serialize};
 use std::num::NonZeroUsize;
-use std::time::{Duration, Instant};
+use std::time::{Duration, Instant, SystemTime};
 
 #[derive(Debug, Clone)]
 pub struct State {
@@ -47,12 +49,23 @@ pub struct State {
 ///    delete, we also store an inversion of the transaction.
 ///
 /// Using time to navigate the history: <https://github.com/helix-editor/helix/pull/194>
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct History {
     revisions: Vec<Revision>,
     current: usize,
 }
 
+/// A read-only view of a revision, used to display the history. See [`History::revisions`].
+#[derive(Debug, Clone, Copy)]
+pub struct RevisionInfo<'a> {
+    /// The revision this revision was created from. The root revision is its own parent.
+    pub parent: usize,
+    /// The time at which the revision was committed.
+    pub timestamp: Instant,
+    /// The changes which lead from the parent revision to this revision.
+    pub transaction: &'a Transaction,
+}
+
 /// A single point in history. See [History] for more information.
 #[derive(Debug, Clone)]
 struct Revision {
@@ -209,12 +222,12 @@ fn path_up(&self, mut n: usize, a: usize) -> Vec<usize> {
         path
     }
 
-    /// Create a [`Transaction`] that will jump to a specific revision in the history.
-    fn jump_to(&mut self, to: usize) -> Vec<Transaction> {
+    /// Returns the [`Transaction`]s that lead from the current revision to the revision `to`
+    /// without changing the current revision.
+    pub fn changes_to(&self, to: usize) -> Vec<Transaction> {
         let lca = self.lowest_common_ancestor(self.current, to);
         let up = self.path_up(self.current, lca);
         let down = self.path_up(to, lca);
-        self.current = to;
         let up_txns = up.iter().map(|&n| self.revisions[n].inversion.clone());
         let down_txns = down
             .iter()
@@ -223,6 +236,29 @@ fn jump_to(&mut self, to: usize) -> Vec<Transaction> {
         up_txns.chain(down_txns).collect()
     }
 
+    /// Create a [`Transaction`] that will jump to a specific revision in the history.
+    fn jump_to(&mut self, to: usize) -> Vec<Transaction> {
+        let txns = self.changes_to(to);
+        self.current = to;
+        txns
+    }
+
+    /// Creates the [`Transaction`]s that will jump to the revision `to`, which may be on a
+    /// different branch of the history.
+    pub fn jump_to_revision(&mut self, to: usize) -> Vec<Transaction> {
+        self.jump_to(to.min(self.revisions.len() - 1))
+    }
+
+    /// Iterates over all revisions of the history, starting with the empty root revision. A
+    /// revision's position in the iterator is its revision number.
+    pub fn revisions(&self) -> impl ExactSizeIterator<Item = RevisionInfo<'_>> {
+        self.revisions.iter().map(|revision| RevisionInfo {
+            parent: revision.parent,
+            timestamp: revision.timestamp,
+            transaction: &revision.transaction,
+        })
+    }
+
     /// Creates a [`Transaction`] that will undo `delta` revisions.
     fn jump_backward(&mut self, delta: usize) -> Vec<Transaction> {
         self.jump_to(self.current.saturating_sub(delta))
@@ -302,6 +338,247 @@ pub fn later(&mut self, uk: UndoKind) -> Vec<Transaction> {
     }
 }
 
+/// A serializable form of a [History], used to persist the undo history of a document across
+/// editor restarts.
+///
+/// Timestamps are stored as wall clock time since [Instant]s can't be compared across processes.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SerializedHistory {
+    current: usize,
+    revisions: Vec<SerializedRevision>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SerializedRevision {
+    parent: usize,
+    last_child: Option<NonZeroUsize>,
+    transaction: SerializedTransaction,
+    inversion: SerializedTransaction,
+    /// Milliseconds since the unix epoch.
+    timestamp: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SerializedTransaction {
+    len: usize,
+    changes: Vec<SerializedOperation>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    selection: Option<SerializedSelection>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum SerializedOperation {
+    Retain(usize),
+    Delete(usize),
+    Insert(String),
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SerializedSelection {
+    /// `(anchor, head)` pairs.
+    ranges: Vec<(usize, usize)>,
+    primary: usize,
+}
+
+impl SerializedTransaction {
+    fn new(transaction: &Transaction) -> Self {
+        let changes = transaction
+            .changes()
+            .changes()
+            .iter()
+            .map(|operation| match operation {
+                Operation::Retain(n) => SerializedOperation::Retain(*n),
+                Operation::Delete(n) => SerializedOperation::Delete(*n),
+                Operation::Insert(fragment) => SerializedOperation::Insert(fragment.to_string()),
+            })
+            .collect();
+        let selection = transaction
+            .selection()
+            .map(|selection| SerializedSelection {
+                ranges: selection
+                    .ranges()
+                    .iter()
+                    .map(|range| (range.anchor, range.head))
+                    .collect(),
+                primary: selection.primary_index(),
+            });
+        Self {
+            len: transaction.changes().len(),
+            changes,
+            selection,
+        }
+    }
+
+    fn into_transaction(self) -> Result<Transaction, String> {
+        let operations = self
+            .changes
+            .into_iter()
+            .map(|operation| match operation {
+                SerializedOperation::Retain(n) => Operation::Retain(n),
+                SerializedOperation::Delete(n) => Operation::Delete(n),
+                SerializedOperation::Insert(fragment) => Operation::Insert(fragment.into()),
+            })
+            .collect();
+        let transaction = Transaction::from(ChangeSet::from_operations(operations, self.len));
+        if transaction.changes().len() != self.len {
+            return Err("transaction length does not match its changes".to_string());
+        }
+        match self.selection {
+            Some(selection) => {
+                if selection.primary >= selection.ranges.len() {
+                    return Err("invalid selection".to_string());
+                }
+                let ranges = selection
+                    .ranges
+                    .into_iter()
+                    .map(|(anchor, head)| Range::new(anchor, head))
+                    .collect();
+                Ok(transaction.with_selection(Selection::new(ranges, selection.primary)))
+            }
+            None => Ok(transaction),
+        }
+    }
+}
+
+impl History {
+    /// Converts the history into its serializable form. `current` is stored as the current
+    /// revision, which allows persisting the history as of a revision that was written to disk
+    /// while the document kept changing.
+    pub fn serialize(&self, current: usize) -> SerializedHistory {
+        let now = Instant::now();
+        let system_now = SystemTime::now();
+        let revisions = self
+            .revisions
+            .iter()
+            .map(|revision| {
+                let time = system_now - now.saturating_duration_since(revision.timestamp);
+                let timestamp = time
+                    .duration_since(SystemTime::UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_millis() as u64;
+                SerializedRevision {
+                    parent: revision.parent,
+                    last_child: revision.last_child,
+                    transaction: SerializedTransaction::new(&revision.transaction),
+                    inversion: SerializedTransaction::new(&revision.inversion),
+                    timestamp,
+                }
+            })
+            .collect();
+        SerializedHistory {
+            current: current.min(self.revisions.len() - 1),
+            revisions,
+        }
+    }
+}
+
+impl TryFrom<SerializedHistory> for History {
+    type Error = String;
+
+    fn try_from(history: SerializedHistory) -> Result<Self, Self::Error> {
+        let len = history.revisions.len();
+        if len == 0 || history.current >= len {
+            return Err("invalid current revision".to_string());
+        }
+
+        let now = Instant::now();
+        let system_now = SystemTime::now();
+        // Revisions which are older than the earliest representable `Instant` (for example older
+        // than the system uptime) are clamped to the timestamp of the following revision. Walking
+        // the revisions backwards keeps the timestamps sorted which `jump_instant` relies on.
+        let mut next_timestamp = now;
+        let mut revisions = Vec::with_capacity(len);
+        for (i, revision) in history.revisions.into_iter().enumerate().rev() {
+            let in_bounds = |index: usize| index < len;
+            if !in_bounds(revision.parent)
+                || (i != 0 && revision.parent >= i)
+                || revision
+                    .last_child
+                    .is_some_and(|child| !in_bounds(child.get()))
+            {
+                return Err("invalid revision tree".to_string());
+            }
+            let time = SystemTime::UNIX_EPOCH + Duration::from_millis(revision.timestamp);
+            let age = system_now.duration_since(time).unwrap_or_default();
+            let timestamp = now
+                .checked_sub(age)
+                .unwrap_or(next_timestamp)
+                .min(next_timestamp);
+            next_timestamp = timestamp;
+            revisions.push(Revision {
+                parent: revision.parent,
+                last_child: revision.last_child,
+                transaction: revision.transaction.into_transaction()?,
+                inversion: revision.inversion.into_transaction()?,
+                timestamp,
+            });
+        }
+        revisions.reverse();
+
+        // The last child of a revision must be a later revision which has it as its parent.
+        for (i, revision) in revisions.iter().enumerate() {
+            if revision
+                .last_child
+                .is_some_and(|child| child.get() <= i || revisions[child.get()].parent != i)
+            {
+                return Err("invalid revision tree".to_string());
+            }
+        }
+        if revisions[0].parent != 0 {
+            return Err("invalid root revision".to_string());
+        }
+
+        Ok(Self {
+            revisions,
+            current: history.current,
+        })
+    }
+}
+
+impl History {
+    /// Checks that the transactions of all revisions fit the lengths of the texts they apply to,
+    /// given that `text` is the text at the current revision, so that moving through the history
+    /// never applies a transaction to a text it wasn't made for.
+    pub fn validate(&self, text: &Rope) -> Result<(), String> {
+        let fits = |transaction: &Transaction, len: usize, len_after: usize| {
+            let changes = transaction.changes();
+            changes.len() == len
+                && changes.len_after() == len_after
+                && transaction.selection().is_none_or(|selection| {
+                    selection
+                        .ranges()
+                        .iter()
+                        .all(|range| range.anchor.max(range.head) <= len_after)
+                })
+        };
+
+        // The lengths of the texts of the revisions from the root to the current revision follow
+        // from the inversions, the lengths of the other revisions from their transactions.
+        let mut lens = vec![None; self.revisions.len()];
+        lens[self.current] = Some(text.len_chars());
+        let mut n = self.current;
+        while n != 0 {
+            let revision = &self.revisions[n];
+            lens[revision.parent] = Some(revision.inversion.changes().len_after());
+            n = revision.parent;
+        }
+
+        for (i, revision) in self.revisions.iter().enumerate().skip(1) {
+            // Parents precede their children, so the length of the parent is known.
+            let parent_len = lens[revision.parent].ok_or("invalid revision tree")?;
+            let len = *lens[i].get_or_insert(revision.transaction.changes().len_after());
+            if !fits(&revision.transaction, parent_len, len)
+                || !fits(&revision.inversion, len, parent_len)
+            {
+                return Err(format!("revision {i} does not apply to the text"));
+            }
+        }
+
+        Ok(())
+    }
+}
+
 /// Whether to undo by a number of edits or a duration of time.
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum UndoKind {
@@ -630,4 +907,140 @@ fn test_parse_undo_kind() {
             Err("duration too large".to_string())
         );
     }
+
+    #[test]
+    fn test_serialize_round_trip() {
+        let mut history = History::default();
+        let mut state = State {
+            doc: Rope::from("hello"),
+            selection: Selection::point(0),
+        };
+        for text in [" world", "!"] {
+            let len = state.doc.len_chars();
+            let transaction =
+                Transaction::change(&state.doc, [(len, len, Some(text.into()))].into_iter());
+            history.commit_revision(&transaction, &state);
+            transaction.apply(&mut state.doc);
+        }
+        assert_eq!("hello world!", state.doc);
+
+        // Persist the history as of the first revision, as if it was written to disk before the
+        // second edit was made.
+        let serialized = serde_json::to_string(&history.serialize(1)).unwrap();
+        let serialized: SerializedHistory = serde_json::from_str(&serialized).unwrap();
+        let mut restored = History::try_from(serialized).unwrap();
+        assert_eq!(restored.current_revision(), 1);
+
+        let mut doc = Rope::from("hello world");
+        restored.redo().unwrap().apply(&mut doc);
+        assert_eq!("hello world!", doc);
+        for transaction in restored.earlier(UndoKind::Steps(2)) {
+            transaction.apply(&mut doc);
+        }
+        assert_eq!("hello", doc);
+        assert!(restored.at_root());
+    }
+
+    #[test]
+    fn test_deserialize_invalid_history() {
+        let mut history = History::default();
+        let mut state = State {
+            doc: Rope::from("hello"),
+            selection: Selection::point(0),
+        };
+        for text in [" world", "!"] {
+            let len = state.doc.len_chars();
+            let transaction =
+                Transaction::change(&state.doc, [(len, len, Some(text.into()))].into_iter());
+            history.commit_revision(&transaction, &state);
+            transaction.apply(&mut state.doc);
+        }
+        let serialized = serde_json::to_value(history.serialize(2)).unwrap();
+        let restore = |edit: &dyn Fn(&mut serde_json::Value), text: &str| {
+            let mut serialized = serialized.clone();
+            edit(&mut serialized);
+            History::try_from(serde_json::from_value::<SerializedHistory>(serialized).unwrap())
+                .and_then(|history| history.validate(&Rope::from(text)))
+        };
+
+        assert_eq!(restore(&|_| (), "hello world!"), Ok(()));
+        // The text changed since the history was written.
+        assert!(restore(&|_| (), "hello").is_err());
+        // A revision is the last child of a revision which isn't its parent.
+        assert!(restore(
+            &|history| history["revisions"][0]["last_child"] = 2.into(),
+            "hello world!"
+        )
+        .is_err());
+        // A revision comes before its parent.
+        assert!(restore(
+            &|history| history["revisions"][1]["parent"] = 2.into(),
+            "hello world!"
+        )
+        .is_err());
+        // A transaction doesn't fit the text of its parent.
+        assert!(restore(
+            &|history| history["revisions"][2]["transaction"]["changes"][1]["insert"] = "!!".into(),
+            "hello world!"
+        )
+        .is_err());
+        // A selection is out of bounds of the text.
+        assert!(restore(
+            &|history| history["revisions"][1]["inversion"]["selection"] =
+                serde_json::json!({ "ranges": [[0, 50]], "primary": 0 }),
+            "hello world!"
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_jump_to_revision() {
+        let mut history = History::default();
+        let mut state = State {
+            doc: Rope::from("a"),
+            selection: Selection::point(0),
+        };
+        let mut commit = |history: &mut History, text: &str| {
+            let len = state.doc.len_chars();
+            let transaction =
+                Transaction::change(&state.doc, [(len, len, Some(text.into()))].into_iter());
+            history.commit_revision(&transaction, &state);
+            transaction.apply(&mut state.doc);
+            state.doc.clone()
+        };
+        commit(&mut history, "b");
+        let mut doc = commit(&mut history, "c");
+        history.undo().unwrap().apply(&mut doc);
+        // Revision 3 branches off revision 1.
+        let transaction = Transaction::change(&doc, [(2, 2, Some("d".into()))].into_iter());
+        history.commit_revision(
+            &transaction,
+            &State {
+                doc: doc.clone(),
+                selection: Selection::point(0),
+            },
+        );
+        transaction.apply(&mut doc);
+        assert_eq!("abd", doc);
+
+        // Previewing a revision does not move through the history.
+        let mut preview = doc.clone();
+        for transaction in history.changes_to(2) {
+            transaction.apply(&mut preview);
+        }
+        assert_eq!("abc", preview);
+        assert_eq!(history.current_revision(), 3);
+
+        for transaction in history.jump_to_revision(2) {
+            transaction.apply(&mut doc);
+        }
+        assert_eq!("abc", doc);
+        assert_eq!(history.current_revision(), 2);
+
+        for transaction in history.jump_to_revision(0) {
+            transaction.apply(&mut doc);
+        }
+        assert_eq!("a", doc);
+        assert!(history.at_root());
+    }
 }
@@ -0,0 +1,67 @@
+/// Increment a channel of a hex color like `#ff8800`.
+///
+/// The short (`#rgb`, `#rgba`) and long (`#rrggbb`, `#rrggbbaa`) forms are supported. The
+/// channel under the cursor is incremented, a cursor on the `#` selects the first channel.
+/// Channels saturate at their minimum and maximum value and keep the case of the original digits.
+pub fn increment(selected_text: &str, cursor: usize, amount: i64) -> Option<String> {
+    let digits = selected_text.strip_prefix('#')?;
+    if !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let width = match digits.len() {
+        3 | 4 => 1,
+        6 | 8 => 2,
+        _ => return None,
+    };
+    let channels = digits.len() / width;
+    let channel = (cursor.saturating_sub(1) / width).min(channels - 1);
+    let max = if width == 1 { 0xf } else { 0xff };
+
+    let range = channel * width..(channel + 1) * width;
+    let value = i64::from_str_radix(&digits[range.clone()], 16).ok()?;
+    let new_value = value.saturating_add(amount).clamp(0, max);
+    let new_digits = if digits.chars().any(|c| c.is_ascii_uppercase()) {
+        format!("{new_value:0width$X}")
+    } else {
+        format!("{new_value:0width$x}")
+    };
+
+    let mut new_text = selected_text.to_owned();
+    new_text.replace_range(range.start + 1..range.end + 1, &new_digits);
+    Some(new_text)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_increment_hex_colors() {
+        let tests = [
+            ("#ff8800", 0, 1, "#ff8800"),
+            ("#ff8800", 3, 1, "#ff8900"),
+            ("#ff8800", 6, -1, "#ff8800"),
+            ("#ff8801", 6, -1, "#ff8800"),
+            ("#FF8800", 5, 0x10, "#FF8810"),
+            ("#0a0b0c", 2, -20, "#000b0c"),
+            ("#abc", 2, 1, "#acc"),
+            ("#abc", 3, 10, "#abf"),
+            ("#11223344", 8, 1, "#11223345"),
+        ];
+
+        for (original, cursor, amount, expected) in tests {
+            assert_eq!(
+                increment(original, cursor, amount).unwrap(),
+                expected,
+                "{original} at {cursor}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_invalid_hex_colors() {
+        for invalid in ["ff8800", "#ff880", "#ggg", "#", "#ff88001122"] {
+            assert_eq!(increment(invalid, 0, 1), None, "{invalid}");
+        }
+    }
+}
@@ -1,10 +1,79 @@
 mod date_time;
+mod hex_color;
 mod integer;
+mod ordinal;
+mod version;
+mod word;
 
-pub fn integer(selected_text: &str, amount: i64) -> Option<String> {
+/// Increments the selected text by `amount`, returning `None` if the text is not understood by
+/// the incrementor. `cursor` is the char index of the cursor within the selected text and is used
+/// by incrementors which change only a part of the text, like a single component of a version.
+pub type Incrementor = fn(selected_text: &str, cursor: usize, amount: i64) -> Option<String>;
+
+/// The built-in incrementors in the order they are tried. [`letter`] isn't included since it
+/// would change the letter under any cursor, it's enabled with `editor.increment-letters`.
+pub const INCREMENTORS: &[Incrementor] = &[integer, date_time, version, hex_color, ordinal];
+
+/// Increments the selected text by `amount` using the first matching word group or incrementor.
+///
+/// Word groups are configured by the user and take precedence over the built-in incrementors.
+pub fn increment(
+    selected_text: &str,
+    cursor: usize,
+    amount: i64,
+    word_groups: &[Vec<String>],
+) -> Option<String> {
+    word::cycle(selected_text, amount, word_groups).or_else(|| {
+        INCREMENTORS
+            .iter()
+            .find_map(|incrementor| incrementor(selected_text, cursor, amount))
+    })
+}
+
+pub fn integer(selected_text: &str, _cursor: usize, amount: i64) -> Option<String> {
     integer::increment(selected_text, amount)
 }
 
-pub fn date_time(selected_text: &str, amount: i64) -> Option<String> {
+pub fn date_time(selected_text: &str, _cursor: usize, amount: i64) -> Option<String> {
     date_time::increment(selected_text, amount)
 }
+
+pub fn version(selected_text: &str, cursor: usize, amount: i64) -> Option<String> {
+    version::increment(selected_text, cursor, amount)
+}
+
+pub fn hex_color(selected_text: &str, cursor: usize, amount: i64) -> Option<String> {
+    hex_color::increment(selected_text, cursor, amount)
+}
+
+pub fn ordinal(selected_text: &str, _cursor: usize, amount: i64) -> Option<String> {
+    ordinal::increment(selected_text, amount)
+}
+
+pub fn letter(selected_text: &str, _cursor: usize, amount: i64) -> Option<String> {
+    word::letter(selected_text, amount)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_decimals_are_not_incremented() {
+        for decimal in ["3.14", "0.5", "-2.75"] {
+            for cursor in 0..decimal.len() {
+                assert_eq!(
+                    increment(decimal, cursor, 1, &[]),
+                    None,
+                    "{decimal} at {cursor}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_letters_are_not_incremented() {
+        assert_eq!(increment("e", 0, 1, &[]), None);
+        assert_eq!(letter("e", 0, 1).as_deref(), Some("f"));
+    }
+}
@@ -0,0 +1,82 @@
+const ORDINAL_WORDS: [&str; 12] = [
+    "first", "second", "third", "fourth", "fifth", "sixth", "seventh", "eighth", "ninth", "tenth",
+    "eleventh", "twelfth",
+];
+
+/// Increment an ordinal number like `1st` or `22nd`, or an ordinal word from `first` to
+/// `twelfth`.
+///
+/// Numeric ordinals saturate at `0th`. Ordinal words keep the capitalization of the original
+/// word and are not incremented beyond `first` and `twelfth`.
+pub fn increment(selected_text: &str, amount: i64) -> Option<String> {
+    numeric(selected_text, amount).or_else(|| word(selected_text, amount))
+}
+
+fn numeric(selected_text: &str, amount: i64) -> Option<String> {
+    let split = selected_text.find(|c: char| !c.is_ascii_digit())?;
+    let (number, suffix) = selected_text.split_at(split);
+    let value: u64 = number.parse().ok()?;
+    if !suffix.eq_ignore_ascii_case(ordinal_suffix(value)) {
+        return None;
+    }
+    let new_value = value.saturating_add_signed(amount);
+    let new_suffix = ordinal_suffix(new_value);
+    if suffix.chars().all(|c| c.is_ascii_uppercase()) {
+        Some(format!("{new_value}{}", new_suffix.to_ascii_uppercase()))
+    } else {
+        Some(format!("{new_value}{new_suffix}"))
+    }
+}
+
+fn ordinal_suffix(value: u64) -> &'static str {
+    match (value % 10, value % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    }
+}
+
+fn word(selected_text: &str, amount: i64) -> Option<String> {
+    let lowercase = selected_text.to_lowercase();
+    let index = ORDINAL_WORDS.iter().position(|word| *word == lowercase)?;
+    let new_index = usize::try_from(index as i64 + amount).ok()?;
+    let new_word = ORDINAL_WORDS.get(new_index)?;
+    Some(super::word::match_case(selected_text, new_word))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_increment_ordinals() {
+        let tests = [
+            ("1st", 1, "2nd"),
+            ("2nd", 1, "3rd"),
+            ("3rd", 1, "4th"),
+            ("10th", 1, "11th"),
+            ("11th", 1, "12th"),
+            ("12th", 1, "13th"),
+            ("20th", 1, "21st"),
+            ("111th", 1, "112th"),
+            ("1ST", 1, "2ND"),
+            ("1st", -5, "0th"),
+            ("first", 1, "second"),
+            ("Third", -1, "Second"),
+            ("TENTH", 2, "TWELFTH"),
+        ];
+
+        for (original, amount, expected) in tests {
+            assert_eq!(increment(original, amount).unwrap(), expected, "{original}");
+        }
+    }
+
+    #[test]
+    fn test_invalid_ordinals() {
+        for (invalid, amount) in [("1nd", 1), ("st", 1), ("first", -1), ("twelfth", 1)] {
+            assert_eq!(increment(invalid, amount), None, "{invalid}");
+        }
+    }
+}
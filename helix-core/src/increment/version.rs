@@ -0,0 +1,77 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+static VERSION: Lazy<Regex> = Lazy::new(|| Regex::new(r"^(v?)(\d+)\.(\d+)(?:\.(\d+))?$").unwrap());
+
+/// Increment a version like `1.2.3` or `v1.2`.
+///
+/// Versions need three components or a `v` prefix, so decimal numbers like `3.14` aren't
+/// mistaken for versions.
+///
+/// The component under the cursor is incremented and all following components are reset to
+/// zero, so incrementing the minor version of `1.2.3` results in `1.3.0`. A cursor on the `v`
+/// prefix selects the major version and a cursor on a `.` selects the component before it.
+/// Components saturate at zero.
+pub fn increment(selected_text: &str, cursor: usize, amount: i64) -> Option<String> {
+    let captures = VERSION.captures(selected_text)?;
+    let prefix = captures.get(1).map_or("", |prefix| prefix.as_str());
+    if prefix.is_empty() && captures.get(4).is_none() {
+        return None;
+    }
+    let mut components = captures
+        .iter()
+        .skip(2)
+        .flatten()
+        .map(|component| component.as_str().parse::<u64>().ok())
+        .collect::<Option<Vec<_>>>()?;
+
+    // The text is ASCII so char and byte indices are the same.
+    let index = selected_text[..cursor.min(selected_text.len())]
+        .matches('.')
+        .count();
+    let component = &mut components[index];
+    *component = component.saturating_add_signed(amount);
+    for component in &mut components[index + 1..] {
+        *component = 0;
+    }
+
+    let components: Vec<_> = components.iter().map(u64::to_string).collect();
+    Some(format!("{prefix}{}", components.join(".")))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_increment_versions() {
+        let tests = [
+            ("1.2.3", 4, 1, "1.2.4"),
+            ("1.2.3", 2, 1, "1.3.0"),
+            ("1.2.3", 0, 1, "2.0.0"),
+            ("1.2.3", 1, 1, "2.0.0"),
+            ("1.2.3", 3, 1, "1.3.0"),
+            ("1.2.3", 4, -5, "1.2.0"),
+            ("v0.9", 0, 1, "v1.0"),
+            ("v0.9", 3, 1, "v0.10"),
+            ("10.20.30", 7, 10, "10.20.40"),
+        ];
+
+        for (original, cursor, amount, expected) in tests {
+            assert_eq!(
+                increment(original, cursor, amount).unwrap(),
+                expected,
+                "{original} at {cursor}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_invalid_versions() {
+        for invalid in [
+            "1", "1.2", "3.14", "1.2.3.4", "1.2.", "a.b.c", "1.2-beta", "",
+        ] {
+            assert_eq!(increment(invalid, 0, 1), None, "{invalid}");
+        }
+    }
+}
@@ -0,0 +1,108 @@
+/// Cycle through a group of words like `true` and `false`.
+///
+/// The selected text is looked up in `word_groups` and replaced by the word `amount` places
+/// after it, wrapping around at the end of the group. Words which only match ignoring case are
+/// replaced by a word with the same capitalization, so `True` is followed by `False`.
+pub fn cycle(selected_text: &str, amount: i64, word_groups: &[Vec<String>]) -> Option<String> {
+    let find = |matches: &dyn Fn(&str) -> bool| {
+        word_groups.iter().find_map(|group| {
+            let index = group.iter().position(|word| matches(word))?;
+            let new_index = (index as i64 + amount).rem_euclid(group.len() as i64);
+            Some(group[new_index as usize].as_str())
+        })
+    };
+
+    if let Some(word) = find(&|word| word == selected_text) {
+        return Some(word.to_owned());
+    }
+    let lowercase = selected_text.to_lowercase();
+    find(&|word| word.to_lowercase() == lowercase).map(|word| match_case(selected_text, word))
+}
+
+/// Applies the capitalization of `original` to `word`: all uppercase, all lowercase or
+/// capitalized. Words with any other capitalization are returned unchanged.
+pub(super) fn match_case(original: &str, word: &str) -> String {
+    let letters: Vec<char> = original.chars().filter(|c| c.is_alphabetic()).collect();
+    match letters.as_slice() {
+        [] => word.to_owned(),
+        [_, _, ..] if letters.iter().all(|c| c.is_uppercase()) => word.to_uppercase(),
+        _ if letters.iter().all(|c| c.is_lowercase()) => word.to_lowercase(),
+        [first, ..] if first.is_uppercase() => {
+            let mut chars = word.chars();
+            chars
+                .next()
+                .map(|first| first.to_uppercase().chain(chars).collect())
+                .unwrap_or_default()
+        }
+        _ => word.to_owned(),
+    }
+}
+
+/// Increment a single letter in the sequence `a` to `z` or `A` to `Z`.
+///
+/// Letters are not incremented beyond the start or end of the alphabet.
+pub fn letter(selected_text: &str, amount: i64) -> Option<String> {
+    let mut chars = selected_text.chars();
+    let (Some(c), None) = (chars.next(), chars.next()) else {
+        return None;
+    };
+    let start = match c {
+        'a'..='z' => b'a',
+        'A'..='Z' => b'A',
+        _ => return None,
+    };
+    let index = (c as u8 - start) as i64 + amount;
+    (0..26)
+        .contains(&index)
+        .then(|| ((start + index as u8) as char).to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_cycle_word_groups() {
+        let groups = vec![
+            vec!["true".to_owned(), "false".to_owned()],
+            vec![
+                "pub".to_owned(),
+                "pub(crate)".to_owned(),
+                "pub(super)".to_owned(),
+            ],
+            vec!["None".to_owned(), "Some".to_owned()],
+        ];
+        let tests = [
+            ("true", 1, "false"),
+            ("false", 1, "true"),
+            ("true", 3, "false"),
+            ("True", 1, "False"),
+            ("TRUE", -1, "FALSE"),
+            ("pub", -1, "pub(super)"),
+            ("pub(crate)", 1, "pub(super)"),
+            ("None", 1, "Some"),
+            ("none", 1, "some"),
+        ];
+
+        for (original, amount, expected) in tests {
+            assert_eq!(
+                cycle(original, amount, &groups).unwrap(),
+                expected,
+                "{original}"
+            );
+        }
+        assert_eq!(cycle("maybe", 1, &groups), None);
+    }
+
+    #[test]
+    fn test_increment_letters() {
+        let tests = [("a", 1, "b"), ("a", 25, "z"), ("X", 2, "Z"), ("c", -2, "a")];
+
+        for (original, amount, expected) in tests {
+            assert_eq!(letter(original, amount).unwrap(), expected, "{original}");
+        }
+        for (invalid, amount) in [("z", 1), ("A", -1), ("ab", 1), ("1", 1), ("é", 1)] {
+            assert_eq!(letter(invalid, amount), None, "{invalid}");
+        }
+    }
+}
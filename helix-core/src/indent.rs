@@ -6,6 +6,7 @@
 use crate::{
     chars::{char_is_line_ending, char_is_whitespace},
     graphemes::{grapheme_width, tab_width_at},
+    line_ending::line_end_char_index,
     syntax::{self, config::IndentationHeuristic},
     tree_sitter::{
         self,
@@ -1086,6 +1087,36 @@ pub fn indent_for_newline(
     indent_style.as_str().repeat(indent_level)
 }
 
+/// Returns the indentation for lines which are moved below `line_before`: the indentation of a
+/// new line opened below it. Returns `None` if the indentation can't be computed with
+/// tree-sitter, in which case moved lines should keep their indentation.
+pub fn indent_for_moved_lines(
+    loader: &syntax::Loader,
+    syntax: Option<&Syntax>,
+    indent_heuristic: &IndentationHeuristic,
+    indent_style: &IndentStyle,
+    tab_width: usize,
+    text: RopeSlice,
+    line_before: usize,
+) -> Option<String> {
+    if *indent_heuristic == IndentationHeuristic::Simple {
+        return None;
+    }
+    let syntax = syntax?;
+    loader.indent_query(syntax.root_language())?;
+    Some(indent_for_newline(
+        loader,
+        Some(syntax),
+        indent_heuristic,
+        indent_style,
+        tab_width,
+        text,
+        line_before,
+        line_end_char_index(&text, line_before),
+        line_before,
+    ))
+}
+
 pub fn get_scopes<'a>(syntax: Option<&'a Syntax>, text: RopeSlice, pos: usize) -> Vec<&'a str> {
     let mut scopes = Vec::new();
     if let Some(syntax) = syntax {
@@ -1,6 +1,8 @@
 pub use encoding_rs as encoding;
 
+pub mod align;
 pub mod auto_pairs;
+pub mod block_selection;
 pub mod case_conversion;
 pub mod chars;
 pub mod command_line;
@@ -25,6 +27,8 @@
 pub mod search;
 pub mod selection;
 pub mod snippets;
+pub mod sort;
+pub mod spelling;
 pub mod surround;
 pub mod syntax;
 pub mod test;
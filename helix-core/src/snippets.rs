@@ -1,5 +1,6 @@
 mod active;
 mod elaborate;
+pub mod library;
 mod parser;
 mod render;
 
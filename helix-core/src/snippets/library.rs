@@ -0,0 +1,268 @@
+//! User-defined snippets.
+//!
+//! Snippets are loaded from `snippets/<language>.toml` and `snippets/<language>.json` files.
+//! Snippets in `global.toml` and `global.json` are available in every language. Both formats
+//! map the name of a snippet to its definition, which follows the format of VS Code snippet
+//! files:
+//!
+//! ```toml
+//! [function]
+//! prefix = "fn"
+//! description = "A function"
+//! body = ["fn ${1:name}($2) {", "\t$0", "}"]
+//! ```
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use helix_stdx::json::strip_jsonc;
+use serde::Deserialize;
+
+use crate::snippets::Snippet;
+
+/// The name of the snippet files whose snippets are available in every language.
+pub const GLOBAL_SNIPPETS: &str = "global";
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum OneOrMany {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl OneOrMany {
+    fn into_vec(self) -> Vec<String> {
+        match self {
+            Self::One(value) => vec![value],
+            Self::Many(values) => values,
+        }
+    }
+}
+
+/// Other fields of VS Code snippets like `scope`, `isFileTemplate`, `include` and `exclude` are
+/// ignored: global snippets are available in every language.
+#[derive(Debug, Deserialize)]
+struct SnippetDefinition {
+    prefix: Option<OneOrMany>,
+    /// A string or a list of lines.
+    body: OneOrMany,
+    description: Option<OneOrMany>,
+}
+
+/// A snippet defined by the user.
+#[derive(Debug)]
+pub struct UserSnippet {
+    pub name: String,
+    /// The words which expand to the snippet when completed. Snippets without a prefix can only
+    /// be inserted with the snippet picker.
+    pub prefixes: Vec<String>,
+    pub description: Option<String>,
+    /// The source of the snippet.
+    pub body: String,
+    pub snippet: Snippet,
+}
+
+/// Parses the snippets in the contents of a snippet file. `json` selects the VS Code JSON
+/// format, which may contain comments, otherwise the contents are parsed as TOML. Snippets with
+/// an invalid body are skipped.
+pub fn parse_snippets(contents: &str, json: bool) -> Result<Vec<UserSnippet>> {
+    let definitions: BTreeMap<String, SnippetDefinition> = if json {
+        serde_json::from_str(&strip_jsonc(contents))?
+    } else {
+        toml::from_str(contents)?
+    };
+
+    let snippets = definitions
+        .into_iter()
+        .filter_map(|(name, definition)| {
+            let body = definition.body.into_vec().join("\n");
+            let snippet = match Snippet::parse(&body) {
+                Ok(snippet) => snippet,
+                Err(err) => {
+                    log::warn!("Failed to parse snippet '{name}': {err}");
+                    return None;
+                }
+            };
+            Some(UserSnippet {
+                name,
+                prefixes: definition
+                    .prefix
+                    .map(OneOrMany::into_vec)
+                    .unwrap_or_default(),
+                description: definition
+                    .description
+                    .map(|description| description.into_vec().join("\n")),
+                body,
+                snippet,
+            })
+        })
+        .collect();
+    Ok(snippets)
+}
+
+/// The user-defined snippets of every language.
+#[derive(Debug, Default)]
+pub struct SnippetLibrary {
+    languages: HashMap<String, Vec<Arc<UserSnippet>>>,
+}
+
+impl SnippetLibrary {
+    /// Loads the snippet files in `dirs`, which are ordered from highest to lowest priority.
+    /// Snippets in lower priority directories are ignored if a snippet with the same name was
+    /// already loaded for the language.
+    pub fn load(dirs: &[PathBuf]) -> Self {
+        let mut library = Self::default();
+        for dir in dirs {
+            let Ok(entries) = fs::read_dir(dir) else {
+                continue;
+            };
+            let mut files: Vec<_> = entries
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .collect();
+            files.sort();
+            for file in files {
+                if let Err(err) = library.load_file(&file) {
+                    log::error!("Failed to load snippets from '{}': {err}", file.display());
+                }
+            }
+        }
+        library
+    }
+
+    fn load_file(&mut self, file: &Path) -> Result<()> {
+        let json = match file.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => true,
+            Some("toml") => false,
+            _ => return Ok(()),
+        };
+        let Some(language) = file.file_stem().and_then(|stem| stem.to_str()) else {
+            return Ok(());
+        };
+        let contents = fs::read_to_string(file).context("failed to read file")?;
+        let snippets = self.languages.entry(language.to_owned()).or_default();
+        for snippet in parse_snippets(&contents, json)? {
+            if !snippets.iter().any(|loaded| loaded.name == snippet.name) {
+                snippets.push(Arc::new(snippet));
+            }
+        }
+        Ok(())
+    }
+
+    /// The snippets available in documents of `language`, followed by the global snippets.
+    pub fn snippets<'a>(
+        &'a self,
+        language: Option<&str>,
+    ) -> impl Iterator<Item = &'a Arc<UserSnippet>> + 'a {
+        let language = language
+            .filter(|&language| language != GLOBAL_SNIPPETS)
+            .and_then(|language| self.languages.get(language));
+        language
+            .into_iter()
+            .chain(self.languages.get(GLOBAL_SNIPPETS))
+            .flatten()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_toml_and_json() {
+        let toml = r#"
+            [function]
+            prefix = "fn"
+            description = "A function"
+            body = ["fn ${1:name}() {", "\t$0", "}"]
+
+            [empty]
+            prefix = "x"
+            body = ""
+        "#;
+        let snippets = parse_snippets(toml, false).unwrap();
+        assert_eq!(snippets.len(), 1);
+        assert_eq!(snippets[0].name, "function");
+        assert_eq!(snippets[0].prefixes, ["fn"]);
+        assert_eq!(snippets[0].description.as_deref(), Some("A function"));
+        assert_eq!(snippets[0].body, "fn ${1:name}() {\n\t$0\n}");
+
+        let json = r#"{
+            "Print to console": {
+                "scope": "javascript,typescript",
+                "prefix": ["log", "console"],
+                "body": "console.log($1);"
+            }
+        }"#;
+        let snippets = parse_snippets(json, true).unwrap();
+        assert_eq!(snippets[0].prefixes, ["log", "console"]);
+        assert_eq!(snippets[0].description, None);
+    }
+
+    #[test]
+    fn parse_vscode_snippet_file() {
+        let json = r##"{
+            // Place your snippets for Rust here.
+            "Test module": {
+                "prefix": "tmod",
+                "isFileTemplate": false,
+                "include": ["**/src/**"],
+                "exclude": ["**/tests/**"],
+                /* Multiple lines */
+                "body": [
+                    "#[cfg(test)]",
+                    "mod test {",
+                    "\t$0",
+                    "}",
+                ],
+                "description": "A test module",
+            },
+        }"##;
+        let snippets = parse_snippets(json, true).unwrap();
+        assert_eq!(snippets.len(), 1);
+        assert_eq!(snippets[0].name, "Test module");
+        assert_eq!(snippets[0].prefixes, ["tmod"]);
+        assert_eq!(snippets[0].body, "#[cfg(test)]\nmod test {\n\t$0\n}");
+    }
+
+    #[test]
+    fn load_with_priority() {
+        let high = tempfile::tempdir().unwrap();
+        let low = tempfile::tempdir().unwrap();
+        fs::write(
+            high.path().join("rust.toml"),
+            "[test]\nprefix = \"test\"\nbody = \"high\"",
+        )
+        .unwrap();
+        fs::write(
+            low.path().join("rust.json"),
+            r#"{ "test": { "body": "low" }, "main": { "body": "fn main() {}" } }"#,
+        )
+        .unwrap();
+        fs::write(
+            low.path().join("global.toml"),
+            "[date]\nbody = \"$CURRENT_YEAR\"",
+        )
+        .unwrap();
+
+        let library = SnippetLibrary::load(&[high.path().into(), low.path().into()]);
+        let names = |language| {
+            library
+                .snippets(language)
+                .map(|snippet| (snippet.name.as_str(), snippet.body.as_str()))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            names(Some("rust")),
+            [
+                ("test", "high"),
+                ("main", "fn main() {}"),
+                ("date", "$CURRENT_YEAR")
+            ]
+        );
+        assert_eq!(names(Some("python")), [("date", "$CURRENT_YEAR")]);
+        assert_eq!(names(None), [("date", "$CURRENT_YEAR")]);
+    }
+}
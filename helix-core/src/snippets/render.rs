@@ -21,7 +21,7 @@ pub enum TabstopKind {
     Transform(Arc<Transform>),
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Tabstop {
     pub ranges: SmallVec<[Range; 1]>,
     pub parent: Option<TabstopIdx>,
@@ -58,7 +58,7 @@ pub fn selection(
     }
 }
 
-#[derive(Debug, Default, PartialEq)]
+#[derive(Debug, Default, Clone, PartialEq)]
 pub struct RenderedSnippet {
     pub tabstops: Vec<Tabstop>,
     pub ranges: Vec<Range>,
@@ -0,0 +1,289 @@
+//! Sorting of selections and lines, used by the `:sort` command.
+//!
+//! Values are compared by a key, which is either the whole value, a field or the match of a
+//! regex. Values with equal keys are ordered by comparing the whole values unless the sort is
+//! stable, in which case they keep their original order.
+
+use std::cmp::Ordering;
+
+use regex::Regex;
+
+/// How the keys of values are compared.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SortOrder {
+    /// Compare keys character by character.
+    #[default]
+    Lexical,
+    /// Compare the first number in each key. Keys without a number come first.
+    Numeric,
+    /// Compare runs of digits by their value and everything else character by character, so
+    /// `file2` comes before `file10` and `1.9` before `1.10`.
+    Natural,
+}
+
+/// The part of a value which is compared.
+#[derive(Debug, Default, Clone)]
+pub enum SortKey {
+    /// The whole value.
+    #[default]
+    Whole,
+    /// The field with the given index, starting at 1. Fields are separated by `separator` or by
+    /// runs of whitespace if there is no separator. Values with fewer fields have an empty key.
+    Field {
+        index: usize,
+        separator: Option<String>,
+    },
+    /// The first match of the regex, or its first capture group if it has one. Values which do
+    /// not match have an empty key.
+    Regex(Regex),
+}
+
+impl SortKey {
+    fn extract<'a>(&self, value: &'a str) -> &'a str {
+        match self {
+            Self::Whole => value,
+            Self::Field { index, separator } => {
+                let index = index.saturating_sub(1);
+                match separator {
+                    Some(separator) => value.split(separator.as_str()).nth(index),
+                    None => value.split_whitespace().nth(index),
+                }
+                .unwrap_or_default()
+            }
+            Self::Regex(regex) => regex
+                .captures(value)
+                .and_then(|captures| captures.get(1).or_else(|| captures.get(0)))
+                .map_or("", |m| m.as_str()),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct SortOptions {
+    pub order: SortOrder,
+    pub key: SortKey,
+    /// Compare keys ignoring case.
+    pub insensitive: bool,
+    pub reverse: bool,
+    /// Only keep the first of each run of values with equal keys.
+    pub unique: bool,
+    /// Keep values with equal keys in their original order.
+    pub stable: bool,
+}
+
+impl SortOptions {
+    fn compare_keys(&self, a: &str, b: &str) -> Ordering {
+        match self.order {
+            SortOrder::Lexical => a.cmp(b),
+            SortOrder::Numeric => match (parse_number(a), parse_number(b)) {
+                (Some(a), Some(b)) => a.total_cmp(&b),
+                (a, b) => a.is_some().cmp(&b.is_some()),
+            },
+            SortOrder::Natural => natural_cmp(a, b),
+        }
+    }
+}
+
+/// Sorts `values` according to `options`.
+pub fn sort<S: AsRef<str>>(values: Vec<S>, options: &SortOptions) -> Vec<S> {
+    let mut values: Vec<_> = values
+        .into_iter()
+        .map(|value| {
+            let key = options.key.extract(value.as_ref());
+            let key = if options.insensitive {
+                key.to_lowercase()
+            } else {
+                key.to_owned()
+            };
+            (key, value)
+        })
+        .collect();
+
+    values.sort_by(|(a_key, a), (b_key, b)| {
+        let mut ordering = options.compare_keys(a_key, b_key);
+        if !options.stable {
+            ordering = ordering.then_with(|| a.as_ref().cmp(b.as_ref()));
+        }
+        if options.reverse {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    });
+    if options.unique {
+        values.dedup_by(|(a, _), (b, _)| options.compare_keys(a, b) == Ordering::Equal);
+    }
+
+    values.into_iter().map(|(_, value)| value).collect()
+}
+
+/// Parses the first number in `text`, including a leading `-` and a fractional part.
+fn parse_number(text: &str) -> Option<f64> {
+    let start = text.find(|c: char| c.is_ascii_digit())?;
+    let rest = &text[start..];
+    let mut seen_dot = false;
+    let end = rest
+        .find(|c: char| {
+            if c == '.' && !seen_dot {
+                seen_dot = true;
+                false
+            } else {
+                !c.is_ascii_digit()
+            }
+        })
+        .unwrap_or(rest.len());
+    let number: f64 = rest[..end].trim_end_matches('.').parse().ok()?;
+    if text[..start].ends_with('-') {
+        Some(-number)
+    } else {
+        Some(number)
+    }
+}
+
+fn natural_cmp(mut a: &str, mut b: &str) -> Ordering {
+    fn split_digits(text: &str) -> (&str, &str) {
+        let end = text
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(text.len());
+        text.split_at(end)
+    }
+
+    loop {
+        match (a.chars().next(), b.chars().next()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                let (a_digits, a_rest) = split_digits(a);
+                let (b_digits, b_rest) = split_digits(b);
+                let a_number = a_digits.trim_start_matches('0');
+                let b_number = b_digits.trim_start_matches('0');
+                let ordering = a_number
+                    .len()
+                    .cmp(&b_number.len())
+                    .then_with(|| a_number.cmp(b_number));
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+                (a, b) = (a_rest, b_rest);
+            }
+            (Some(x), Some(y)) => {
+                if x != y {
+                    return x.cmp(&y);
+                }
+                (a, b) = (&a[x.len_utf8()..], &b[y.len_utf8()..]);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn sorted(values: &[&'static str], options: SortOptions) -> Vec<&'static str> {
+        sort(values.to_vec(), &options)
+    }
+
+    #[test]
+    fn sort_orders() {
+        let values = ["item 10", "item 9", "Item 1.5", "none", "item -2"];
+        assert_eq!(
+            sorted(&values, SortOptions::default()),
+            ["Item 1.5", "item -2", "item 10", "item 9", "none"]
+        );
+        assert_eq!(
+            sorted(
+                &values,
+                SortOptions {
+                    order: SortOrder::Numeric,
+                    ..Default::default()
+                }
+            ),
+            ["none", "item -2", "Item 1.5", "item 9", "item 10"]
+        );
+
+        let versions = ["v1.10.0", "v1.9.2", "v1.9.10", "v01.9.3", "v2"];
+        assert_eq!(
+            sorted(
+                &versions,
+                SortOptions {
+                    order: SortOrder::Natural,
+                    reverse: true,
+                    ..Default::default()
+                }
+            ),
+            ["v2", "v1.10.0", "v1.9.10", "v01.9.3", "v1.9.2"]
+        );
+    }
+
+    #[test]
+    fn sort_keys() {
+        let values = ["b 3 x", "a 10 y", "c 3 a"];
+        let by_field = SortOptions {
+            order: SortOrder::Numeric,
+            key: SortKey::Field {
+                index: 2,
+                separator: None,
+            },
+            ..Default::default()
+        };
+        // Equal keys fall back to comparing the whole values unless the sort is stable.
+        assert_eq!(
+            sorted(&values, by_field.clone()),
+            ["b 3 x", "c 3 a", "a 10 y"]
+        );
+        let by_regex = SortOptions {
+            order: SortOrder::Numeric,
+            key: SortKey::Regex(Regex::new(r"(\d+) \w").unwrap()),
+            ..Default::default()
+        };
+        let values = ["c 3 a", "b 3 x", "a 10 y"];
+        assert_eq!(
+            sorted(&values, by_regex.clone()),
+            ["b 3 x", "c 3 a", "a 10 y"]
+        );
+        let stable = SortOptions {
+            stable: true,
+            ..by_regex
+        };
+        assert_eq!(sorted(&values, stable), ["c 3 a", "b 3 x", "a 10 y"]);
+
+        let csv = ["x,2", "y,1", "z"];
+        let by_column = SortOptions {
+            key: SortKey::Field {
+                index: 2,
+                separator: Some(",".into()),
+            },
+            ..Default::default()
+        };
+        assert_eq!(sorted(&csv, by_column), ["z", "y,1", "x,2"]);
+    }
+
+    #[test]
+    fn sort_unique() {
+        let values = ["b", "A", "a", "B", "a"];
+        assert_eq!(
+            sorted(
+                &values,
+                SortOptions {
+                    unique: true,
+                    ..Default::default()
+                }
+            ),
+            ["A", "B", "a", "b"]
+        );
+        assert_eq!(
+            sorted(
+                &values,
+                SortOptions {
+                    insensitive: true,
+                    unique: true,
+                    stable: true,
+                    ..Default::default()
+                }
+            ),
+            ["A", "b"]
+        );
+    }
+}
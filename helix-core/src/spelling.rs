@@ -0,0 +1,301 @@
+//! Spell checking with Hunspell dictionaries.
+//!
+//! Dictionaries are pairs of `<language>.aff` and `<language>.dic` files, for example
+//! `en_US.aff` and `en_US.dic`. Words added by the user are kept in a personal word list with
+//! one word per line which is loaded on top of the dictionary.
+
+use std::{
+    fs,
+    io::Write,
+    ops::Range,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{anyhow, Context, Result};
+use ropey::RopeSlice;
+use spellbook::Dictionary;
+
+use crate::syntax::{HighlightEvent, Loader};
+use crate::Syntax;
+
+/// The pseudo scope which selects text that isn't highlighted at all, like the paragraphs of a
+/// Markdown document or the body of a commit message.
+pub const PROSE_SCOPE: &str = "text";
+
+/// A Hunspell dictionary together with the personal word list of the user.
+pub struct SpellChecker {
+    dictionary: Dictionary,
+    personal: Option<PathBuf>,
+}
+
+impl std::fmt::Debug for SpellChecker {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SpellChecker")
+            .field("personal", &self.personal)
+            .finish_non_exhaustive()
+    }
+}
+
+impl SpellChecker {
+    /// Creates a spell checker from the contents of a `.aff` and a `.dic` file.
+    pub fn new(aff: &str, dic: &str) -> Result<Self> {
+        let dictionary = Dictionary::new(aff, dic).map_err(|err| anyhow!("{err}"))?;
+        Ok(Self {
+            dictionary,
+            personal: None,
+        })
+    }
+
+    /// Loads the dictionary of `language` from the first directory of `dirs` which contains
+    /// it and the words of the personal word list at `personal`, which doesn't need to exist.
+    pub fn load(language: &str, dirs: &[PathBuf], personal: PathBuf) -> Result<Self> {
+        let (aff, dic) = dirs
+            .iter()
+            .map(|dir| {
+                (
+                    dir.join(format!("{language}.aff")),
+                    dir.join(format!("{language}.dic")),
+                )
+            })
+            .find(|(aff, dic)| aff.is_file() && dic.is_file())
+            .ok_or_else(|| anyhow!("no dictionary found for '{language}'"))?;
+        let read = |path: &Path| {
+            fs::read_to_string(path).with_context(|| format!("failed to read {}", path.display()))
+        };
+        let mut checker = Self::new(&read(&aff)?, &read(&dic)?)?;
+
+        if personal.is_file() {
+            for word in read(&personal)?.lines().map(str::trim) {
+                if !word.is_empty() {
+                    checker.add(word)?;
+                }
+            }
+        }
+        checker.personal = Some(personal);
+        Ok(checker)
+    }
+
+    fn add(&mut self, word: &str) -> Result<()> {
+        self.dictionary.add(word).map_err(|err| anyhow!("{err}"))
+    }
+
+    /// Whether `word` is spelled correctly.
+    pub fn check(&self, word: &str) -> bool {
+        self.dictionary.check(word)
+    }
+
+    /// The corrections for `word`, best first.
+    pub fn suggest(&self, word: &str) -> Vec<String> {
+        let mut suggestions = Vec::new();
+        self.dictionary.suggest(word, &mut suggestions);
+        suggestions
+    }
+
+    /// Accepts `word` from now on and appends it to the personal word list.
+    pub fn add_word(&mut self, word: &str) -> Result<()> {
+        if word.is_empty() || word.contains(char::is_whitespace) {
+            return Err(anyhow!("'{word}' is not a single word"));
+        }
+        self.add(word)?;
+        if let Some(path) = &self.personal {
+            if let Some(dir) = path.parent() {
+                fs::create_dir_all(dir)?;
+            }
+            let mut file = fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .with_context(|| format!("failed to open {}", path.display()))?;
+            writeln!(file, "{word}")?;
+        }
+        Ok(())
+    }
+
+    /// Returns the char ranges of the misspelled words within the byte `ranges` of `text`.
+    pub fn misspellings(&self, text: RopeSlice, ranges: &[Range<usize>]) -> Vec<Range<usize>> {
+        let mut misspellings = Vec::new();
+        for range in ranges {
+            let fragment = String::from(text.byte_slice(range.clone()));
+            for (offset, word) in words(&fragment) {
+                if !self.check(word) {
+                    let start = text.byte_to_char(range.start + offset);
+                    misspellings.push(start..start + word.chars().count());
+                }
+            }
+        }
+        misspellings
+    }
+}
+
+/// Splits `text` into the words to check, along with their byte offsets. Words are split at
+/// case changes, so `camelCase` yields `camel` and `Case`. Identifiers containing digits or
+/// underscores, words in all caps, single letters, URLs, paths and email addresses are skipped.
+pub fn words(text: &str) -> Vec<(usize, &str)> {
+    let mut words = Vec::new();
+    for chunk in text.split(char::is_whitespace) {
+        let offset = chunk.as_ptr() as usize - text.as_ptr() as usize;
+        if chunk.contains("://") || chunk.contains(['/', '\\', '@']) {
+            continue;
+        }
+
+        let is_word_char = |c: char| c.is_alphanumeric() || c == '_' || c == '\'' || c == '’';
+        let mut rest = chunk;
+        while let Some(start) = rest.find(is_word_char) {
+            let end = rest[start..]
+                .find(|c| !is_word_char(c))
+                .map_or(rest.len(), |end| start + end);
+            let raw = &rest[start..end];
+            let token = raw.trim_start_matches(['\'', '’']);
+            let token_offset =
+                offset + (chunk.len() - rest.len()) + start + raw.len() - token.len();
+            let token = token.trim_end_matches(['\'', '’']);
+            rest = &rest[end..];
+
+            if token.contains(|c: char| c.is_numeric() || c == '_') {
+                continue;
+            }
+            for (sub_offset, word) in split_case(token) {
+                let letters = word.chars().filter(|c| c.is_alphabetic());
+                if letters.clone().count() < 2 || letters.clone().all(char::is_uppercase) {
+                    continue;
+                }
+                words.push((token_offset + sub_offset, word));
+            }
+        }
+    }
+    words
+}
+
+/// Splits `word` before each uppercase letter which follows a lowercase one.
+fn split_case(word: &str) -> Vec<(usize, &str)> {
+    let mut parts = Vec::new();
+    let mut start = 0;
+    let mut prev_lowercase = false;
+    for (i, c) in word.char_indices() {
+        if c.is_uppercase() && prev_lowercase {
+            parts.push((start, &word[start..i]));
+            start = i;
+        }
+        prev_lowercase = c.is_lowercase();
+    }
+    parts.push((start, &word[start..]));
+    parts
+}
+
+/// Whether the highlight `scope` is selected by the configured `selected` scope, either exactly or as
+/// one of its children: `comment` selects `comment.line` but not `commentary`.
+fn scope_matches(scope: &str, selected: &str) -> bool {
+    scope
+        .strip_prefix(selected)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('.'))
+}
+
+/// Returns the byte ranges of `text` to spell check: the text whose innermost highlight matches
+/// one of `scopes`, and text without any highlight if `scopes` contains [`PROSE_SCOPE`]. The
+/// whole text is checked if there's no syntax tree.
+pub fn checked_ranges(
+    text: RopeSlice,
+    syntax: Option<&Syntax>,
+    loader: &Loader,
+    scopes: &[String],
+) -> Vec<Range<usize>> {
+    let len = text.len_bytes();
+    let Some(syntax) = syntax else {
+        return std::iter::once(0..len).collect();
+    };
+
+    let names = loader.scopes();
+    let prose = scopes.iter().any(|scope| scope == PROSE_SCOPE);
+    let is_checked = |highlight: Option<&crate::syntax::Highlight>| match highlight {
+        Some(highlight) => names.get(highlight.idx()).is_some_and(|name| {
+            scopes
+                .iter()
+                .any(|scope| scope != PROSE_SCOPE && scope_matches(name, scope))
+        }),
+        None => prose,
+    };
+
+    let mut ranges: Vec<Range<usize>> = Vec::new();
+    let mut highlighter = syntax.highlighter(text, loader, ..);
+    let mut highlights = Vec::new();
+    let mut pos = 0;
+    while pos < len {
+        if pos as u32 == highlighter.next_event_offset() {
+            let (event, new_highlights) = highlighter.advance();
+            if event == HighlightEvent::Refresh {
+                highlights.clear();
+            }
+            highlights.extend(new_highlights);
+        }
+
+        let start = pos;
+        pos = (highlighter.next_event_offset() as usize).min(len);
+        if pos <= start {
+            continue;
+        }
+        if is_checked(highlights.last()) {
+            match ranges.last_mut() {
+                Some(last) if last.end == start => last.end = pos,
+                _ => ranges.push(start..pos),
+            }
+        }
+    }
+    ranges
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ropey::Rope;
+
+    const AFF: &str = "SET UTF-8\nSFX S Y 1\nSFX S 0 s .\n";
+    const DIC: &str = "3\nhello\nworld/S\ncase\n";
+
+    #[test]
+    fn split_words() {
+        let words: Vec<_> = words(
+            "Hello, world's camelCase snake_case v2 HTML a https://example.com 'quoted' ünïcode",
+        );
+        assert_eq!(
+            words,
+            [
+                (0, "Hello"),
+                (7, "world's"),
+                (15, "camel"),
+                (20, "Case"),
+                (67, "quoted"),
+                (75, "ünïcode"),
+            ]
+        );
+    }
+
+    #[test]
+    fn check_and_add_words() {
+        let mut checker = SpellChecker::new(AFF, DIC).unwrap();
+        assert!(checker.check("hello"));
+        assert!(checker.check("worlds"));
+        assert!(!checker.check("helo"));
+        assert!(checker.suggest("helo").contains(&"hello".to_string()));
+
+        let text = Rope::from("hello wrold, héllo worlds\n");
+        assert_eq!(
+            checker.misspellings(text.slice(..), &[0..5, 6..text.len_bytes()]),
+            [6..11, 13..18]
+        );
+        assert!(checker
+            .misspellings(text.slice(..), &[0..5, 20..26])
+            .is_empty());
+
+        checker.add_word("wrold").unwrap();
+        assert!(checker.check("wrold"));
+        assert!(checker.add_word("two words").is_err());
+    }
+
+    #[test]
+    fn match_scopes() {
+        assert!(scope_matches("comment", "comment"));
+        assert!(scope_matches("comment.line.documentation", "comment"));
+        assert!(!scope_matches("commentary", "comment"));
+        assert!(!scope_matches("string", "comment"));
+    }
+}
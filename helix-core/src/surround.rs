@@ -1,14 +1,19 @@
-use std::fmt::Display;
+use std::{borrow::Cow, cell::OnceCell, fmt::Display, ops};
 
 use crate::{
+    chars::char_is_word,
     graphemes::next_grapheme_boundary,
     match_brackets::{
         find_matching_bracket, find_matching_bracket_fuzzy, get_pair, is_close_bracket,
         is_open_bracket,
     },
     movement::Direction,
-    search, Range, Selection, Syntax,
+    search,
+    tree_sitter::Node,
+    Range, Selection, Syntax,
 };
+use once_cell::sync::Lazy;
+use regex::Regex;
 use ropey::RopeSlice;
 
 #[derive(Debug, PartialEq, Eq)]
@@ -313,6 +318,319 @@ pub fn get_surround_pos(
     Ok(change_pos)
 }
 
+/// A surround pair which can't be described by a single character.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SurroundPair {
+    /// An HTML or XML element like `<div class="a">` and `</div>`.
+    Tag,
+    /// A function call like `foo(` and `)`.
+    Function,
+    /// A pair of literal delimiters like `**` and `**` or `Some(` and `)`.
+    Literal { open: String, close: String },
+}
+
+/// The char ranges of the opening and closing delimiter of a surround pair.
+pub type Delimiters = (ops::Range<usize>, ops::Range<usize>);
+
+/// Finds the delimiters of `pair` around every range of the selection, using tree-sitter when
+/// possible. `skip` selects the nth closest pair around each range.
+pub fn get_surround_ranges(
+    syntax: Option<&Syntax>,
+    text: RopeSlice,
+    selection: &Selection,
+    pair: &SurroundPair,
+    skip: usize,
+) -> Result<Vec<Delimiters>> {
+    let mut delimiters: Vec<Delimiters> = Vec::new();
+    // The tags of the document are only scanned once for all ranges.
+    let tags = OnceCell::new();
+    for &range in selection {
+        if range.to() > text.len_chars() {
+            return Err(Error::RangeExceedsText);
+        }
+        let (open, close) = find_nth_surround_ranges(syntax, text, range, pair, skip, &tags)
+            .ok_or(Error::PairNotFound)?;
+        let overlaps = |other: &ops::Range<usize>| {
+            delimiters.iter().any(|(prev_open, prev_close)| {
+                [prev_open, prev_close]
+                    .iter()
+                    .any(|prev| prev.start < other.end && other.start < prev.end)
+            })
+        };
+        if overlaps(&open) || overlaps(&close) {
+            return Err(Error::CursorOverlap);
+        }
+        delimiters.push((open, close));
+    }
+    Ok(delimiters)
+}
+
+fn find_nth_surround_ranges(
+    syntax: Option<&Syntax>,
+    text: RopeSlice,
+    range: Range,
+    pair: &SurroundPair,
+    skip: usize,
+    tags: &OnceCell<Vec<Delimiters>>,
+) -> Option<Delimiters> {
+    let skip = skip.max(1);
+    if let Some(syntax) = syntax {
+        let found = surround_ranges_ts(syntax, text, range, pair);
+        if let Some(delimiters) = found.into_iter().nth(skip - 1) {
+            return Some(delimiters);
+        }
+    }
+    match pair {
+        SurroundPair::Tag => tags
+            .get_or_init(|| tag_pairs_plain(text))
+            .iter()
+            .filter(|delimiters| encloses(delimiters, range))
+            .nth(skip - 1)
+            .cloned(),
+        SurroundPair::Function => function_ranges_plain(text, range, skip),
+        SurroundPair::Literal { open, close } => {
+            literal_ranges_plain(text, range, open, close, skip)
+        }
+    }
+}
+
+fn encloses((open, close): &Delimiters, range: Range) -> bool {
+    open.start <= range.from() && close.end >= range.to()
+}
+
+fn starts_with_at(text: RopeSlice, pos: usize, prefix: &str) -> bool {
+    text.get_slice(pos..pos + prefix.chars().count())
+        .is_some_and(|slice| slice == prefix)
+}
+
+/// Returns the start of the function name ending right before the `(` at `paren`.
+fn function_name_start(text: RopeSlice, paren: usize) -> Option<usize> {
+    let name_len = text
+        .chars_at(paren)
+        .reversed()
+        .take_while(|&ch| char_is_word(ch))
+        .count();
+    (name_len > 0).then(|| paren - name_len)
+}
+
+/// Collects the delimiters of the syntax nodes enclosing `range` which match `pair`, from the
+/// innermost to the outermost node.
+fn surround_ranges_ts(
+    syntax: &Syntax,
+    text: RopeSlice,
+    range: Range,
+    pair: &SurroundPair,
+) -> Vec<Delimiters> {
+    let start = text.char_to_byte(range.from()) as u32;
+    let end = text.char_to_byte(range.to()) as u32;
+    let mut found: Vec<Delimiters> = Vec::new();
+    let layers: Vec<_> = syntax.layers_for_byte_range(start, end).collect();
+    for layer in layers.into_iter().rev() {
+        let Some(tree) = syntax.layer(layer).tree() else {
+            continue;
+        };
+        let mut node = tree.root_node().descendant_for_byte_range(start, end);
+        while let Some(current) = node {
+            if let Some(delimiters) = node_delimiters(text, &current, pair) {
+                if encloses(&delimiters, range) && !found.contains(&delimiters) {
+                    found.push(delimiters);
+                }
+            }
+            node = current.parent();
+        }
+    }
+    found
+}
+
+fn node_delimiters(text: RopeSlice, node: &Node, pair: &SurroundPair) -> Option<Delimiters> {
+    let char_range = |node: &Node| {
+        text.byte_to_char(node.start_byte() as usize)..text.byte_to_char(node.end_byte() as usize)
+    };
+    let range = char_range(node);
+    match pair {
+        SurroundPair::Tag => {
+            if node.child_count() < 2 {
+                return None;
+            }
+            let open = char_range(&node.child(0)?);
+            let close = char_range(&node.child(node.child_count() - 1)?);
+            let open_text = Cow::from(text.slice(open.clone()));
+            let close_text = Cow::from(text.slice(close.clone()));
+            let is_open = open_text.starts_with('<')
+                && !open_text.starts_with("</")
+                && open_text.ends_with('>')
+                && !open_text.ends_with("/>");
+            let is_close = close_text.starts_with("</") && close_text.ends_with('>');
+            (is_open && is_close).then_some((open, close))
+        }
+        SurroundPair::Function => {
+            let arguments = node.child(node.child_count().checked_sub(1)?)?;
+            if !arguments.kind().contains("argument") {
+                return None;
+            }
+            let arguments = char_range(&arguments);
+            if arguments.start == range.start
+                || arguments.len() < 2
+                || text.char(arguments.start) != '('
+                || text.char(arguments.end - 1) != ')'
+            {
+                return None;
+            }
+            // Calls of methods or paths like `a.b(` are surrounded by their last segment only.
+            let name_start = function_name_start(text, arguments.start)
+                .unwrap_or(range.start)
+                .max(range.start);
+            Some((
+                name_start..arguments.start + 1,
+                arguments.end - 1..arguments.end,
+            ))
+        }
+        SurroundPair::Literal { open, close } => {
+            let (open_len, close_len) = (open.chars().count(), close.chars().count());
+            if range.len() < open_len + close_len
+                || !starts_with_at(text, range.start, open)
+                || !starts_with_at(text, range.end - close_len, close)
+            {
+                return None;
+            }
+            Some((
+                range.start..range.start + open_len,
+                range.end - close_len..range.end,
+            ))
+        }
+    }
+}
+
+/// Finds the delimiters of all elements in `text`, from the last to the first opening tag.
+fn tag_pairs_plain(text: RopeSlice) -> Vec<Delimiters> {
+    static TAG: Lazy<Regex> = Lazy::new(|| {
+        Regex::new(r#"<(/?)([A-Za-z][\w:.-]*)(?:[^<>"']|"[^"]*"|'[^']*')*>"#).unwrap()
+    });
+
+    let source = Cow::from(text);
+    let mut open_tags: Vec<(&str, ops::Range<usize>)> = Vec::new();
+    let mut found = Vec::new();
+    for captures in TAG.captures_iter(&source) {
+        let tag = captures.get(0).unwrap();
+        let name = captures.get(2).unwrap().as_str();
+        let tag_range = text.byte_to_char(tag.start())..text.byte_to_char(tag.end());
+        if &captures[1] == "/" {
+            // Unclosed tags like `<br>` inside of the element are dropped.
+            if let Some(i) = open_tags.iter().rposition(|(open, _)| *open == name) {
+                let (_, open) = open_tags.swap_remove(i);
+                open_tags.truncate(i);
+                found.push((open, tag_range));
+            }
+        } else if !tag.as_str().ends_with("/>") {
+            open_tags.push((name, tag_range));
+        }
+    }
+    found.sort_by_key(|(open, _)| std::cmp::Reverse(open.start));
+    found
+}
+
+fn function_ranges_plain(text: RopeSlice, range: Range, mut skip: usize) -> Option<Delimiters> {
+    for n in 1.. {
+        let (open, close) = find_nth_pairs_pos(text, '(', range, n).ok()?;
+        let (open, close) = (open.min(close), open.max(close));
+        let Some(name_start) = function_name_start(text, open) else {
+            continue;
+        };
+        let delimiters = (name_start..open + 1, close..close + 1);
+        if !encloses(&delimiters, range) {
+            continue;
+        }
+        skip -= 1;
+        if skip == 0 {
+            return Some(delimiters);
+        }
+    }
+    None
+}
+
+fn literal_ranges_plain(
+    text: RopeSlice,
+    range: Range,
+    open: &str,
+    close: &str,
+    mut skip: usize,
+) -> Option<Delimiters> {
+    let (open_len, close_len) = (open.chars().count(), close.chars().count());
+    if open_len == 0 || close_len == 0 {
+        return None;
+    }
+
+    if open == close {
+        // Symmetric delimiters can't nest, so they are paired up from the start of the text.
+        let mut found = Vec::new();
+        let mut pending = None;
+        let mut pos = 0;
+        while pos < text.len_chars() {
+            if !starts_with_at(text, pos, open) {
+                pos += 1;
+                continue;
+            }
+            match pending.take() {
+                None => pending = Some(pos),
+                Some(start) => {
+                    let delimiters = (start..start + open_len, pos..pos + close_len);
+                    if encloses(&delimiters, range) {
+                        found.push(delimiters);
+                    }
+                }
+            }
+            pos += open_len;
+        }
+        return found.into_iter().nth(skip - 1);
+    }
+
+    // Search backwards for an opening delimiter whose closing delimiter is after the range.
+    let mut start = range.from();
+    loop {
+        if starts_with_at(text, start, open) {
+            if let Some(end) = find_literal_close(text, open, close, start + open_len) {
+                let delimiters = (start..start + open_len, end..end + close_len);
+                if encloses(&delimiters, range) {
+                    skip -= 1;
+                    if skip == 0 {
+                        return Some(delimiters);
+                    }
+                }
+            }
+        }
+        start = start.checked_sub(1)?;
+    }
+}
+
+/// Finds the start of the closing delimiter matching an opening delimiter which ends at `pos`.
+fn find_literal_close(text: RopeSlice, open: &str, close: &str, mut pos: usize) -> Option<usize> {
+    // Delimiters like `Some(` and `)` are matched by their brackets.
+    if let Some(bracket) = open.chars().last().filter(|&ch| is_open_bracket(ch)) {
+        let (open_bracket, close_bracket) = get_pair(bracket);
+        if close.starts_with(close_bracket) {
+            let end = find_nth_close_pair(text, open_bracket, close_bracket, pos, 1)?;
+            return starts_with_at(text, end, close).then_some(end);
+        }
+    }
+
+    let mut depth = 0usize;
+    while pos < text.len_chars() {
+        if starts_with_at(text, pos, close) {
+            if depth == 0 {
+                return Some(pos);
+            }
+            depth -= 1;
+            pos += close.chars().count();
+        } else if starts_with_at(text, pos, open) {
+            depth += 1;
+            pos += open.chars().count();
+        } else {
+            pos += 1;
+        }
+    }
+    None
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -445,6 +763,91 @@ fn test_find_nth_closest_pairs_pos_index_range_panic() {
         )
     }
 
+    #[test]
+    fn test_get_surround_ranges_plain() {
+        let ranges = |text: &str, pos: usize, pair: SurroundPair, skip: usize| {
+            let doc = Rope::from(text);
+            let selection = Selection::point(pos);
+            get_surround_ranges(None, doc.slice(..), &selection, &pair, skip)
+        };
+        let literal = |open: &str, close: &str| SurroundPair::Literal {
+            open: open.into(),
+            close: close.into(),
+        };
+
+        let html = "<div class=\"a\"><br><b>bold</b> text</div>";
+        assert_eq!(
+            ranges(html, 22, SurroundPair::Tag, 1),
+            Ok(vec![(19..22, 26..30)])
+        );
+        assert_eq!(
+            ranges(html, 22, SurroundPair::Tag, 2),
+            Ok(vec![(0..15, 35..41)])
+        );
+        assert_eq!(
+            ranges(html, 22, SurroundPair::Tag, 3),
+            Err(Error::PairNotFound)
+        );
+
+        let call = "x = foo(bar(1), (2))";
+        assert_eq!(
+            ranges(call, 17, SurroundPair::Function, 1),
+            Ok(vec![(4..8, 19..20)])
+        );
+        assert_eq!(
+            ranges(call, 12, SurroundPair::Function, 1),
+            Ok(vec![(8..12, 13..14)])
+        );
+
+        let markdown = "**a** and **bold** text";
+        assert_eq!(
+            ranges(markdown, 13, literal("**", "**"), 1),
+            Ok(vec![(10..12, 16..18)])
+        );
+        assert_eq!(
+            ranges(markdown, 7, literal("**", "**"), 1),
+            Err(Error::PairNotFound)
+        );
+
+        let rust = "Some(Some(x)).map(|x| (x))";
+        assert_eq!(
+            ranges(rust, 10, literal("Some(", ")"), 1),
+            Ok(vec![(5..10, 11..12)])
+        );
+        assert_eq!(
+            ranges(rust, 10, literal("Some(", ")"), 2),
+            Ok(vec![(0..5, 12..13)])
+        );
+        assert_eq!(
+            ranges("{{ {{a}} }}", 6, literal("{{", "}}"), 2),
+            Ok(vec![(0..2, 9..11)])
+        );
+    }
+
+    #[test]
+    fn test_get_surround_ranges_overlap() {
+        let doc = Rope::from("foo(a, b)");
+        let selection =
+            Selection::new(SmallVec::from_slice(&[Range::point(4), Range::point(7)]), 0);
+        assert_eq!(
+            get_surround_ranges(None, doc.slice(..), &selection, &SurroundPair::Function, 1),
+            Err(Error::CursorOverlap)
+        );
+    }
+
+    #[test]
+    fn test_get_surround_ranges_multiple_tags() {
+        let doc = Rope::from("<a>x</a><b>y</b>");
+        let selection = Selection::new(
+            SmallVec::from_slice(&[Range::point(3), Range::point(11)]),
+            0,
+        );
+        assert_eq!(
+            get_surround_ranges(None, doc.slice(..), &selection, &SurroundPair::Tag, 1),
+            Ok(vec![(0..3, 4..8), (8..11, 12..16)])
+        );
+    }
+
     // Create a Rope and a matching Selection using a specification language.
     // ^ is a single-point selection.
     // _ is an expected index. These are returned as a Vec<usize> for use in assertions.
@@ -1,4 +1,8 @@
-use crate::{auto_pairs::AutoPairs, diagnostic::Severity, Language};
+use crate::{
+    auto_pairs::{AutoPairs, Pair},
+    diagnostic::Severity,
+    Language,
+};
 
 use helix_stdx::rope;
 use serde::{ser::SerializeSeq as _, Deserialize, Serialize};
@@ -103,6 +107,17 @@ pub struct LanguageConfiguration {
     pub persistent_diagnostic_sources: Vec<String>,
     /// Overrides the `editor.rainbow-brackets` config key for the language.
     pub rainbow_brackets: Option<bool>,
+    /// Groups of words which `increment` and `decrement` cycle through in addition to
+    /// `editor.increment-groups`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub increment_groups: Vec<Vec<String>>,
+    /// Surround pairs for the surround commands by key, like `["**", "**"]` for `b` to surround
+    /// with bold text in Markdown. These take precedence over the built-in pairs.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub surround_pairs: HashMap<char, (String, String)>,
+    /// The highlight scopes to spell check, like `comment` or `string`. `text` selects text
+    /// without any highlight. If set, overrides `editor.spelling.scopes`.
+    pub spelling_scopes: Option<Vec<String>>,
 }
 
 impl LanguageConfiguration {
@@ -490,6 +505,10 @@ pub struct DebugAdapterConfig {
     pub templates: Vec<DebugTemplate>,
     #[serde(default)]
     pub quirks: DebuggerQuirks,
+    /// Values of the `type` field in `.vscode/launch.json` configurations which should be
+    /// started with this adapter, in addition to the adapter's `name`.
+    #[serde(default)]
+    pub vscode_types: Vec<String>,
 }
 
 // Different workarounds for adapters' differences
@@ -530,8 +549,33 @@ pub enum AutoPairConfig {
     /// Enables or disables auto pairing. False means disabled. True means to use the default pairs.
     Enable(bool),
 
-    /// The mappings of pairs.
-    Pairs(HashMap<char, char>),
+    /// The mappings of pairs, from the opening to the closing string.
+    Pairs(HashMap<String, AutoPairClose>),
+}
+
+/// The closing string of an auto pair, like `')'` or `{ close = "'", not-in = ["comment"] }`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum AutoPairClose {
+    Close(String),
+    Conditional {
+        close: String,
+        /// The kinds of syntax nodes in which the pair is not closed.
+        #[serde(default, rename = "not-in")]
+        not_in: Vec<String>,
+    },
+}
+
+impl AutoPairClose {
+    fn pair(&self, open: &str) -> Pair {
+        match self {
+            Self::Close(close) => Pair::new(open, close.as_str()),
+            Self::Conditional { close, not_in } => Pair {
+                not_in: not_in.clone(),
+                ..Pair::new(open, close.as_str())
+            },
+        }
+    }
 }
 
 impl Default for AutoPairConfig {
@@ -545,7 +589,9 @@ fn from(auto_pair_config: &AutoPairConfig) -> Self {
         match auto_pair_config {
             AutoPairConfig::Enable(false) => None,
             AutoPairConfig::Enable(true) => Some(AutoPairs::default()),
-            AutoPairConfig::Pairs(pairs) => Some(AutoPairs::new(pairs.iter())),
+            AutoPairConfig::Pairs(pairs) => Some(AutoPairs::new(
+                pairs.iter().map(|(open, close)| close.pair(open)),
+            )),
         }
     }
 }
@@ -1,9 +1,13 @@
 use std::fmt::Display;
 
+use helix_stdx::path::find_paths;
+use once_cell::sync::Lazy;
+use regex::Regex;
 use ropey::RopeSlice;
 
 use crate::chars::{categorize_char, char_is_whitespace, CharCategory};
 use crate::graphemes::{next_grapheme_boundary, prev_grapheme_boundary};
+use crate::indent::indent_level_for_line;
 use crate::line_ending::rope_is_line_ending;
 use crate::movement::Direction;
 use crate::syntax;
@@ -67,16 +71,33 @@ fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
     }
 }
 
-// count doesn't do anything yet
+/// Selects the word under the cursor. With a `count` greater than one the following words are
+/// selected as well.
 pub fn textobject_word(
     slice: RopeSlice,
     range: Range,
     textobject: TextObject,
-    _count: usize,
+    count: usize,
     long: bool,
 ) -> Range {
-    let pos = range.cursor(slice);
+    let mut word = word_range(slice, range.cursor(slice), textobject, long);
+    for _ in 1..count {
+        // The next word starts after the whitespace following the current word.
+        let pos = word.to()
+            + slice
+                .chars_at(word.to())
+                .take_while(|c| char_is_whitespace(*c))
+                .count();
+        if pos >= slice.len_chars() {
+            break;
+        }
+        let next = word_range(slice, pos, textobject, long);
+        word = Range::new(word.from(), next.to());
+    }
+    word
+}
 
+fn word_range(slice: RopeSlice, pos: usize, textobject: TextObject, long: bool) -> Range {
     let word_start = find_word_boundary(slice, pos, Direction::Backward, long);
     let word_end = match slice.get_char(pos).map(categorize_char) {
         None | Some(CharCategory::Whitespace | CharCategory::Eol) => pos,
@@ -197,6 +218,297 @@ pub fn textobject_paragraph(
     Range::new(anchor, head)
 }
 
+/// The visual width of the indentation of `line`, or `None` if the line is blank.
+fn line_indent(slice: RopeSlice, line: usize, tab_width: usize) -> Option<usize> {
+    let line = slice.line(line);
+    if line.chars().all(char::is_whitespace) {
+        return None;
+    }
+    Some(indent_level_for_line(line, tab_width, 1))
+}
+
+/// Selects the block of lines around the cursor which are indented at least as far as the line
+/// under the cursor. Blank lines inside the block are included, blank lines at its start and end
+/// are not.
+///
+/// `Around` also selects the less indented header line before the block and a footer line after
+/// it, if the footer has the same indentation as the header and closes the block (it starts with
+/// a closing bracket or `end`). With a `count` greater than one the block of the header line is
+/// selected instead, so `2ai` selects two levels out.
+pub fn textobject_indentation_level(
+    slice: RopeSlice,
+    range: Range,
+    textobject: TextObject,
+    count: usize,
+    tab_width: usize,
+) -> Range {
+    let indent = |line| line_indent(slice, line, tab_width);
+    let last_line = slice.len_lines().saturating_sub(1);
+    let cursor_line = range.cursor_line(slice);
+
+    // Blank lines belong to the block of the next non-blank line.
+    let Some(mut line) = (cursor_line..=last_line)
+        .chain((0..cursor_line).rev())
+        .find(|&line| indent(line).is_some())
+    else {
+        return range;
+    };
+
+    let block = |line: usize| {
+        let level = indent(line).unwrap();
+        let in_block = |line| indent(line).is_none_or(|indent| indent >= level);
+        let mut start = line;
+        while start > 0 && in_block(start - 1) {
+            start -= 1;
+        }
+        let mut end = line;
+        while end < last_line && in_block(end + 1) {
+            end += 1;
+        }
+        while indent(start).is_none() {
+            start += 1;
+        }
+        while indent(end).is_none() {
+            end -= 1;
+        }
+        (start, end)
+    };
+
+    let (mut start, mut end) = block(line);
+    for _ in 1..count {
+        // The header is the closest non-blank line before the block, which is less indented.
+        let Some(header) = (0..start).rev().find(|&line| indent(line).is_some()) else {
+            break;
+        };
+        line = header;
+        (start, end) = block(line);
+    }
+
+    if textobject == TextObject::Around {
+        if let Some(header) = (0..start).rev().find(|&line| indent(line).is_some()) {
+            let header_indent = indent(header);
+            let footer = (end + 1..=last_line).find(|&line| indent(line).is_some());
+            if let Some(footer) = footer.filter(|&footer| {
+                let text = slice.line(footer).to_string();
+                let text = text.trim_start();
+                indent(footer) == header_indent
+                    && (text.starts_with([')', ']', '}'])
+                        || text.split_whitespace().next() == Some("end"))
+            }) {
+                end = footer;
+            }
+            start = header;
+        }
+    }
+
+    Range::new(slice.line_to_char(start), slice.line_to_char(end + 1))
+}
+
+/// Selects the line under the cursor and the `count - 1` lines after it. `Inside` excludes the
+/// indentation, trailing whitespace and the line ending.
+pub fn textobject_line(
+    slice: RopeSlice,
+    range: Range,
+    textobject: TextObject,
+    count: usize,
+) -> Range {
+    let start_line = range.cursor_line(slice);
+    let end_line = (start_line + count.max(1) - 1).min(slice.len_lines().saturating_sub(1));
+    let start = slice.line_to_char(start_line);
+    let end = slice.line_to_char(end_line + 1);
+
+    match textobject {
+        TextObject::Around => Range::new(start, end),
+        TextObject::Inside => {
+            let leading = slice
+                .chars_at(start)
+                .take_while(|&c| char_is_whitespace(c))
+                .count();
+            let mut trailing = slice.chars_at(end);
+            trailing.reverse();
+            let trailing = trailing.take_while(|c| c.is_whitespace()).count();
+            let inner_start = start + leading;
+            Range::new(inner_start, (end - trailing).max(inner_start))
+        }
+        TextObject::Movement => unreachable!(),
+    }
+}
+
+/// Finds the first match of `regex` on the line of the cursor which contains or follows the
+/// cursor. `filter` maps a match to the byte range of the object within the line, or rejects it.
+/// Returns the char range of the object.
+fn find_on_cursor_line(
+    slice: RopeSlice,
+    range: Range,
+    regex: &Regex,
+    filter: impl Fn(&str, regex::Captures) -> Option<std::ops::Range<usize>>,
+) -> Option<Range> {
+    let cursor = range.cursor(slice);
+    let line = slice.char_to_line(cursor);
+    let line_start = slice.line_to_char(line);
+    let text = slice.line(line).to_string();
+    let cursor_byte = text
+        .char_indices()
+        .nth(cursor - line_start)
+        .map_or(text.len(), |(i, _)| i);
+
+    let object = regex
+        .captures_iter(&text)
+        .filter_map(|captures| filter(&text, captures))
+        .find(|object| object.end > cursor_byte)?;
+    let to_char = |byte: usize| line_start + text[..byte].chars().count();
+    Some(Range::new(to_char(object.start), to_char(object.end)))
+}
+
+/// Selects the number under or after the cursor on the current line. Decimal numbers with a
+/// fraction and exponent as well as hexadecimal, octal and binary numbers are recognized.
+///
+/// `Around` also selects the sign and a unit or type suffix, like in `-12px` or `10u32`.
+pub fn textobject_number(slice: RopeSlice, range: Range, textobject: TextObject) -> Range {
+    static NUMBER: Lazy<Regex> = Lazy::new(|| {
+        Regex::new(
+            r"(?x)
+            (?P<sign>[-+])?
+            (?P<number>
+                0[xX][0-9a-fA-F](?:_?[0-9a-fA-F])*
+                | 0[oO][0-7](?:_?[0-7])*
+                | 0[bB][01](?:_?[01])*
+                | \d(?:_?\d)*(?:\.\d(?:_?\d)*)?(?:[eE][-+]?\d+)?
+            )
+            (?P<suffix>[a-zA-Z%][a-zA-Z0-9_]*)?",
+        )
+        .unwrap()
+    });
+
+    find_on_cursor_line(slice, range, &NUMBER, |text, captures| {
+        let number = captures.name("number")?;
+        let before = |i: usize| text[..i].chars().next_back();
+        // Digits in identifiers like `utf8` are not numbers.
+        if before(number.start()).is_some_and(|c| c.is_alphanumeric() || c == '_') {
+            return None;
+        }
+        match textobject {
+            TextObject::Inside => Some(number.range()),
+            _ => {
+                // A sign directly after an operand is a binary operator like in `a-1`.
+                let start = captures
+                    .name("sign")
+                    .filter(|sign| {
+                        before(sign.start())
+                            .is_none_or(|c| !(c.is_alphanumeric() || ")]_".contains(c)))
+                    })
+                    .map_or(number.start(), |sign| sign.start());
+                let end = captures.get(0).unwrap().end();
+                Some(start..end)
+            }
+        }
+    })
+    .unwrap_or(range)
+}
+
+/// Selects the URL under or after the cursor on the current line. Trailing punctuation and
+/// unbalanced closing brackets are not considered part of the URL.
+///
+/// `Around` also selects brackets or quotes which directly enclose the URL, like in
+/// `<https://example.com>`.
+pub fn textobject_url(slice: RopeSlice, range: Range, textobject: TextObject) -> Range {
+    static URL: Lazy<Regex> =
+        Lazy::new(|| Regex::new(r#"\b[a-zA-Z][a-zA-Z0-9+.-]*://[^\s<>"'`]+"#).unwrap());
+
+    find_on_cursor_line(slice, range, &URL, |text, captures| {
+        let url = captures.get(0)?;
+        let mut end = url.end();
+        loop {
+            let url = &text[url.start()..end];
+            let trim = match url.chars().next_back()? {
+                '.' | ',' | ';' | ':' | '!' | '?' => true,
+                close @ (')' | ']' | '}') => {
+                    let open = match close {
+                        ')' => '(',
+                        ']' => '[',
+                        _ => '{',
+                    };
+                    url.matches(close).count() > url.matches(open).count()
+                }
+                _ => false,
+            };
+            if !trim {
+                break;
+            }
+            end -= 1;
+        }
+        let (start, end) = (url.start(), end);
+        if textobject == TextObject::Inside {
+            return Some(start..end);
+        }
+        let enclosing = (
+            text[..start].chars().next_back(),
+            text[end..].chars().next(),
+        );
+        match enclosing {
+            (Some('<'), Some('>'))
+            | (Some('('), Some(')'))
+            | (Some('['), Some(']'))
+            | (Some('"'), Some('"'))
+            | (Some('\''), Some('\''))
+            | (Some('`'), Some('`')) => Some(start - 1..end + 1),
+            _ => Some(start..end),
+        }
+    })
+    .unwrap_or(range)
+}
+
+/// Selects the file path under the cursor, or the first path after the cursor on the current line
+/// which contains a directory separator.
+///
+/// `Around` also selects a `:line` or `:line:column` suffix, like in `src/main.rs:12:5`.
+pub fn textobject_path(slice: RopeSlice, range: Range, textobject: TextObject) -> Range {
+    static POSITION: Lazy<Regex> = Lazy::new(|| Regex::new(r"^:\d+(?::\d+)?").unwrap());
+
+    let cursor = range.cursor(slice);
+    let line = slice.char_to_line(cursor);
+    let line_slice = slice.line(line);
+    let line_start = slice.line_to_char(line);
+    let cursor_byte = slice.char_to_byte(cursor) - slice.line_to_byte(line);
+
+    let Some(path) = find_paths(line_slice, true)
+        .find(|path| path.contains(&cursor_byte))
+        .or_else(|| find_paths(line_slice, false).find(|path| path.end > cursor_byte))
+    else {
+        return range;
+    };
+    let mut end = path.end;
+    if textobject == TextObject::Around {
+        let rest = line_slice.byte_slice(end..).to_string();
+        if let Some(position) = POSITION.find(&rest) {
+            end += position.end();
+        }
+    }
+    Range::new(
+        line_start + line_slice.byte_to_char(path.start),
+        line_start + line_slice.byte_to_char(end),
+    )
+}
+
+/// Selects the entire buffer. `Inside` excludes leading and trailing whitespace.
+pub fn textobject_buffer(slice: RopeSlice, textobject: TextObject) -> Range {
+    let len = slice.len_chars();
+    match textobject {
+        TextObject::Around => Range::new(0, len),
+        TextObject::Inside => {
+            let start = slice.chars().take_while(|c| c.is_whitespace()).count();
+            if start == len {
+                return Range::new(0, len);
+            }
+            let mut chars = slice.chars_at(len);
+            chars.reverse();
+            let trailing = chars.take_while(|c| c.is_whitespace()).count();
+            Range::new(start, len - trailing)
+        }
+        TextObject::Movement => unreachable!(),
+    }
+}
+
 pub fn textobject_pair_surround(
     syntax: Option<&Syntax>,
     slice: RopeSlice,
@@ -253,6 +565,9 @@ fn textobject_pair_surround_impl(
 
 /// Transform the given range to select text objects based on tree-sitter.
 /// `object_name` is a query capture base name like "function", "class", etc.
+/// The smallest node captured as `object_name` which contains the cursor is selected, or the
+/// `count`th smallest with a `count` greater than one, so `2af` selects the function
+/// surrounding the function under the cursor.
 /// `slice_tree` is the tree-sitter node corresponding to given text slice.
 pub fn textobject_treesitter(
     slice: RopeSlice,
@@ -261,7 +576,7 @@ pub fn textobject_treesitter(
     object_name: &str,
     syntax: &Syntax,
     loader: &syntax::Loader,
-    _count: usize,
+    count: usize,
 ) -> Range {
     let root = syntax.tree().root_node();
     let textobject_query = loader.textobject_query(syntax.root_language());
@@ -269,10 +584,14 @@ pub fn textobject_treesitter(
         let byte_pos = slice.char_to_byte(range.cursor(slice));
 
         let capture_name = format!("{}.{}", object_name, textobject); // eg. function.inner
-        let node = textobject_query?
+        let mut nodes: Vec<_> = textobject_query?
             .capture_nodes(&capture_name, &root, slice)?
             .filter(|node| node.byte_range().contains(&byte_pos))
-            .min_by_key(|node| node.byte_range().len())?;
+            .collect();
+        nodes.sort_by_key(|node| node.byte_range().len());
+        // Captures spanning the same text, for example a node and its only child, count once.
+        nodes.dedup_by_key(|node| node.byte_range());
+        let node = nodes.get(count.saturating_sub(1)).or(nodes.last())?;
 
         let len = slice.len_bytes();
         let start_byte = node.start_byte();
@@ -587,4 +906,148 @@ fn test_textobject_surround() {
             }
         }
     }
+
+    fn assert_textobject(tests: &[(&str, &str)], textobject: impl Fn(RopeSlice, Range) -> Range) {
+        for (before, expected) in tests {
+            let (s, selection) = crate::test::print(before);
+            let text = Rope::from(s.as_str());
+            let selection = selection.transform(|r| textobject(text.slice(..), r));
+            let actual = crate::test::plain(s.as_ref(), &selection);
+            assert_eq!(actual, *expected, "\nbefore: `{:?}`", before);
+        }
+    }
+
+    #[test]
+    fn test_textobject_word_count() {
+        assert_textobject(&[("#[f|]#oo bar baz", "#[foo bar|]# baz")], |text, r| {
+            textobject_word(text, r, Inside, 2, false)
+        });
+        assert_textobject(&[("#[f|]#oo bar baz", "#[foo bar |]#baz")], |text, r| {
+            textobject_word(text, r, Around, 2, false)
+        });
+    }
+
+    #[test]
+    fn test_textobject_indentation_level() {
+        let text =
+            "fn main() {\n    if x {\n        #[a|]#\n        b\n\n        c\n    }\n    d\n}\n";
+        let tests = [
+            (
+                Inside,
+                1,
+                "fn main() {\n    if x {\n#[        a\n        b\n\n        c\n|]#    }\n    d\n}\n",
+            ),
+            (
+                Around,
+                1,
+                "fn main() {\n#[    if x {\n        a\n        b\n\n        c\n    }\n|]#    d\n}\n",
+            ),
+            (
+                Inside,
+                2,
+                "fn main() {\n#[    if x {\n        a\n        b\n\n        c\n    }\n    d\n|]#}\n",
+            ),
+            (
+                Around,
+                2,
+                "#[fn main() {\n    if x {\n        a\n        b\n\n        c\n    }\n    d\n}\n|]#",
+            ),
+        ];
+        for (textobject, count, expected) in tests {
+            assert_textobject(&[(text, expected)], |text, r| {
+                textobject_indentation_level(text, r, textobject, count, 4)
+            });
+        }
+
+        // Without a footer only the header is added and blank lines belong to the next block.
+        assert_textobject(
+            &[(
+                "if x:\n#[\n|]#    a\n    b\nc\n",
+                "#[if x:\n\n    a\n    b\n|]#c\n",
+            )],
+            |text, r| textobject_indentation_level(text, r, Around, 1, 4),
+        );
+    }
+
+    #[test]
+    fn test_textobject_line() {
+        let text = "  #[f|]#oo bar  \nbaz\n";
+        assert_textobject(&[(text, "  #[foo bar|]#  \nbaz\n")], |text, r| {
+            textobject_line(text, r, Inside, 1)
+        });
+        assert_textobject(&[(text, "#[  foo bar  \n|]#baz\n")], |text, r| {
+            textobject_line(text, r, Around, 1)
+        });
+        assert_textobject(&[(text, "#[  foo bar  \nbaz\n|]#")], |text, r| {
+            textobject_line(text, r, Around, 2)
+        });
+    }
+
+    #[test]
+    fn test_textobject_number() {
+        let inside = [
+            ("#[f|]#oo(-12.5px, 0xff)", "foo(-#[12.5|]#px, 0xff)"),
+            ("foo(-12.5px, 0x#[f|]#f)", "foo(-12.5px, #[0xff|]#)"),
+            ("utf8 #[x|]# 1_000e3", "utf8 x #[1_000e3|]#"),
+            ("no numbers #[h|]#ere", "no numbers #[h|]#ere"),
+        ];
+        assert_textobject(&inside, |text, r| textobject_number(text, r, Inside));
+        let around = [
+            ("#[f|]#oo(-12.5px, 0xff)", "foo(#[-12.5px|]#, 0xff)"),
+            ("a-#[1|]#", "a-#[1|]#"),
+            ("x = #[1|]#0u32;", "x = #[10u32|]#;"),
+        ];
+        assert_textobject(&around, |text, r| textobject_number(text, r, Around));
+    }
+
+    #[test]
+    fn test_textobject_url() {
+        let inside = [
+            (
+                "#[s|]#ee <https://example.com/a_(b)>.",
+                "see <#[https://example.com/a_(b)|]#>.",
+            ),
+            ("(#[h|]#ttps://x.org/y).", "(#[https://x.org/y|]#)."),
+        ];
+        assert_textobject(&inside, |text, r| textobject_url(text, r, Inside));
+        let around = [
+            (
+                "#[s|]#ee <https://example.com/a_(b)>.",
+                "see #[<https://example.com/a_(b)>|]#.",
+            ),
+            (
+                "go to #[h|]#ttps://x.org, now",
+                "go to #[https://x.org|]#, now",
+            ),
+        ];
+        assert_textobject(&around, |text, r| textobject_url(text, r, Around));
+    }
+
+    #[test]
+    fn test_textobject_path() {
+        let text = "error#[ |]#at src/main.rs:12:5: oops";
+        assert_textobject(
+            &[(text, "error at #[src/main.rs|]#:12:5: oops")],
+            |text, r| textobject_path(text, r, Inside),
+        );
+        assert_textobject(
+            &[(text, "error at #[src/main.rs:12:5|]#: oops")],
+            |text, r| textobject_path(text, r, Around),
+        );
+        assert_textobject(
+            &[("open #[R|]#EADME.md", "open #[README.md|]#")],
+            |text, r| textobject_path(text, r, Inside),
+        );
+    }
+
+    #[test]
+    fn test_textobject_buffer() {
+        let text = "\n  #[f|]#oo\nbar  \n\n";
+        assert_textobject(&[(text, "\n  #[foo\nbar|]#  \n\n")], |text, _| {
+            textobject_buffer(text, Inside)
+        });
+        assert_textobject(&[(text, "#[\n  foo\nbar  \n\n|]#")], |text, _| {
+            textobject_buffer(text, Around)
+        });
+    }
 }
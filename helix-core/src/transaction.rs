@@ -103,6 +103,34 @@ pub fn changes(&self) -> &[Operation] {
         &self.changes
     }
 
+    /// Rebuilds a changeset from its operations. `len` is the length of the document the
+    /// changes apply to and is only needed for changesets without any operations.
+    pub(crate) fn from_operations(operations: Vec<Operation>, len: usize) -> Self {
+        let mut changes = Self::with_capacity(operations.len());
+        for operation in operations {
+            match operation {
+                Operation::Retain(n) => changes.retain(n),
+                Operation::Delete(n) => changes.delete(n),
+                Operation::Insert(fragment) => changes.insert(fragment),
+            }
+        }
+        if changes.changes.is_empty() {
+            changes.len = len;
+            changes.len_after = len;
+        }
+        changes
+    }
+
+    /// The length of the document the changes apply to.
+    pub(crate) fn len(&self) -> usize {
+        self.len
+    }
+
+    /// The length of the document after the changes were applied.
+    pub(crate) fn len_after(&self) -> usize {
+        self.len_after
+    }
+
     // Changeset builder operations: delete/insert/retain
     pub(crate) fn delete(&mut self, n: usize) {
         use Operation::*;
@@ -1,5 +1,8 @@
+use std::ops::Range;
+
 use smartstring::{LazyCompact, SmartString};
 use textwrap::{Options, WordSplitter::NoHyphenation};
+use unicode_width::UnicodeWidthStr;
 
 /// Given a slice of text, return the text re-wrapped to fit it
 /// within the given width.
@@ -9,3 +12,207 @@ pub fn reflow_hard_wrap(text: &str, text_width: usize) -> SmartString<LazyCompac
         .word_separator(textwrap::WordSeparator::AsciiSpace);
     textwrap::refill(text, options).into()
 }
+
+/// The prefix of a line which is repeated when the line is wrapped: the indentation, comment
+/// tokens, `>` quotes and a list marker.
+#[derive(Debug, PartialEq, Eq)]
+pub struct LinePrefix<'a> {
+    /// The prefix of the line itself, including its list marker.
+    pub first: &'a str,
+    /// The prefix of the lines the line is wrapped into, with the list marker replaced by
+    /// spaces for a hanging indent.
+    pub rest: String,
+    /// Whether the prefix ends with a list marker like `-`, `*` or `1.`.
+    pub list_item: bool,
+}
+
+/// Detects the prefix of `line`. `comment_tokens` are the line comment tokens of the language.
+pub fn line_prefix<'a>(line: &'a str, comment_tokens: &[&str]) -> LinePrefix<'a> {
+    let skip_blanks = |pos: usize| line.len() - line[pos..].trim_start_matches([' ', '\t']).len();
+
+    let mut end = skip_blanks(0);
+    loop {
+        let rest = &line[end..];
+        let token = comment_tokens
+            .iter()
+            .filter(|token| !token.is_empty() && rest.starts_with(**token))
+            .max_by_key(|token| token.len())
+            .copied()
+            .or_else(|| rest.starts_with('>').then_some(">"));
+        match token {
+            Some(token) => end = skip_blanks(end + token.len()),
+            None => break,
+        }
+    }
+
+    let base = &line[..end];
+    match list_marker_len(&line[end..]) {
+        Some(len) => LinePrefix {
+            first: &line[..end + len],
+            rest: format!("{base}{}", " ".repeat(line[end..end + len].width())),
+            list_item: true,
+        },
+        None => LinePrefix {
+            first: base,
+            rest: base.to_string(),
+            list_item: false,
+        },
+    }
+}
+
+/// Returns the length of the list marker at the start of `text` including the spaces after it.
+fn list_marker_len(text: &str) -> Option<usize> {
+    let digits = text.len() - text.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    let marker = if digits > 0 {
+        text[digits..]
+            .starts_with(['.', ')'])
+            .then_some(digits + 1)?
+    } else {
+        text.starts_with(['-', '*', '+']).then_some(1)?
+    };
+    let spaces = text[marker..].len() - text[marker..].trim_start_matches(' ').len();
+    // A marker is followed by a space and the content of the item.
+    (spaces > 0 && marker + spaces < text.len()).then_some(marker + spaces)
+}
+
+/// Reflows `text` to fit within `text_width`, like [`reflow_hard_wrap`], while keeping the
+/// prefixes of comments, quotes and list items. Each paragraph is rewrapped with the prefix of
+/// its first line, lines which only consist of a prefix separate paragraphs.
+pub fn reflow(text: &str, text_width: usize, comment_tokens: &[&str]) -> String {
+    let line_ending = if text.contains("\r\n") { "\r\n" } else { "\n" };
+    let mut reflowed = String::with_capacity(text.len());
+    let mut paragraph: Option<(LinePrefix, String)> = None;
+
+    let flush = |reflowed: &mut String, paragraph: &mut Option<(LinePrefix, String)>| {
+        if let Some((prefix, content)) = paragraph.take() {
+            let options = Options::new(text_width)
+                .initial_indent(prefix.first)
+                .subsequent_indent(&prefix.rest)
+                .word_splitter(NoHyphenation)
+                .word_separator(textwrap::WordSeparator::AsciiSpace);
+            for line in textwrap::wrap(&content, options) {
+                reflowed.push_str(&line);
+                reflowed.push_str(line_ending);
+            }
+        }
+    };
+
+    for line in text.lines() {
+        let prefix = line_prefix(line, comment_tokens);
+        let content = line[prefix.first.len()..].trim();
+        if content.is_empty() {
+            flush(&mut reflowed, &mut paragraph);
+            reflowed.push_str(line.trim_end());
+            reflowed.push_str(line_ending);
+            continue;
+        }
+
+        match &mut paragraph {
+            Some((paragraph_prefix, paragraph_content))
+                if !prefix.list_item && prefix.first == paragraph_prefix.rest =>
+            {
+                paragraph_content.push(' ');
+                paragraph_content.push_str(content);
+            }
+            _ => {
+                flush(&mut reflowed, &mut paragraph);
+                paragraph = Some((prefix, content.to_string()));
+            }
+        }
+    }
+    flush(&mut reflowed, &mut paragraph);
+
+    if !text.ends_with('\n') {
+        reflowed.truncate(reflowed.len() - line_ending.len());
+    }
+    reflowed
+}
+
+/// Finds where to break `line` while typing so that the text before the cursor at byte
+/// `cursor` fits within `text_width`. Returns the byte range of the blanks to replace with a
+/// line break and the prefix of the new line.
+pub fn auto_wrap_break(
+    line: &str,
+    cursor: usize,
+    text_width: usize,
+    comment_tokens: &[&str],
+) -> Option<(Range<usize>, String)> {
+    if line[..cursor].width() <= text_width {
+        return None;
+    }
+    let prefix = line_prefix(line, comment_tokens);
+    let start = prefix.first.len().min(cursor);
+
+    let mut blanks = Vec::new();
+    let mut run: Option<usize> = None;
+    for (i, c) in line[start..cursor].char_indices() {
+        let i = start + i;
+        match (c == ' ' || c == '\t', run) {
+            (true, None) => run = Some(i),
+            (false, Some(run_start)) => {
+                blanks.push(run_start..i);
+                run = None;
+            }
+            _ => (),
+        }
+    }
+    // Blanks right before the cursor are not broken, the next word may still fit.
+    let last = blanks
+        .iter()
+        .rev()
+        .find(|blank| line[..blank.start].width() <= text_width)
+        .or_else(|| blanks.first())?;
+    Some((last.clone(), prefix.rest))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn reflow_prefixes() {
+        let text = "\
+/// A doc comment which is much too long for a single line.
+///
+/// - A list item which is long enough to be wrapped.
+///   Its continuation.
+/// 12. Numbered
+";
+        assert_eq!(
+            reflow(text, 30, &["//", "///"]),
+            "\
+/// A doc comment which is
+/// much too long for a single
+/// line.
+///
+/// - A list item which
+///   is long enough to
+///   be wrapped. Its
+///   continuation.
+/// 12. Numbered
+"
+        );
+
+        let quote = "> > a quoted\n> > text\n    indented code";
+        assert_eq!(
+            reflow(quote, 40, &[]),
+            "> > a quoted text\n    indented code"
+        );
+        assert_eq!(reflow("# one\r\n# two\r\n", 80, &["#"]), "# one two\r\n");
+    }
+
+    #[test]
+    fn auto_wrap() {
+        let line = "// some words here";
+        assert_eq!(auto_wrap_break(line, line.len(), 20, &["//"]), None);
+        assert_eq!(
+            auto_wrap_break(line, line.len(), 12, &["//"]),
+            Some((7..8, "// ".to_string()))
+        );
+        assert_eq!(
+            auto_wrap_break("- item text", 11, 8, &[]),
+            Some((6..7, "  ".to_string()))
+        );
+        assert_eq!(auto_wrap_break("averylongword", 13, 8, &[]), None);
+    }
+}
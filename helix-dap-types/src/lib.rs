@@ -332,6 +332,39 @@ pub struct Module {
     pub address_range: Option<String>,
 }
 
+#[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GotoTarget {
+    pub id: usize,
+    pub label: String,
+    pub line: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub column: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_line: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_column: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instruction_pointer_reference: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExceptionDetails {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub type_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub full_type_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub evaluate_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stack_trace: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub inner_exception: Option<Vec<ExceptionDetails>>,
+}
+
 fn from_number<'de, D>(deserializer: D) -> Result<String, D::Error>
 where
     D: Deserializer<'de>,
@@ -468,7 +501,7 @@ pub struct ConfigurationDoneArguments {}
     #[derive(Debug, Default, PartialEq, Eq, Clone, Deserialize, Serialize)]
     #[serde(rename_all = "camelCase")]
     pub struct SetBreakpointsArguments {
-        pub source: Source,
+        pub source: super::Source,
         #[serde(skip_serializing_if = "Option::is_none")]
         pub breakpoints: Option<Vec<SourceBreakpoint>>,
         // lines is deprecated
@@ -664,6 +697,82 @@ impl Request for Next {
         const COMMAND: &'static str = "next";
     }
 
+    #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct StepBackArguments {
+        pub thread_id: ThreadId,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub single_thread: Option<bool>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub granularity: Option<String>,
+    }
+
+    #[derive(Debug)]
+    pub enum StepBack {}
+
+    impl Request for StepBack {
+        type Arguments = StepBackArguments;
+        type Result = ();
+        const COMMAND: &'static str = "stepBack";
+    }
+
+    #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct ReverseContinueArguments {
+        pub thread_id: ThreadId,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub single_thread: Option<bool>,
+    }
+
+    #[derive(Debug)]
+    pub enum ReverseContinue {}
+
+    impl Request for ReverseContinue {
+        type Arguments = ReverseContinueArguments;
+        type Result = ();
+        const COMMAND: &'static str = "reverseContinue";
+    }
+
+    #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct GotoTargetsArguments {
+        pub source: super::Source,
+        pub line: usize,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub column: Option<usize>,
+    }
+
+    #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct GotoTargetsResponse {
+        pub targets: Vec<GotoTarget>,
+    }
+
+    #[derive(Debug)]
+    pub enum GotoTargets {}
+
+    impl Request for GotoTargets {
+        type Arguments = GotoTargetsArguments;
+        type Result = GotoTargetsResponse;
+        const COMMAND: &'static str = "gotoTargets";
+    }
+
+    #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct GotoArguments {
+        pub thread_id: ThreadId,
+        pub target_id: usize,
+    }
+
+    #[derive(Debug)]
+    pub enum Goto {}
+
+    impl Request for Goto {
+        type Arguments = GotoArguments;
+        type Result = ();
+        const COMMAND: &'static str = "goto";
+    }
+
     #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
     #[serde(rename_all = "camelCase")]
     pub struct PauseArguments {
@@ -717,6 +826,79 @@ impl Request for Evaluate {
         const COMMAND: &'static str = "evaluate";
     }
 
+    #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct SetVariableArguments {
+        pub variables_reference: usize,
+        pub name: String,
+        pub value: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub format: Option<ValueFormat>,
+    }
+
+    #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct SetVariableResponse {
+        pub value: String,
+        #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+        pub ty: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub variables_reference: Option<usize>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub named_variables: Option<usize>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub indexed_variables: Option<usize>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub memory_reference: Option<String>,
+    }
+
+    #[derive(Debug)]
+    pub enum SetVariable {}
+
+    impl Request for SetVariable {
+        type Arguments = SetVariableArguments;
+        type Result = SetVariableResponse;
+        const COMMAND: &'static str = "setVariable";
+    }
+
+    #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct SetExpressionArguments {
+        pub expression: String,
+        pub value: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub frame_id: Option<usize>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub format: Option<ValueFormat>,
+    }
+
+    #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct SetExpressionResponse {
+        pub value: String,
+        #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+        pub ty: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub presentation_hint: Option<VariablePresentationHint>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub variables_reference: Option<usize>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub named_variables: Option<usize>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub indexed_variables: Option<usize>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub memory_reference: Option<String>,
+    }
+
+    #[derive(Debug)]
+    pub enum SetExpression {}
+
+    impl Request for SetExpression {
+        type Arguments = SetExpressionArguments;
+        type Result = SetExpressionResponse;
+        const COMMAND: &'static str = "setExpression";
+    }
+
     #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
     #[serde(rename_all = "camelCase")]
     pub struct SetExceptionBreakpointsArguments {
@@ -741,6 +923,102 @@ impl Request for SetExceptionBreakpoints {
         const COMMAND: &'static str = "setExceptionBreakpoints";
     }
 
+    #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct ExceptionInfoArguments {
+        pub thread_id: ThreadId,
+    }
+
+    #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct ExceptionInfoResponse {
+        pub exception_id: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub description: Option<String>,
+        pub break_mode: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub details: Option<ExceptionDetails>,
+    }
+
+    #[derive(Debug)]
+    pub enum ExceptionInfo {}
+
+    impl Request for ExceptionInfo {
+        type Arguments = ExceptionInfoArguments;
+        type Result = ExceptionInfoResponse;
+        const COMMAND: &'static str = "exceptionInfo";
+    }
+
+    #[derive(Debug, Default, PartialEq, Eq, Clone, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct LoadedSourcesArguments {}
+
+    #[derive(Debug, Default, PartialEq, Eq, Clone, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct LoadedSourcesResponse {
+        pub sources: Vec<super::Source>,
+    }
+
+    #[derive(Debug)]
+    pub enum LoadedSources {}
+
+    impl Request for LoadedSources {
+        type Arguments = Option<LoadedSourcesArguments>;
+        type Result = LoadedSourcesResponse;
+        const COMMAND: &'static str = "loadedSources";
+    }
+
+    #[derive(Debug, Default, PartialEq, Eq, Clone, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct ModulesArguments {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub start_module: Option<usize>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub module_count: Option<usize>,
+    }
+
+    #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct ModulesResponse {
+        pub modules: Vec<Module>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub total_modules: Option<usize>,
+    }
+
+    #[derive(Debug)]
+    pub enum Modules {}
+
+    impl Request for Modules {
+        type Arguments = ModulesArguments;
+        type Result = ModulesResponse;
+        const COMMAND: &'static str = "modules";
+    }
+
+    #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct SourceArguments {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub source: Option<super::Source>,
+        pub source_reference: usize,
+    }
+
+    #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize)]
+    #[serde(rename_all = "camelCase")]
+    pub struct SourceResponse {
+        pub content: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub mime_type: Option<String>,
+    }
+
+    #[derive(Debug)]
+    pub enum Source {}
+
+    impl Request for Source {
+        type Arguments = SourceArguments;
+        type Result = SourceResponse;
+        const COMMAND: &'static str = "source";
+    }
+
     // Reverse Requests
 
     #[derive(Debug, Default, PartialEq, Eq, Clone, Deserialize, Serialize)]
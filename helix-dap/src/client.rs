@@ -43,9 +43,21 @@ pub struct Client {
     pub thread_id: Option<ThreadId>,
     /// Currently active frame for the current thread.
     pub active_frame: Option<usize>,
+    /// Details about the exceptions which caused threads to stop. Entries are removed when the
+    /// thread continues.
+    pub exceptions: HashMap<ThreadId, requests::ExceptionInfoResponse>,
+    /// Sources reported through `loadedSource` events.
+    pub loaded_sources: Vec<Source>,
+    /// Modules reported through `module` events.
+    pub modules: Vec<Module>,
     pub quirks: DebuggerQuirks,
     /// The config which was used to start this debugger.
     pub config: Option<DebugAdapterConfig>,
+    /// A human readable name for the debugging session, usually the name of the template
+    /// which was used to start it.
+    pub name: String,
+    /// The debugger which requested this session to be started, if any.
+    pub parent: Option<DebugAdapterId>,
 }
 
 impl Client {
@@ -88,11 +100,16 @@ pub fn streams(
             starting_request_args: None,
             socket: None,
             stack_frames: HashMap::new(),
+            exceptions: HashMap::new(),
+            loaded_sources: Vec::new(),
+            modules: Vec::new(),
             thread_states: HashMap::new(),
             thread_id: None,
             active_frame: None,
             quirks: DebuggerQuirks::default(),
             config: None,
+            name: String::new(),
+            parent: None,
         };
 
         tokio::spawn(Self::recv(id, server_rx, client_tx));
@@ -243,6 +260,7 @@ pub fn resume_application(&mut self) {
         if let Some(thread_id) = self.thread_id {
             self.thread_states.insert(thread_id, "running".to_string());
             self.stack_frames.remove(&thread_id);
+            self.exceptions.remove(&thread_id);
         }
         self.active_frame = None;
         self.thread_id = None;
@@ -401,22 +419,26 @@ pub fn restart(&self) -> impl Future<Output = Result<Value>> {
         self.call::<requests::Restart>(args)
     }
 
+    fn source_for_path(path: PathBuf) -> Source {
+        Source {
+            path: Some(path),
+            name: None,
+            source_reference: None,
+            presentation_hint: None,
+            origin: None,
+            sources: None,
+            adapter_data: None,
+            checksums: None,
+        }
+    }
+
     pub async fn set_breakpoints(
         &self,
         file: PathBuf,
         breakpoints: Vec<SourceBreakpoint>,
     ) -> Result<Option<Vec<Breakpoint>>> {
         let args = requests::SetBreakpointsArguments {
-            source: Source {
-                path: Some(file),
-                name: None,
-                source_reference: None,
-                presentation_hint: None,
-                origin: None,
-                sources: None,
-                adapter_data: None,
-                checksums: None,
-            },
+            source: Self::source_for_path(file),
             breakpoints: Some(breakpoints),
             source_modified: Some(false),
         };
@@ -504,6 +526,54 @@ pub fn next(&self, thread_id: ThreadId) -> impl Future<Output = Result<Value>> {
         self.call::<requests::Next>(args)
     }
 
+    pub fn step_back(&self, thread_id: ThreadId) -> impl Future<Output = Result<Value>> {
+        let args = requests::StepBackArguments {
+            thread_id,
+            single_thread: None,
+            granularity: None,
+        };
+
+        self.call::<requests::StepBack>(args)
+    }
+
+    pub fn reverse_continue(&self, thread_id: ThreadId) -> impl Future<Output = Result<Value>> {
+        let args = requests::ReverseContinueArguments {
+            thread_id,
+            single_thread: None,
+        };
+
+        self.call::<requests::ReverseContinue>(args)
+    }
+
+    pub async fn goto_targets(
+        &self,
+        file: PathBuf,
+        line: usize,
+        column: Option<usize>,
+    ) -> Result<Vec<GotoTarget>> {
+        let args = requests::GotoTargetsArguments {
+            source: Self::source_for_path(file),
+            line,
+            column,
+        };
+
+        let response = self.request::<requests::GotoTargets>(args).await?;
+        Ok(response.targets)
+    }
+
+    pub fn goto(
+        &self,
+        thread_id: ThreadId,
+        target_id: usize,
+    ) -> impl Future<Output = Result<Value>> {
+        let args = requests::GotoArguments {
+            thread_id,
+            target_id,
+        };
+
+        self.call::<requests::Goto>(args)
+    }
+
     pub fn pause(&self, thread_id: ThreadId) -> impl Future<Output = Result<Value>> {
         let args = requests::PauseArguments { thread_id };
 
@@ -525,6 +595,65 @@ pub async fn eval(
         self.request::<requests::Evaluate>(args).await
     }
 
+    pub fn set_variable(
+        &self,
+        variables_reference: usize,
+        name: String,
+        value: String,
+    ) -> impl Future<Output = Result<Value>> {
+        let args = requests::SetVariableArguments {
+            variables_reference,
+            name,
+            value,
+            format: None,
+        };
+
+        self.call::<requests::SetVariable>(args)
+    }
+
+    pub fn set_expression(
+        &self,
+        expression: String,
+        value: String,
+        frame_id: Option<usize>,
+    ) -> impl Future<Output = Result<Value>> {
+        let args = requests::SetExpressionArguments {
+            expression,
+            value,
+            frame_id,
+            format: None,
+        };
+
+        self.call::<requests::SetExpression>(args)
+    }
+
+    pub async fn exception_info(
+        &self,
+        thread_id: ThreadId,
+    ) -> Result<requests::ExceptionInfoResponse> {
+        let args = requests::ExceptionInfoArguments { thread_id };
+
+        self.request::<requests::ExceptionInfo>(args).await
+    }
+
+    pub fn loaded_sources(&self) -> impl Future<Output = Result<Value>> {
+        self.call::<requests::LoadedSources>(Some(requests::LoadedSourcesArguments {}))
+    }
+
+    pub fn modules(&self) -> impl Future<Output = Result<Value>> {
+        self.call::<requests::Modules>(requests::ModulesArguments::default())
+    }
+
+    /// Fetches the contents of a source which is not available locally.
+    pub fn source(&self, source: Source) -> impl Future<Output = Result<Value>> {
+        let args = requests::SourceArguments {
+            source_reference: source.source_reference.unwrap_or_default(),
+            source: Some(source),
+        };
+
+        self.call::<requests::Source>(args)
+    }
+
     pub fn set_exception_breakpoints(
         &self,
         filters: Vec<String>,
@@ -534,9 +663,22 @@ pub fn set_exception_breakpoints(
         self.call::<requests::SetExceptionBreakpoints>(args)
     }
 
+    /// Whether the currently selected thread of this debugger is paused.
+    pub fn is_paused(&self) -> bool {
+        self.thread_id.is_some()
+    }
+
     pub fn current_stack_frame(&self) -> Option<&StackFrame> {
         self.stack_frames
             .get(&self.thread_id?)?
             .get(self.active_frame?)
     }
+
+    /// The exception which stopped the current thread along with the frame which raised it.
+    pub fn current_exception(&self) -> Option<(&StackFrame, &requests::ExceptionInfoResponse)> {
+        let thread_id = self.thread_id?;
+        let exception = self.exceptions.get(&thread_id)?;
+        let frame = self.stack_frames.get(&thread_id)?.first()?;
+        Some((frame, exception))
+    }
 }
@@ -0,0 +1,145 @@
+//! Support for VS Code style `.vscode/launch.json` debug configurations.
+//!
+//! See <https://code.visualstudio.com/docs/editor/debugging#_launch-configurations>.
+
+use crate::Result;
+use helix_core::syntax::config::DebugTemplate;
+use helix_stdx::json::strip_jsonc;
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::path::{Path, PathBuf};
+
+/// Location of the launch configurations, relative to the workspace root.
+pub const LAUNCH_JSON_PATH: &str = ".vscode/launch.json";
+
+#[derive(Debug, Deserialize)]
+struct LaunchJson {
+    #[serde(default)]
+    configurations: Vec<Map<String, Value>>,
+}
+
+/// A single entry of the `configurations` array in `launch.json`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LaunchConfiguration {
+    pub name: String,
+    /// The debugger type, for example `lldb` or `pwa-node`.
+    pub ty: String,
+    /// Either `launch` or `attach`.
+    pub request: String,
+    /// The full configuration, including `type`, `request` and `name`. Adapters which are
+    /// shared with VS Code expect these to be passed through as part of the arguments.
+    pub args: Map<String, Value>,
+}
+
+impl LaunchConfiguration {
+    fn from_object(mut object: Map<String, Value>) -> Option<Self> {
+        // Platform specific properties override the top-level ones.
+        let platform = if cfg!(windows) {
+            "windows"
+        } else if cfg!(target_os = "macos") {
+            "osx"
+        } else {
+            "linux"
+        };
+        for key in ["windows", "osx", "linux"] {
+            if let Some(Value::Object(overrides)) = object.remove(key) {
+                if key == platform {
+                    object.extend(overrides);
+                }
+            }
+        }
+
+        let string = |key: &str| object.get(key)?.as_str().map(str::to_owned);
+        let name = string("name")?;
+        let ty = string("type")?;
+        let request = string("request")?;
+
+        Some(Self {
+            name,
+            ty,
+            request,
+            args: object,
+        })
+    }
+
+    /// Converts the configuration into a template which can be started like the templates
+    /// defined in `languages.toml`.
+    pub fn into_template(self) -> DebugTemplate {
+        DebugTemplate {
+            name: self.name,
+            request: self.request,
+            completion: Vec::new(),
+            args: self.args.into_iter().collect(),
+        }
+    }
+}
+
+/// Returns the path of the `launch.json` file within the given workspace.
+pub fn launch_json_path(workspace: &Path) -> PathBuf {
+    workspace.join(LAUNCH_JSON_PATH)
+}
+
+/// Reads the launch configurations of the given workspace.
+///
+/// Returns an empty list if the workspace has no `launch.json`. Entries which are missing a
+/// `name`, `type` or `request` are skipped.
+pub fn load(workspace: &Path) -> Result<Vec<LaunchConfiguration>> {
+    let path = launch_json_path(workspace);
+    let contents = match std::fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err.into()),
+    };
+    parse(&contents)
+}
+
+/// Parses the contents of a `launch.json` file.
+pub fn parse(contents: &str) -> Result<Vec<LaunchConfiguration>> {
+    let launch_json: LaunchJson = serde_json::from_str(&strip_jsonc(contents))?;
+    Ok(launch_json
+        .configurations
+        .into_iter()
+        .filter_map(LaunchConfiguration::from_object)
+        .collect())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_configurations() {
+        let input = r#"{
+            "version": "0.2.0",
+            "configurations": [
+                {
+                    "type": "lldb",
+                    "request": "launch",
+                    "name": "Debug",
+                    "program": "${workspaceFolder}/target/debug/hx",
+                    "linux": { "program": "linux" },
+                    "osx": { "program": "osx" },
+                    "windows": { "program": "windows" },
+                },
+                { "name": "missing type", "request": "launch" },
+            ],
+        }"#;
+        let configurations = parse(input).unwrap();
+        assert_eq!(configurations.len(), 1);
+
+        let configuration = &configurations[0];
+        assert_eq!(configuration.name, "Debug");
+        assert_eq!(configuration.ty, "lldb");
+        assert_eq!(configuration.request, "launch");
+        assert_eq!(configuration.args["type"], "lldb");
+        assert!(!configuration.args.contains_key("linux"));
+        let platform = if cfg!(windows) {
+            "windows"
+        } else if cfg!(target_os = "macos") {
+            "osx"
+        } else {
+            "linux"
+        };
+        assert_eq!(configuration.args["program"], platform);
+    }
+}
@@ -1,4 +1,6 @@
 mod client;
+pub mod launch_json;
+pub mod process;
 pub mod registry;
 mod transport;
 
@@ -0,0 +1,117 @@
+//! Enumeration of local processes, used to pick a process to attach the debugger to.
+
+use crate::Result;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// A process running on the local machine.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Process {
+    pub pid: u32,
+    /// The name of the user owning the process, or the numeric user id if it has no name.
+    pub user: String,
+    /// The full command line of the process. Kernel threads, which have no command line, use
+    /// their name in square brackets instead.
+    pub command: String,
+}
+
+/// Lists the processes visible in `/proc`, most recently started (highest pid) first.
+///
+/// The process of the editor itself is excluded.
+pub fn list() -> Result<Vec<Process>> {
+    let users = fs::read_to_string("/etc/passwd")
+        .map(|passwd| parse_passwd(&passwd))
+        .unwrap_or_default();
+    let own_pid = std::process::id();
+
+    let mut processes: Vec<_> = fs::read_dir("/proc")?
+        .filter_map(|entry| {
+            let entry = entry.ok()?;
+            let pid: u32 = entry.file_name().to_str()?.parse().ok()?;
+            if pid == own_pid {
+                return None;
+            }
+            // Processes may exit while they are being read, skip those.
+            read_process(&entry.path(), pid, &users)
+        })
+        .collect();
+    processes.sort_unstable_by(|a, b| b.pid.cmp(&a.pid));
+    Ok(processes)
+}
+
+fn read_process(dir: &Path, pid: u32, users: &HashMap<u32, String>) -> Option<Process> {
+    let status = fs::read_to_string(dir.join("status")).ok()?;
+    let uid = parse_uid(&status)?;
+    let user = users.get(&uid).cloned().unwrap_or_else(|| uid.to_string());
+
+    let cmdline = fs::read(dir.join("cmdline")).ok()?;
+    let command = match parse_cmdline(&cmdline) {
+        Some(command) => command,
+        None => {
+            let comm = fs::read_to_string(dir.join("comm")).ok()?;
+            format!("[{}]", comm.trim_end())
+        }
+    };
+
+    Some(Process { pid, user, command })
+}
+
+/// Extracts the real user id from the contents of `/proc/<pid>/status`.
+fn parse_uid(status: &str) -> Option<u32> {
+    status
+        .lines()
+        .find_map(|line| line.strip_prefix("Uid:"))?
+        .split_whitespace()
+        .next()?
+        .parse()
+        .ok()
+}
+
+/// Joins the nul separated arguments of `/proc/<pid>/cmdline`.
+fn parse_cmdline(cmdline: &[u8]) -> Option<String> {
+    let command = cmdline
+        .split(|&byte| byte == 0)
+        .filter(|arg| !arg.is_empty())
+        .map(String::from_utf8_lossy)
+        .collect::<Vec<_>>()
+        .join(" ");
+    (!command.is_empty()).then_some(command)
+}
+
+/// Maps user ids to user names using the contents of `/etc/passwd`.
+fn parse_passwd(passwd: &str) -> HashMap<u32, String> {
+    passwd
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split(':');
+            let name = fields.next()?;
+            let uid = fields.nth(1)?.parse().ok()?;
+            Some((uid, name.to_owned()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse_proc_files() {
+        let status =
+            "Name:\tbash\nUmask:\t0022\nState:\tS (sleeping)\nUid:\t1000\t1000\t1000\t1000\n";
+        assert_eq!(parse_uid(status), Some(1000));
+
+        assert_eq!(
+            parse_cmdline(b"/usr/bin/python3\0-m\0http.server\0"),
+            Some("/usr/bin/python3 -m http.server".to_owned())
+        );
+        assert_eq!(parse_cmdline(b""), None);
+
+        let users = parse_passwd(
+            "root:x:0:0:root:/root:/bin/bash\nalice:x:1000:1000::/home/alice:/bin/sh\n",
+        );
+        assert_eq!(users.get(&0).map(String::as_str), Some("root"));
+        assert_eq!(users.get(&1000).map(String::as_str), Some("alice"));
+    }
+}
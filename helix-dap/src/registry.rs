@@ -11,10 +11,9 @@
 /// plus showing the heirarcihical nature betweeen them
 pub struct Registry {
     inner: SlotMap<DebugAdapterId, Client>,
-    /// The active debugger client
+    /// The focused debugger client
     ///
-    /// TODO: You can have multiple active debuggers, so the concept of a single active debugger
-    /// may need to be changed
+    /// Multiple debuggers may be running at once but commands only act on the focused one.
     current_client_id: Option<DebugAdapterId>,
     /// A stream of incoming messages from all debuggers
     pub incoming: SelectAll<UnboundedReceiverStream<(DebugAdapterId, Payload)>>,
@@ -51,6 +50,7 @@ pub fn start_client(
             self.incoming.push(UnboundedReceiverStream::new(receiver));
 
             client.config = Some(config.clone());
+            client.name = config.name.clone();
             block_on(client.initialize(config.name.clone()))?;
             client.quirks = config.quirks.clone();
 
@@ -58,8 +58,21 @@ pub fn start_client(
         })
     }
 
+    /// Removes the client, moving the focus to another client if it was focused.
     pub fn remove_client(&mut self, id: DebugAdapterId) {
         self.inner.remove(id);
+        if self.current_client_id == Some(id) {
+            self.unfocus_client(id);
+        }
+    }
+
+    /// Returns an iterator over all running clients.
+    pub fn clients(&self) -> impl Iterator<Item = &Client> {
+        self.inner.values()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
     }
 
     pub fn get_client(&self, id: DebugAdapterId) -> Option<&Client> {
@@ -87,10 +100,40 @@ pub fn set_active_client(&mut self, id: DebugAdapterId) {
         }
     }
 
+    /// Focuses a client which stopped, unless another focused client is stopped as well so that
+    /// a session stopping in the background doesn't take the focus from the session which is
+    /// being debugged. Returns whether the client is focused.
+    pub fn focus_stopped_client(&mut self, id: DebugAdapterId) -> bool {
+        let keep_focus = self
+            .current_client_id
+            .filter(|&current| current != id)
+            .and_then(|current| self.get_client(current))
+            .is_some_and(Client::is_paused);
+        if !keep_focus {
+            self.set_active_client(id);
+        }
+        !keep_focus
+    }
+
+    pub fn active_client_id(&self) -> Option<DebugAdapterId> {
+        self.current_client_id
+    }
+
     pub fn unset_active_client(&mut self) {
         self.current_client_id = None;
     }
 
+    /// Moves the focus from the given client to another running client, preferring paused
+    /// clients. Unsets the active client if no other client is running.
+    pub fn unfocus_client(&mut self, id: DebugAdapterId) {
+        let mut others = self.inner.iter().filter(|(other, _)| *other != id);
+        self.current_client_id = others
+            .clone()
+            .find(|(_, client)| client.is_paused())
+            .or_else(|| others.next())
+            .map(|(other, _)| other);
+    }
+
     pub fn current_stack_frame(&self) -> Option<&StackFrame> {
         self.get_active_client()
             .and_then(|debugger| debugger.current_stack_frame())
@@ -112,3 +155,52 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{:?}", self.0)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ThreadId;
+
+    fn add_client(registry: &mut Registry) -> DebugAdapterId {
+        registry.inner.insert_with_key(|id| {
+            let (client, _) = Client::streams(
+                Box::new(tokio::io::empty()),
+                Box::new(tokio::io::sink()),
+                None,
+                id,
+                None,
+            )
+            .unwrap();
+            client
+        })
+    }
+
+    #[tokio::test]
+    async fn focus_stopped_client() {
+        let mut registry = Registry::new();
+        let a = add_client(&mut registry);
+        let b = add_client(&mut registry);
+
+        // Without a focused client the stopped client is focused.
+        assert!(registry.focus_stopped_client(a));
+        assert_eq!(registry.active_client_id(), Some(a));
+
+        // The focused client is running, so the stopped client takes the focus.
+        assert!(registry.focus_stopped_client(b));
+        assert_eq!(registry.active_client_id(), Some(b));
+
+        // The focused client is stopped and keeps the focus.
+        registry.get_client_mut(b).unwrap().thread_id = Some(ThreadId::default());
+        assert!(!registry.focus_stopped_client(a));
+        assert_eq!(registry.active_client_id(), Some(b));
+
+        // The focused client stopping again stays focused.
+        assert!(registry.focus_stopped_client(b));
+        assert_eq!(registry.active_client_id(), Some(b));
+
+        // Once the focused client is gone, the next stopped client is focused.
+        registry.remove_client(b);
+        assert!(registry.focus_stopped_client(a));
+        assert_eq!(registry.active_client_id(), Some(a));
+    }
+}
@@ -124,6 +124,33 @@ pub fn config_dir() -> PathBuf {
     path
 }
 
+/// The directories containing snippet files, ordered from highest to lowest priority: the
+/// `snippets` directory in the config directory followed by the one in each runtime directory.
+pub fn snippet_dirs() -> Vec<PathBuf> {
+    std::iter::once(config_dir())
+        .chain(RUNTIME_DIRS.iter().cloned())
+        .map(|dir| dir.join("snippets"))
+        .collect()
+}
+
+/// The directories containing Hunspell dictionaries, ordered from highest to lowest priority:
+/// the `dictionaries` directory in the config directory followed by the one in each runtime
+/// directory.
+pub fn dictionary_dirs() -> Vec<PathBuf> {
+    std::iter::once(config_dir())
+        .chain(RUNTIME_DIRS.iter().cloned())
+        .map(|dir| dir.join("dictionaries"))
+        .collect()
+}
+
+/// The personal word list for the dictionary of `language`, containing the words added by the
+/// user.
+pub fn personal_dictionary_file(language: &str) -> PathBuf {
+    config_dir()
+        .join("dictionaries")
+        .join(format!("{language}.txt"))
+}
+
 pub fn cache_dir() -> PathBuf {
     // TODO: allow env var override
     let strategy = choose_base_strategy().expect("Unable to find the cache directory!");
@@ -132,6 +159,28 @@ pub fn cache_dir() -> PathBuf {
     path
 }
 
+/// Directory for persistent state which is not configuration, like undo files. Falls back to the
+/// cache directory on platforms without a dedicated state directory.
+pub fn state_dir() -> PathBuf {
+    // TODO: allow env var override
+    let strategy = choose_base_strategy().expect("Unable to find the state directory!");
+    let mut path = strategy.state_dir().unwrap_or_else(|| strategy.cache_dir());
+    path.push("helix");
+    path
+}
+
+/// Writes a file in the state directory, creating its parent directories. The file is replaced
+/// atomically so that concurrently exiting editors never leave a partially written file behind.
+pub fn write_state_file(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(format!(".{}", std::process::id()));
+    std::fs::write(&tmp, contents)?;
+    std::fs::rename(&tmp, path)
+}
+
 pub fn config_file() -> PathBuf {
     CONFIG_FILE.get().map(|path| path.to_path_buf()).unwrap()
 }
@@ -0,0 +1,87 @@
+//! Functions for working with JSON files.
+
+/// Converts "JSON with comments" into plain JSON by removing line and block comments as well as
+/// trailing commas in objects and arrays.
+pub fn strip_jsonc(input: &str) -> String {
+    let mut output = String::with_capacity(input.len());
+    let mut chars = input.chars().peekable();
+    // The byte offset in `output` of a comma which may turn out to be a trailing comma.
+    let mut pending_comma = None;
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            '"' => {
+                pending_comma = None;
+                output.push(ch);
+                while let Some(ch) = chars.next() {
+                    output.push(ch);
+                    match ch {
+                        '\\' => output.extend(chars.next()),
+                        '"' => break,
+                        _ => (),
+                    }
+                }
+            }
+            '/' if chars.peek() == Some(&'/') => {
+                for ch in chars.by_ref() {
+                    if ch == '\n' {
+                        output.push(ch);
+                        break;
+                    }
+                }
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut prev = None;
+                for ch in chars.by_ref() {
+                    if prev == Some('*') && ch == '/' {
+                        break;
+                    }
+                    prev = Some(ch);
+                }
+            }
+            ',' => {
+                pending_comma = Some(output.len());
+                output.push(ch);
+            }
+            '}' | ']' => {
+                if let Some(offset) = pending_comma.take() {
+                    output.remove(offset);
+                }
+                output.push(ch);
+            }
+            ch if ch.is_whitespace() => output.push(ch),
+            ch => {
+                pending_comma = None;
+                output.push(ch);
+            }
+        }
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn strip_comments_and_trailing_commas() {
+        let input = concat!(
+            "{\n",
+            "  // line comment\n",
+            "  \"a\": \"http://x\", /* block\n",
+            "  comment */ \"b\": [1, 2,],\n",
+            "  \"c\": \"\\\"//\\\"\",\n",
+            "}",
+        );
+        let expected = concat!(
+            "{\n",
+            "  \n",
+            "  \"a\": \"http://x\",  \"b\": [1, 2],\n",
+            "  \"c\": \"\\\"//\\\"\"\n",
+            "}",
+        );
+        assert_eq!(strip_jsonc(input), expected);
+    }
+}
@@ -3,6 +3,7 @@
 
 pub mod env;
 pub mod faccess;
+pub mod json;
 pub mod path;
 pub mod range;
 pub mod rope;
@@ -1,6 +1,9 @@
 use arc_swap::{access::Map, ArcSwap};
 use futures_util::Stream;
-use helix_core::{diagnostic::Severity, pos_at_coords, syntax, Range, Selection};
+use helix_core::{
+    diagnostic::Severity, pos_at_coords, snippets::library::SnippetLibrary, syntax, Range,
+    Selection,
+};
 use helix_lsp::{
     lsp::{self, notification::Notification},
     util::lsp_range_to_range,
@@ -419,6 +422,8 @@ fn refresh_config(&mut self) {
             // the sake of locals highlighting.
             let lang_loader = helix_core::config::user_lang_loader()?;
             self.editor.syn_loader.store(Arc::new(lang_loader));
+            self.editor.snippets = SnippetLibrary::load(&helix_loader::snippet_dirs());
+            self.editor.dictionaries.clear();
             Self::load_configured_theme(
                 &mut self.editor,
                 &default_config,
@@ -1330,6 +1335,16 @@ pub async fn close(&mut self) -> Vec<anyhow::Error> {
             errs.push(err);
         }
 
+        if let Err(err) = self.editor.save_marks() {
+            log::error!("Error saving marks: {}", err);
+            errs.push(err.into());
+        }
+
+        if let Err(err) = self.editor.save_registers() {
+            log::error!("Error saving registers: {}", err);
+            errs.push(err.into());
+        }
+
         if self.editor.close_language_servers(None).await.is_err() {
             log::error!("Timed out waiting for language servers to shutdown");
             errs.push(anyhow::format_err!(
@@ -20,10 +20,12 @@
 pub use typed::*;
 
 use helix_core::{
-    char_idx_at_visual_offset,
+    block_selection::{BlockSelection, VirtualSpace},
+    case_conversion, char_idx_at_visual_offset,
     chars::char_is_word,
     command_line::{self, Args},
     comment,
+    diagnostic::{Diagnostic, DiagnosticProvider},
     doc_formatter::TextFormat,
     encoding, find_workspace,
     graphemes::{self, next_grapheme_boundary},
@@ -36,24 +38,28 @@
     object, pos_at_coords,
     regex::{self, Regex},
     search::{self, CharMatcher},
-    selection, surround,
+    selection,
+    snippets::{library::UserSnippet, ActiveSnippet, Snippet},
+    surround::{self, SurroundPair},
     syntax::config::{BlockCommentToken, LanguageServerFeature},
     text_annotations::{Overlay, TextAnnotations},
     textobject,
     unicode::width::UnicodeWidthChar,
-    visual_offset_from_block, Deletion, LineEnding, Position, Range, Rope, RopeReader, RopeSlice,
-    Selection, SmallVec, Syntax, Tendril, Transaction,
+    visual_offset_from_block, Change, Deletion, LineEnding, Position, Range, Rope, RopeReader,
+    RopeSlice, Selection, SmallVec, Syntax, Tendril, Transaction,
 };
 use helix_view::{
     document::{FormatterError, Mode, SCRATCH_BUFFER_NAME},
     editor::Action,
     expansion,
+    handlers::SpellingEvent,
     info::Info,
     input::KeyEvent,
     keyboard::KeyCode,
+    marks, register,
     theme::Style,
     tree,
-    view::View,
+    view::{ActiveBlockSelection, View},
     Document, DocumentId, Editor, ViewId,
 };
 
@@ -79,6 +85,7 @@
     future::Future,
     io::Read,
     num::NonZeroUsize,
+    sync::Arc,
 };
 
 use std::{
@@ -318,6 +325,10 @@ pub fn doc(&self) -> &str {
         extend_visual_line_down, "Extend down",
         copy_selection_on_next_line, "Copy selection on next line",
         copy_selection_on_prev_line, "Copy selection on previous line",
+        move_lines_up, "Move selected lines up",
+        move_lines_down, "Move selected lines down",
+        duplicate_selections_above, "Duplicate selections or their lines above",
+        duplicate_selections_below, "Duplicate selections or their lines below",
         move_next_word_start, "Move to start of next word",
         move_prev_word_start, "Move to start of previous word",
         move_next_word_end, "Move to end of next word",
@@ -359,6 +370,13 @@ pub fn doc(&self) -> &str {
         switch_case, "Switch (toggle) case",
         switch_to_uppercase, "Switch to uppercase",
         switch_to_lowercase, "Switch to lowercase",
+        switch_to_snake_case, "Switch to snake_case",
+        switch_to_screaming_snake_case, "Switch to SCREAMING_SNAKE_CASE",
+        switch_to_kebab_case, "Switch to kebab-case",
+        switch_to_camel_case, "Switch to camelCase",
+        switch_to_pascal_case, "Switch to PascalCase",
+        switch_to_title_case, "Switch to Title Case",
+        switch_to_dot_case, "Switch to dot.case",
         page_up, "Move page up",
         page_down, "Move page down",
         half_page_up, "Move half page up",
@@ -409,6 +427,9 @@ pub fn doc(&self) -> &str {
         code_action, "Perform code action",
         buffer_picker, "Open buffer picker",
         jumplist_picker, "Open jumplist picker",
+        mark_picker, "Open mark picker",
+        set_mark, "Set a named mark on the selections",
+        goto_mark, "Goto named mark",
         symbol_picker, "Open symbol picker",
         syntax_symbol_picker, "Open symbol picker from syntax information",
         lsp_or_syntax_symbol_picker, "Open symbol picker from LSP or syntax information",
@@ -427,6 +448,7 @@ pub fn doc(&self) -> &str {
         normal_mode, "Enter normal mode",
         select_mode, "Enter selection extend mode",
         exit_select_mode, "Exit selection mode",
+        select_block_mode, "Toggle rectangular block selection",
         goto_definition, "Goto definition",
         goto_declaration, "Goto declaration",
         add_newline_above, "Add newline above",
@@ -454,6 +476,10 @@ pub fn doc(&self) -> &str {
         goto_last_diag, "Goto last diagnostic",
         goto_next_diag, "Goto next diagnostic",
         goto_prev_diag, "Goto previous diagnostic",
+        goto_next_misspelling, "Goto next misspelled word",
+        goto_prev_misspelling, "Goto previous misspelled word",
+        suggest_spelling, "Pick a spelling suggestion for the word under the cursor",
+        add_to_dictionary, "Add the word under the cursor to the personal dictionary",
         goto_next_change, "Goto next change",
         goto_prev_change, "Goto previous change",
         goto_first_change, "Goto first change",
@@ -488,6 +514,8 @@ pub fn doc(&self) -> &str {
         earlier, "Move backward in history",
         later, "Move forward in history",
         commit_undo_checkpoint, "Commit changes to new checkpoint",
+        undo_tree, "Show the undo tree",
+        register_picker, "Open register picker",
         yank, "Yank selection",
         yank_to_clipboard, "Yank selections to clipboard",
         yank_to_primary_clipboard, "Yank selections to primary clipboard",
@@ -590,12 +618,19 @@ pub fn doc(&self) -> &str {
         dap_step_in, "Step in",
         dap_step_out, "Step out",
         dap_next, "Step to next",
+        dap_step_back, "Step back",
+        dap_reverse_continue, "Continue program execution backwards",
+        dap_run_to_cursor, "Continue program execution to the cursor",
+        dap_goto_cursor, "Move program execution to the cursor",
         dap_variables, "List variables",
         dap_terminate, "End debug session",
         dap_edit_condition, "Edit breakpoint condition on current line",
         dap_edit_log, "Edit breakpoint log message on current line",
         dap_switch_thread, "Switch current thread",
         dap_switch_stack_frame, "Switch stack frame",
+        dap_switch_session, "Switch debug session",
+        dap_loaded_sources, "List loaded sources",
+        dap_modules, "List loaded modules",
         dap_enable_exceptions, "Enable exception breakpoints",
         dap_disable_exceptions, "Disable exception breakpoints",
         shell_pipe, "Pipe selections through shell command",
@@ -614,9 +649,68 @@ pub fn doc(&self) -> &str {
         extend_to_word, "Extend to a two-character label",
         goto_next_tabstop, "Goto next snippet placeholder",
         goto_prev_tabstop, "Goto next snippet placeholder",
+        snippet_picker, "Open snippet picker",
         rotate_selections_first, "Make the first selection your primary one",
         rotate_selections_last, "Make the last selection your primary one",
     );
+
+    /// Commands which only move the cursor, extending the selections in select mode. Block
+    /// selections follow the cursor for these commands.
+    pub const MOVEMENT_COMMANDS: &'static [Self] = &[
+        Self::move_char_left,
+        Self::move_char_right,
+        Self::move_line_up,
+        Self::move_line_down,
+        Self::move_visual_line_up,
+        Self::move_visual_line_down,
+        Self::extend_char_left,
+        Self::extend_char_right,
+        Self::extend_line_up,
+        Self::extend_line_down,
+        Self::extend_visual_line_up,
+        Self::extend_visual_line_down,
+        Self::extend_next_word_start,
+        Self::extend_prev_word_start,
+        Self::extend_next_word_end,
+        Self::extend_prev_word_end,
+        Self::extend_next_long_word_start,
+        Self::extend_prev_long_word_start,
+        Self::extend_next_long_word_end,
+        Self::extend_prev_long_word_end,
+        Self::extend_till_char,
+        Self::extend_next_char,
+        Self::extend_till_prev_char,
+        Self::extend_prev_char,
+        Self::extend_to_line_start,
+        Self::extend_to_line_end,
+        Self::extend_to_column,
+        Self::extend_to_file_start,
+        Self::extend_to_last_line,
+        Self::goto_line_start,
+        Self::goto_line_end,
+        Self::goto_first_nonwhitespace,
+        Self::goto_file_start,
+        Self::goto_file_end,
+        Self::goto_last_line,
+        Self::page_up,
+        Self::page_down,
+        Self::half_page_up,
+        Self::half_page_down,
+        Self::page_cursor_up,
+        Self::page_cursor_down,
+        Self::page_cursor_half_up,
+        Self::page_cursor_half_down,
+    ];
+
+    /// Whether the command is one of [`Self::MOVEMENT_COMMANDS`].
+    pub fn is_movement(&self) -> bool {
+        match self {
+            Self::Static { name, .. } => Self::MOVEMENT_COMMANDS
+                .iter()
+                .any(|command| command.name() == *name),
+            Self::Typable { .. } | Self::Macro { .. } => false,
+        }
+    }
 }
 
 impl fmt::Debug for MappableCommand {
@@ -1820,6 +1914,67 @@ fn switch_to_lowercase(cx: &mut Context) {
     });
 }
 
+/// Converts the words of each selection with `convert`. Leading and trailing characters which
+/// are not part of a word, like whitespace selected by `w`, are kept.
+fn convert_case_impl<F>(cx: &mut Context, convert: F)
+where
+    F: Fn(&str, &mut Tendril),
+{
+    switch_case_impl(cx, |string| {
+        let string = Cow::from(string);
+        let start = string.find(char::is_alphanumeric).unwrap_or(string.len());
+        let end = string.rfind(char::is_alphanumeric).map_or(start, |i| {
+            i + string[i..].chars().next().unwrap().len_utf8()
+        });
+        let mut text = Tendril::from(&string[..start]);
+        convert(&string[start..end], &mut text);
+        text.push_str(&string[end..]);
+        text
+    });
+}
+
+fn switch_to_snake_case(cx: &mut Context) {
+    convert_case_impl(cx, |text, buf| {
+        case_conversion::to_snake_case_with(text.chars(), buf)
+    });
+}
+
+fn switch_to_screaming_snake_case(cx: &mut Context) {
+    convert_case_impl(cx, |text, buf| {
+        case_conversion::to_screaming_snake_case_with(text.chars(), buf)
+    });
+}
+
+fn switch_to_kebab_case(cx: &mut Context) {
+    convert_case_impl(cx, |text, buf| {
+        case_conversion::to_kebab_case_with(text.chars(), buf)
+    });
+}
+
+fn switch_to_camel_case(cx: &mut Context) {
+    convert_case_impl(cx, |text, buf| {
+        case_conversion::to_camel_case_words_with(text.chars(), buf)
+    });
+}
+
+fn switch_to_pascal_case(cx: &mut Context) {
+    convert_case_impl(cx, |text, buf| {
+        case_conversion::to_pascal_case_words_with(text.chars(), buf)
+    });
+}
+
+fn switch_to_title_case(cx: &mut Context) {
+    convert_case_impl(cx, |text, buf| {
+        case_conversion::to_title_case_with(text.chars(), buf)
+    });
+}
+
+fn switch_to_dot_case(cx: &mut Context) {
+    convert_case_impl(cx, |text, buf| {
+        case_conversion::to_dot_case_with(text.chars(), buf)
+    });
+}
+
 pub fn scroll(cx: &mut Context, offset: usize, direction: Direction, sync_cursor: bool) {
     use Direction::*;
     let config = cx.editor.config();
@@ -2073,6 +2228,258 @@ fn copy_selection_on_next_line(cx: &mut Context) {
     copy_selection_on_line(cx, Direction::Forward)
 }
 
+fn move_lines_up(cx: &mut Context) {
+    move_lines_impl(cx, Direction::Backward)
+}
+
+fn move_lines_down(cx: &mut Context) {
+    move_lines_impl(cx, Direction::Forward)
+}
+
+/// Moves the lines of the selections `count` lines past their neighbors and reindents them for
+/// their new position. Selections on adjacent lines move as one block, and no block moves past
+/// another one.
+fn move_lines_impl(cx: &mut Context, direction: Direction) {
+    let count = cx.count();
+    let loader = cx.editor.syn_loader.load();
+    let (view, doc) = current!(cx.editor);
+    let text = doc.text().slice(..);
+    let selection = doc.selection(view.id);
+    let line_ending = doc.line_ending.as_str();
+
+    // Ignore the empty line after a trailing line ending.
+    let last_line = text.len_lines().saturating_sub(
+        if text.len_lines() > 1 && text.line(text.len_lines() - 1).len_chars() == 0 {
+            2
+        } else {
+            1
+        },
+    );
+    let blocks: Vec<_> = selection
+        .line_ranges(text)
+        .map(|(start, end)| (start.min(last_line), end.min(last_line)))
+        .collect();
+    let (first, last) = (blocks[0], blocks[blocks.len() - 1]);
+    let count = match direction {
+        Direction::Backward => count.min(first.0),
+        Direction::Forward => count.min(last_line - last.1),
+    };
+    if count == 0 {
+        return;
+    }
+
+    // The lines between `lo` and `hi` are reordered: `order[i]` is the line which ends up at
+    // `lo + i`.
+    let (lo, hi) = match direction {
+        Direction::Backward => (first.0 - count, last.1),
+        Direction::Forward => (first.0, last.1 + count),
+    };
+    let mut order: Vec<usize> = (lo..=hi).collect();
+    let move_block = |order: &mut Vec<usize>, &(start, end): &(usize, usize)| {
+        let block: Vec<_> = order.drain(start - lo..=end - lo).collect();
+        let index = match direction {
+            Direction::Backward => start - lo - count,
+            Direction::Forward => start - lo + count,
+        };
+        order.splice(index..index, block);
+    };
+    match direction {
+        Direction::Backward => blocks
+            .iter()
+            .for_each(|block| move_block(&mut order, block)),
+        Direction::Forward => blocks
+            .iter()
+            .rev()
+            .for_each(|block| move_block(&mut order, block)),
+    }
+
+    // The content of each line along with the lengths of its old and new indentation if it was
+    // reindented.
+    let mut lines: Vec<(String, usize, usize)> = (lo..=hi)
+        .map(|line| {
+            let content = text.slice(text.line_to_char(line)..line_end_char_index(&text, line));
+            (content.to_string(), 0, 0)
+        })
+        .collect();
+    let is_blank = |line: usize| text.line(line).chars().all(char::is_whitespace);
+    for &(start, end) in &blocks {
+        let index = order.iter().position(|&line| line == start).unwrap();
+        let line_before = order[..index]
+            .iter()
+            .rev()
+            .copied()
+            .chain((0..lo).rev())
+            .find(|&line| !is_blank(line));
+        let Some(first_line) = (start..=end).find(|&line| !is_blank(line)) else {
+            continue;
+        };
+        let Some(new_indent) = line_before.and_then(|line_before| {
+            indent::indent_for_moved_lines(
+                &loader,
+                doc.syntax(),
+                &doc.config.load().indent_heuristic,
+                &doc.indent_style,
+                doc.tab_width(),
+                text,
+                line_before,
+            )
+        }) else {
+            continue;
+        };
+        let content = &lines[first_line - lo].0;
+        let old_indent = content[..content.len() - content.trim_start().len()].to_string();
+        if old_indent == new_indent {
+            continue;
+        }
+        for line in start..=end {
+            let (content, old_len, new_len) = &mut lines[line - lo];
+            if let Some(rest) = content.strip_prefix(old_indent.as_str()) {
+                if !rest.trim().is_empty() {
+                    *content = format!("{new_indent}{rest}");
+                    *old_len = old_indent.chars().count();
+                    *new_len = new_indent.chars().count();
+                }
+            }
+        }
+    }
+
+    let mut new_text = String::new();
+    for (i, &line) in order.iter().enumerate() {
+        if i > 0 {
+            new_text.push_str(line_ending);
+        }
+        new_text.push_str(&lines[line - lo].0);
+    }
+    let transaction = Transaction::change(
+        doc.text(),
+        std::iter::once((
+            text.line_to_char(lo),
+            line_end_char_index(&text, hi),
+            Some(new_text.into()),
+        )),
+    );
+
+    // Map each position to the same column of its line at the line's new place.
+    let mut new_doc = doc.text().clone();
+    transaction.apply(&mut new_doc);
+    let mut new_index = vec![0; order.len()];
+    for (i, &line) in order.iter().enumerate() {
+        new_index[line - lo] = i;
+    }
+    let map_pos = |pos: usize| {
+        let line = text.char_to_line(pos);
+        let col = pos - text.line_to_char(line);
+        let (line, col) = if (lo..=hi).contains(&line) {
+            let (_, old_len, new_len) = lines[line - lo];
+            let col = if col >= old_len {
+                col - old_len + new_len
+            } else {
+                col.min(new_len)
+            };
+            (lo + new_index[line - lo], col)
+        } else {
+            (line, col)
+        };
+        (new_doc.line_to_char(line) + col).min(new_doc.len_chars())
+    };
+    let selection = selection.clone().transform(|range| {
+        let from = map_pos(range.from());
+        // Map the last selected char so that a selection ending with a line ending keeps it.
+        let to = if range.is_empty() {
+            from
+        } else {
+            map_pos(range.to() - 1) + 1
+        };
+        if range.anchor <= range.head {
+            Range::new(from, to)
+        } else {
+            Range::new(to, from)
+        }
+    });
+
+    doc.apply(&transaction.with_selection(selection), view.id);
+}
+
+fn duplicate_selections_above(cx: &mut Context) {
+    duplicate_selections_impl(cx, Direction::Backward)
+}
+
+fn duplicate_selections_below(cx: &mut Context) {
+    duplicate_selections_impl(cx, Direction::Forward)
+}
+
+/// Duplicates the selected text `count` times above or below and selects the copy which is
+/// furthest in that direction. Cursors and selections of whole lines duplicate their lines.
+fn duplicate_selections_impl(cx: &mut Context, direction: Direction) {
+    let count = cx.count();
+    let (view, doc) = current!(cx.editor);
+    let text = doc.text().slice(..);
+    let selection = doc.selection(view.id);
+    let line_ending = doc.line_ending.as_str();
+    let is_line_start = |pos: usize| pos == text.line_to_char(text.char_to_line(pos));
+
+    let mut changes: Vec<Change> = Vec::with_capacity(selection.len());
+    let mut ranges = SmallVec::with_capacity(selection.len());
+    // The number of chars inserted before the current range.
+    let mut offset = 0;
+    // The last line of the previously duplicated lines and the shift of the ranges on them.
+    let mut prev_lines: Option<(usize, usize)> = None;
+    let shift = |range: &Range, shift: usize| Range::new(range.anchor + shift, range.head + shift);
+    for range in selection {
+        if let Some((_, lines_shift)) =
+            prev_lines.filter(|&(end, _)| text.char_to_line(range.from()) <= end)
+        {
+            ranges.push(shift(range, lines_shift));
+            continue;
+        }
+
+        let line_wise = range.len() <= 1
+            || (is_line_start(range.from())
+                && (is_line_start(range.to()) || range.to() == text.len_chars()));
+        let (pos, copy, range_shift) = if line_wise {
+            let (start, end) = range.line_range(text);
+            let content = text.slice(text.line_to_char(start)..line_end_char_index(&text, end));
+            let len = content.len_chars() + line_ending.chars().count();
+            let (pos, copy, range_shift) = match direction {
+                Direction::Forward => (
+                    line_end_char_index(&text, end),
+                    format!("{line_ending}{content}"),
+                    count * len,
+                ),
+                Direction::Backward => (
+                    text.line_to_char(start),
+                    format!("{content}{line_ending}"),
+                    0,
+                ),
+            };
+            prev_lines = Some((end, offset + range_shift));
+            (pos, copy, range_shift)
+        } else {
+            let content = range.fragment(text);
+            let (pos, range_shift) = match direction {
+                Direction::Forward => (range.to(), count * content.chars().count()),
+                Direction::Backward => (range.from(), 0),
+            };
+            prev_lines = None;
+            (pos, content.into_owned(), range_shift)
+        };
+        if changes.last().is_some_and(|&(last, _, _)| pos < last) {
+            ranges.push(shift(range, offset));
+            continue;
+        }
+
+        let copy = copy.repeat(count);
+        ranges.push(shift(range, offset + range_shift));
+        offset += copy.chars().count();
+        changes.push((pos, pos, Some(copy.into())));
+    }
+
+    let selection = Selection::new(ranges, selection.primary_index());
+    let transaction =
+        Transaction::change(doc.text(), changes.into_iter()).with_selection(selection);
+    doc.apply(&transaction, view.id);
+}
+
 fn select_all(cx: &mut Context) {
     let (view, doc) = current!(cx.editor);
 
@@ -3008,6 +3415,7 @@ fn enter_insert_mode(cx: &mut Context) {
 
 // inserts at the start of each selection
 fn insert_mode(cx: &mut Context) {
+    pad_block_selection(cx, false);
     enter_insert_mode(cx);
     let (view, doc) = current!(cx.editor);
 
@@ -3027,6 +3435,7 @@ fn insert_mode(cx: &mut Context) {
 
 // inserts at the end of each selection
 fn append_mode(cx: &mut Context) {
+    pad_block_selection(cx, true);
     enter_insert_mode(cx);
     let (view, doc) = current!(cx.editor);
     doc.restore_cursor = true;
@@ -3329,6 +3738,172 @@ struct JumpMeta {
     cx.push_layer(Box::new(overlaid(picker)));
 }
 
+struct MarkMeta {
+    name: char,
+    /// The open document the mark is in, `None` for global marks in files which are not open.
+    id: Option<DocumentId>,
+    path: Option<PathBuf>,
+    line: usize,
+    text: String,
+}
+
+/// The marks of the current document and the global marks of all other files.
+fn mark_metas(editor: &Editor) -> Vec<MarkMeta> {
+    let current = doc!(editor).id();
+    let mut metas: Vec<_> = editor
+        .documents()
+        .flat_map(|doc| {
+            doc.marks()
+                .iter()
+                .filter(move |(name, _)| doc.id() == current || marks::is_global(**name))
+                .map(move |(name, selection)| {
+                    let text = doc.text().slice(..);
+                    let line = selection.primary().cursor_line(text);
+                    MarkMeta {
+                        name: *name,
+                        id: Some(doc.id()),
+                        path: doc.path().cloned(),
+                        line,
+                        text: text.line(line).to_string().trim().to_string(),
+                    }
+                })
+        })
+        .collect();
+
+    // The contents of files which are not open are only shown in the preview of the picker.
+    metas.extend(editor.marks.iter().map(|(name, path, mark)| MarkMeta {
+        name,
+        id: None,
+        path: Some(path.to_path_buf()),
+        line: mark.line,
+        text: String::new(),
+    }));
+
+    metas.sort_by_key(|meta| meta.name);
+    metas
+}
+
+fn mark_picker(cx: &mut Context) {
+    let columns = [
+        ui::PickerColumn::new("mark", |item: &MarkMeta, _| item.name.to_string().into()),
+        ui::PickerColumn::new("path", |item: &MarkMeta, _| {
+            let path = item
+                .path
+                .as_deref()
+                .map(helix_stdx::path::get_relative_path);
+            path.as_deref()
+                .and_then(Path::to_str)
+                .unwrap_or(SCRATCH_BUFFER_NAME)
+                .to_string()
+                .into()
+        }),
+        ui::PickerColumn::new("line", |item: &MarkMeta, _| {
+            (item.line + 1).to_string().into()
+        }),
+        ui::PickerColumn::new("contents", |item: &MarkMeta, _| item.text.as_str().into()),
+    ];
+
+    let picker = Picker::new(
+        columns,
+        1, // path
+        mark_metas(cx.editor),
+        (),
+        |cx, meta, action| {
+            if let Err(err) = goto_mark_impl(cx.editor, meta.name, action) {
+                cx.editor.set_error(err.to_string());
+            }
+        },
+    )
+    .with_preview(|_editor, meta| {
+        let line = Some((meta.line, meta.line));
+        match (meta.id, &meta.path) {
+            (Some(id), _) => Some((id.into(), line)),
+            (None, Some(path)) => Some((path.as_path().into(), line)),
+            (None, None) => None,
+        }
+    });
+    cx.push_layer(Box::new(overlaid(picker)));
+}
+
+fn set_mark(cx: &mut Context) {
+    cx.on_next_key(move |cx, event| {
+        let Some(name) = event.char() else {
+            return;
+        };
+        if marks::is_local(name) || marks::is_global(name) {
+            cx.editor.set_mark(name);
+            cx.editor.set_status(format!("Set mark '{name}'"));
+        } else {
+            cx.editor.set_error(format!("Invalid mark '{name}'"));
+        }
+    })
+}
+
+fn goto_mark(cx: &mut Context) {
+    let body: Vec<_> = mark_metas(cx.editor)
+        .into_iter()
+        .map(|meta| {
+            let path = meta
+                .path
+                .as_deref()
+                .map(helix_stdx::path::get_relative_path);
+            let path = path
+                .as_deref()
+                .and_then(Path::to_str)
+                .unwrap_or(SCRATCH_BUFFER_NAME);
+            (meta.name.to_string(), format!("{path}:{}", meta.line + 1))
+        })
+        .collect();
+    cx.editor.autoinfo = Some(Info::new("Goto mark", &body));
+    cx.on_next_key(move |cx, event| {
+        cx.editor.autoinfo = None;
+        let Some(name) = event.char() else {
+            return;
+        };
+        if let Err(err) = goto_mark_impl(cx.editor, name, Action::Replace) {
+            cx.editor.set_error(err.to_string());
+        }
+    })
+}
+
+fn goto_mark_impl(editor: &mut Editor, name: char, action: Action) -> anyhow::Result<()> {
+    let current = doc!(editor).id();
+    let doc_id = if marks::is_local(name) {
+        doc!(editor).marks().contains_key(&name).then_some(current)
+    } else {
+        editor
+            .documents()
+            .find(|doc| doc.marks().contains_key(&name))
+            .map(|doc| doc.id())
+    };
+    let path = editor.marks.get(name).map(|(path, _)| path.to_path_buf());
+    if doc_id.is_none() && path.is_none() {
+        bail!("Mark '{name}' is not set");
+    }
+
+    let (view, doc) = current!(editor);
+    push_jump(view, doc);
+    match (doc_id, path) {
+        (Some(doc_id), _) => editor.switch(doc_id, action),
+        // Opening the file moves the mark onto the document.
+        (None, Some(path)) => {
+            editor.open(&path, action)?;
+        }
+        (None, None) => unreachable!(),
+    }
+
+    let config = editor.config();
+    let (view, doc) = current!(editor);
+    let selection = doc
+        .marks()
+        .get(&name)
+        .cloned()
+        .context("the mark was removed while opening its file")?;
+    doc.set_selection(view.id, selection);
+    view.ensure_cursor_in_view_center(doc, config.scrolloff);
+    Ok(())
+}
+
 fn changed_file_picker(cx: &mut Context) {
     pub struct FileChangeData {
         cwd: PathBuf,
@@ -3959,9 +4534,52 @@ fn exit_select_mode(cx: &mut Context) {
     }
 }
 
+fn select_block_mode(cx: &mut Context) {
+    let virtual_space = cx.editor.config().block_virtual_space;
+    let (view, doc) = current!(cx.editor);
+    if cx.editor.mode == Mode::Select && view.block_selection.take().is_some() {
+        // Keep the ranges of the block as a regular selection.
+        return;
+    }
+
+    let text = doc.text().slice(..);
+    let tab_width = doc.tab_width();
+    let block = BlockSelection::from_range(text, doc.selection(view.id).primary(), tab_width);
+    let active = ActiveBlockSelection::new(block, text, tab_width, virtual_space);
+    doc.set_selection(view.id, active.selection.clone());
+    view.block_selection = Some(active);
+    cx.editor.mode = Mode::Select;
+}
+
+/// Pads the lines of an active block selection with spaces so that inserting (or appending)
+/// reaches every line of the block, if `editor.block-virtual-space` is `pad`.
+fn pad_block_selection(cx: &mut Context, append: bool) {
+    let virtual_space = cx.editor.config().block_virtual_space;
+    let (view, doc) = current!(cx.editor);
+    let Some(ActiveBlockSelection { block, .. }) = view.block_selection.take() else {
+        return;
+    };
+    if virtual_space != VirtualSpace::Pad {
+        return;
+    }
+
+    let tab_width = doc.tab_width();
+    let columns = block.columns();
+    let col = if append {
+        columns.end() + 1
+    } else {
+        *columns.start()
+    };
+    let changes = block.padding(doc.text().slice(..), tab_width, col);
+    let transaction = Transaction::change(doc.text(), changes.into_iter());
+    doc.apply(&transaction, view.id);
+    let selection = block.to_selection(doc.text().slice(..), tab_width, virtual_space);
+    doc.set_selection(view.id, selection);
+}
+
 fn goto_first_diag(cx: &mut Context) {
     let (view, doc) = current!(cx.editor);
-    let selection = match doc.diagnostics().first() {
+    let selection = match doc.diagnostics().iter().find(|diag| is_navigable(diag)) {
         Some(diag) => Selection::single(diag.range.start, diag.range.end),
         None => return,
     };
@@ -3973,7 +4591,7 @@ fn goto_first_diag(cx: &mut Context) {
 
 fn goto_last_diag(cx: &mut Context) {
     let (view, doc) = current!(cx.editor);
-    let selection = match doc.diagnostics().last() {
+    let selection = match doc.diagnostics().iter().rfind(|diag| is_navigable(diag)) {
         Some(diag) => Selection::single(diag.range.start, diag.range.end),
         None => return,
     };
@@ -3983,7 +4601,29 @@ fn goto_last_diag(cx: &mut Context) {
         .immediately_show_diagnostic(doc, view.id);
 }
 
+/// Whether the diagnostic commands jump to the diagnostic. Misspellings are skipped since
+/// they would bury the other diagnostics, `goto_next_misspelling` jumps to them instead.
+fn is_navigable(diag: &Diagnostic) -> bool {
+    diag.provider != DiagnosticProvider::Spelling
+}
+
 fn goto_next_diag(cx: &mut Context) {
+    goto_next_diag_impl(cx, is_navigable);
+}
+
+fn goto_prev_diag(cx: &mut Context) {
+    goto_prev_diag_impl(cx, is_navigable);
+}
+
+fn goto_next_misspelling(cx: &mut Context) {
+    goto_next_diag_impl(cx, |diag| diag.provider == DiagnosticProvider::Spelling);
+}
+
+fn goto_prev_misspelling(cx: &mut Context) {
+    goto_prev_diag_impl(cx, |diag| diag.provider == DiagnosticProvider::Spelling);
+}
+
+fn goto_next_diag_impl(cx: &mut Context, filter: fn(&Diagnostic) -> bool) {
     let motion = move |editor: &mut Editor| {
         let (view, doc) = current!(editor);
 
@@ -3995,7 +4635,7 @@ fn goto_next_diag(cx: &mut Context) {
         let diag = doc
             .diagnostics()
             .iter()
-            .find(|diag| diag.range.start > cursor_pos);
+            .find(|diag| diag.range.start > cursor_pos && filter(diag));
 
         let selection = match diag {
             Some(diag) => Selection::single(diag.range.start, diag.range.end),
@@ -4010,7 +4650,7 @@ fn goto_next_diag(cx: &mut Context) {
     cx.editor.apply_motion(motion);
 }
 
-fn goto_prev_diag(cx: &mut Context) {
+fn goto_prev_diag_impl(cx: &mut Context, filter: fn(&Diagnostic) -> bool) {
     let motion = move |editor: &mut Editor| {
         let (view, doc) = current!(editor);
 
@@ -4023,7 +4663,7 @@ fn goto_prev_diag(cx: &mut Context) {
             .diagnostics()
             .iter()
             .rev()
-            .find(|diag| diag.range.start < cursor_pos);
+            .find(|diag| diag.range.start < cursor_pos && filter(diag));
 
         let selection = match diag {
             // NOTE: the selection is reversed because we're jumping to the
@@ -4039,10 +4679,98 @@ fn goto_prev_diag(cx: &mut Context) {
     cx.editor.apply_motion(motion)
 }
 
-fn goto_first_change(cx: &mut Context) {
-    goto_first_change_impl(cx, false);
-}
-
+/// The range of the word to correct: the misspelling at the primary cursor or the word under it.
+fn spelling_word_range(doc: &Document, view_id: ViewId) -> Range {
+    let text = doc.text().slice(..);
+    let range = doc.selection(view_id).primary();
+    let cursor = range.cursor(text);
+    doc.diagnostics()
+        .iter()
+        .find(|diag| {
+            diag.provider == DiagnosticProvider::Spelling
+                && (diag.range.start..diag.range.end).contains(&cursor)
+        })
+        .map(|diag| Range::new(diag.range.start, diag.range.end))
+        .unwrap_or_else(|| {
+            textobject::textobject_word(text, range, textobject::TextObject::Inside, 1, false)
+        })
+}
+
+fn suggest_spelling(cx: &mut Context) {
+    let (view, doc) = current_ref!(cx.editor);
+    let doc_id = doc.id();
+    let range = spelling_word_range(doc, view.id);
+    let word = doc.text().slice(range.from()..range.to()).to_string();
+    if word.trim().is_empty() {
+        cx.editor.set_error("No word under the cursor");
+        return;
+    }
+
+    let Some(checker) = cx.editor.spell_checker() else {
+        return;
+    };
+    let checker = checker.read();
+    if checker.check(&word) {
+        cx.editor
+            .set_status(format!("'{word}' is spelled correctly"));
+        return;
+    }
+    let suggestions = checker.suggest(&word);
+    if suggestions.is_empty() {
+        cx.editor.set_error(format!("No suggestions for '{word}'"));
+        return;
+    }
+
+    let columns = [ui::PickerColumn::new(
+        "suggestion",
+        |suggestion: &String, _| suggestion.as_str().into(),
+    )];
+    let picker = Picker::new(
+        columns,
+        0,
+        suggestions,
+        (),
+        move |cx, suggestion, _action| {
+            let (view, doc) = current!(cx.editor);
+            if doc.id() != doc_id {
+                return;
+            }
+            let transaction = Transaction::change(
+                doc.text(),
+                std::iter::once((range.from(), range.to(), Some(suggestion.as_str().into()))),
+            );
+            doc.apply(&transaction, view.id);
+            doc.append_changes_to_history(view);
+        },
+    );
+    cx.push_layer(Box::new(overlaid(picker)));
+}
+
+fn add_to_dictionary(cx: &mut Context) {
+    let (view, doc) = current_ref!(cx.editor);
+    let range = spelling_word_range(doc, view.id);
+    let word = doc.text().slice(range.from()..range.to()).to_string();
+
+    let Some(checker) = cx.editor.spell_checker() else {
+        return;
+    };
+    if let Err(err) = checker.write().add_word(&word) {
+        cx.editor
+            .set_error(format!("Failed to add '{word}' to the dictionary: {err}"));
+        return;
+    }
+    cx.editor
+        .set_status(format!("Added '{word}' to the dictionary"));
+
+    for doc in cx.editor.documents() {
+        helix_event::send_blocking(&cx.editor.handlers.spelling, SpellingEvent(doc.id()));
+    }
+}
+
+fn goto_first_change(cx: &mut Context) {
+    goto_first_change_impl(cx, false);
+}
+
 fn goto_last_change(cx: &mut Context) {
     goto_first_change_impl(cx, true);
 }
@@ -4179,7 +4907,7 @@ pub fn insert_char(cx: &mut Context, c: char) {
 
         let transaction = auto_pairs
             .as_ref()
-            .and_then(|ap| auto_pairs::hook(text, selection, c, ap))
+            .and_then(|ap| auto_pairs::hook(text, selection, c, ap, doc.syntax()))
             .or_else(|| insert(text, selection, c));
 
         let (view, doc) = current!(cx.editor);
@@ -4187,9 +4915,52 @@ pub fn insert_char(cx: &mut Context, c: char) {
             doc.apply(&t, view.id);
         }
 
+        if cx.editor.config().auto_wrap && !c.is_whitespace() {
+            auto_wrap(cx.editor);
+        }
+
         helix_event::dispatch(PostInsertChar { c, cx });
     }
 
+    /// Breaks the lines of the cursors which exceed the text width at the last blank that fits.
+    fn auto_wrap(editor: &mut Editor) {
+        let (view, doc) = current!(editor);
+        let text_width = doc.text_width();
+        let comment_tokens: Vec<&str> = doc
+            .language_config()
+            .and_then(|config| config.comment_tokens.as_ref())
+            .map(|tokens| tokens.iter().map(String::as_str).collect())
+            .unwrap_or_default();
+        let line_ending = doc.line_ending.as_str();
+        let text = doc.text().slice(..);
+
+        let mut changes = Vec::new();
+        for range in doc.selection(view.id) {
+            let cursor = range.cursor(text);
+            let line_start = text.line_to_char(text.char_to_line(cursor));
+            let line = Cow::from(text.line(text.char_to_line(cursor)));
+            let cursor_byte = line
+                .char_indices()
+                .nth(cursor - line_start)
+                .map_or(line.len(), |(i, _)| i);
+            if let Some((blanks, prefix)) =
+                helix_core::wrap::auto_wrap_break(&line, cursor_byte, text_width, &comment_tokens)
+            {
+                let from = line_start + line[..blanks.start].chars().count();
+                let to = line_start + line[..blanks.end].chars().count();
+                changes.push((from, to, Some(format!("{line_ending}{prefix}").into())));
+            }
+        }
+        if changes.is_empty() {
+            return;
+        }
+        // Cursors on the same line break it once.
+        changes.dedup_by_key(|(from, _, _)| *from);
+
+        let transaction = Transaction::change(doc.text(), changes.into_iter());
+        doc.apply(&transaction, view.id);
+    }
+
     pub fn smart_tab(cx: &mut Context) {
         let (view, doc) = current_ref!(cx.editor);
         let view_id = view.id;
@@ -4349,8 +5120,10 @@ pub fn insert_newline(cx: &mut Context) {
                 // more and place the cursor there
                 let on_auto_pair = doc
                     .auto_pairs(cx.editor, loader, view)
-                    .and_then(|pairs| pairs.get(prev))
-                    .is_some_and(|pair| pair.open == prev && pair.close == curr);
+                    .is_some_and(|pairs| {
+                        pairs.get(prev).and_then(auto_pairs::Pair::chars) == Some((prev, curr))
+                            || pairs.pair_at(text, pos).is_some()
+                    });
 
                 let local_offs = if let Some(token) = continue_comment_token {
                     new_text.reserve_exact(line_ending.len() + indent.len() + token.len() + 1);
@@ -4481,6 +5254,15 @@ pub fn delete_char_backward(cx: &mut Context) {
                         }
                         (start, pos) // delete!
                     }
+                } else if let Some(pair) = auto_pairs
+                    .and_then(|ap| ap.pair_at(text, pos))
+                    .filter(|pair| pair.chars().is_none() && range.is_single_grapheme(text))
+                {
+                    // delete both parts of a pair of multiple characters
+                    (
+                        pos - pair.open.chars().count(),
+                        pos + pair.close.chars().count(),
+                    )
                 } else {
                     match (
                         text.get_char(pos.saturating_sub(1)),
@@ -4489,9 +5271,8 @@ pub fn delete_char_backward(cx: &mut Context) {
                     ) {
                         (Some(_x), Some(_y), Some(ap))
                             if range.is_single_grapheme(text)
-                                && ap.get(_x).is_some()
-                                && ap.get(_x).unwrap().open == _x
-                                && ap.get(_x).unwrap().close == _y =>
+                                && ap.get(_x).and_then(auto_pairs::Pair::chars)
+                                    == Some((_x, _y)) =>
                         // delete both autopaired characters
                         {
                             (
@@ -4603,6 +5384,96 @@ fn commit_undo_checkpoint(cx: &mut Context) {
     doc.append_changes_to_history(view);
 }
 
+fn undo_tree(cx: &mut Context) {
+    let (view, doc) = current!(cx.editor);
+    doc.append_changes_to_history(view);
+    let (doc_id, view_id) = (doc.id(), view.id);
+    let undo_tree = ui::UndoTree::new(cx.editor, doc_id, view_id);
+    cx.push_layer(Box::new(overlaid(undo_tree)));
+}
+
+fn register_picker(cx: &mut Context) {
+    let picker = ui::register_picker(cx.editor);
+    cx.push_layer(Box::new(overlaid(picker)));
+}
+
+fn snippet_picker(cx: &mut Context) {
+    let doc = doc!(cx.editor);
+    let snippets: Vec<_> = cx
+        .editor
+        .snippets
+        .snippets(doc.language_name())
+        .cloned()
+        .collect();
+    if snippets.is_empty() {
+        cx.editor.set_error("No snippets defined");
+        return;
+    }
+
+    let columns = [
+        ui::PickerColumn::new("name", |snippet: &Arc<UserSnippet>, _| {
+            snippet.name.as_str().into()
+        }),
+        ui::PickerColumn::new("prefix", |snippet: &Arc<UserSnippet>, _| {
+            snippet.prefixes.join(", ").into()
+        }),
+        ui::PickerColumn::new("description", |snippet: &Arc<UserSnippet>, _| {
+            let description = snippet.description.as_deref().unwrap_or_default();
+            description.lines().next().unwrap_or_default().into()
+        }),
+    ];
+    let picker = Picker::new(columns, 0, snippets, (), |cx, snippet, _action| {
+        insert_snippet(cx.editor, &snippet.snippet);
+        // Typing replaces the first placeholder, as after completing a snippet.
+        cx.jobs.callback(async {
+            let call: job::Callback = Callback::EditorCompositor(Box::new(
+                |_editor: &mut Editor, compositor: &mut Compositor| {
+                    if let Some(editor_view) = compositor.find::<ui::EditorView>() {
+                        editor_view.set_on_next_key(
+                            Box::new(replace_placeholder),
+                            OnKeyCallbackKind::Fallback,
+                        );
+                    }
+                },
+            ));
+            Ok(call)
+        });
+    });
+    cx.push_layer(Box::new(overlaid(picker)));
+}
+
+/// Inserts `snippet` at each cursor, replacing the selected text of selections longer than one
+/// character, and enters insert mode at the first tabstop.
+fn insert_snippet(editor: &mut Editor, snippet: &Snippet) {
+    let (view, doc) = current!(editor);
+    let text = doc.text().slice(..);
+    let selection = doc.selection(view.id).clone();
+    let (transaction, mapped_selection, rendered) = snippet.render(
+        doc.text(),
+        &selection,
+        |range| {
+            if range.len() > 1 {
+                (range.from(), range.to())
+            } else {
+                let cursor = range.cursor(text);
+                (cursor, cursor)
+            }
+        },
+        &mut doc.snippet_ctx(view.id),
+    );
+    let transaction = transaction.with_selection(rendered.first_selection(
+        selection.primary().direction(),
+        mapped_selection.primary_index(),
+    ));
+    doc.apply(&transaction, view.id);
+    doc.append_changes_to_history(view);
+    doc.active_snippet = match doc.active_snippet.take() {
+        Some(active) => active.insert_subsnippet(rendered),
+        None => ActiveSnippet::new(rendered),
+    };
+    editor.mode = Mode::Insert;
+}
+
 // Yank / Paste
 
 fn yank(cx: &mut Context) {
@@ -4715,7 +5586,7 @@ fn yank_main_selection_to_primary_clipboard(cx: &mut Context) {
 }
 
 #[derive(Copy, Clone)]
-enum Paste {
+pub(crate) enum Paste {
     Before,
     After,
     Cursor,
@@ -4900,7 +5771,7 @@ fn replace_selections_with_primary_clipboard(cx: &mut Context) {
     exit_select_mode(cx);
 }
 
-fn paste(editor: &mut Editor, register: char, pos: Paste, count: usize) {
+pub(crate) fn paste(editor: &mut Editor, register: char, pos: Paste, count: usize) {
     let Some(values) = editor.registers.read(register, editor) else {
         return;
     };
@@ -5270,27 +6141,20 @@ pub fn completion(cx: &mut Context) {
 }
 
 // comments
-type CommentTransactionFn = fn(
-    line_token: Option<&str>,
-    block_tokens: Option<&[BlockCommentToken]>,
-    doc: &Rope,
-    selection: &Selection,
-) -> Transaction;
+type CommentTransactionFn =
+    fn(tokens: comment::CommentTokens, doc: &Rope, selection: &Selection) -> Transaction;
 
 fn toggle_comments_impl(cx: &mut Context, comment_transaction: CommentTransactionFn) {
     let (view, doc) = current!(cx.editor);
-    let line_token: Option<&str> = doc
-        .language_config()
-        .and_then(|lc| lc.comment_tokens.as_ref())
-        .and_then(|tc| tc.first())
-        .map(|tc| tc.as_str());
-    let block_tokens: Option<&[BlockCommentToken]> = doc
-        .language_config()
-        .and_then(|lc| lc.block_comment_tokens.as_ref())
-        .map(|tc| &tc[..]);
-
-    let transaction =
-        comment_transaction(line_token, block_tokens, doc.text(), doc.selection(view.id));
+    let loader = cx.editor.syn_loader.load();
+    let transaction = comment::toggle_injected_comments(
+        doc.text(),
+        doc.selection(view.id),
+        doc.syntax(),
+        &loader,
+        doc.language_config(),
+        comment_transaction,
+    );
 
     doc.apply(&transaction, view.id);
     exit_select_mode(cx);
@@ -5303,7 +6167,8 @@ fn toggle_comments_impl(cx: &mut Context, comment_transaction: CommentTransactio
 /// 4. all lines not commented and block tokens -> comment uncommented lines
 /// 5. no comment tokens and not block commented -> line comment
 fn toggle_comments(cx: &mut Context) {
-    toggle_comments_impl(cx, |line_token, block_tokens, doc, selection| {
+    toggle_comments_impl(cx, |tokens, doc, selection| {
+        let (line_token, block_tokens) = (tokens.line, tokens.block);
         let text = doc.slice(..);
 
         // only have line comment tokens
@@ -5361,7 +6226,8 @@ fn toggle_comments(cx: &mut Context) {
 }
 
 fn toggle_line_comments(cx: &mut Context) {
-    toggle_comments_impl(cx, |line_token, block_tokens, doc, selection| {
+    toggle_comments_impl(cx, |tokens, doc, selection| {
+        let (line_token, block_tokens) = (tokens.line, tokens.block);
         if line_token.is_none() && block_tokens.is_some() {
             let default_block_tokens = &[BlockCommentToken::default()];
             let block_comment_tokens = block_tokens.unwrap_or(default_block_tokens);
@@ -5377,7 +6243,8 @@ fn toggle_line_comments(cx: &mut Context) {
 }
 
 fn toggle_block_comments(cx: &mut Context) {
-    toggle_comments_impl(cx, |line_token, block_tokens, doc, selection| {
+    toggle_comments_impl(cx, |tokens, doc, selection| {
+        let (line_token, block_tokens) = (tokens.line, tokens.block);
         if line_token.is_some() && block_tokens.is_none() {
             comment::toggle_line_comments(doc, selection, line_token)
         } else {
@@ -6088,6 +6955,18 @@ fn select_textobject(cx: &mut Context, objtype: textobject::TextObject) {
                         'e' => textobject_treesitter("entry", range),
                         'x' => textobject_treesitter("xml-element", range),
                         'p' => textobject::textobject_paragraph(text, range, objtype, count),
+                        'i' => textobject::textobject_indentation_level(
+                            text,
+                            range,
+                            objtype,
+                            count,
+                            doc.tab_width(),
+                        ),
+                        'l' => textobject::textobject_line(text, range, objtype, count),
+                        'n' => textobject::textobject_number(text, range, objtype),
+                        'u' => textobject::textobject_url(text, range, objtype),
+                        'F' => textobject::textobject_path(text, range, objtype),
+                        'b' => textobject::textobject_buffer(text, objtype),
                         'm' => textobject::textobject_pair_surround_closest(
                             doc.syntax(),
                             text,
@@ -6123,6 +7002,12 @@ fn select_textobject(cx: &mut Context, objtype: textobject::TextObject) {
         ("w", "Word"),
         ("W", "WORD"),
         ("p", "Paragraph"),
+        ("i", "Indentation level"),
+        ("l", "Line"),
+        ("n", "Number"),
+        ("u", "URL"),
+        ("F", "File path"),
+        ("b", "Entire buffer"),
         ("t", "Type definition (tree-sitter)"),
         ("f", "Function (tree-sitter)"),
         ("a", "Argument/parameter (tree-sitter)"),
@@ -6138,90 +7023,208 @@ fn select_textobject(cx: &mut Context, objtype: textobject::TextObject) {
     cx.editor.autoinfo = Some(Info::new(title, &help_text));
 }
 
-static SURROUND_HELP_TEXT: [(&str, &str); 6] = [
+static SURROUND_HELP_TEXT: [(&str, &str); 8] = [
     ("m", "Nearest matching pair"),
     ("( or )", "Parentheses"),
     ("{ or }", "Curly braces"),
     ("< or >", "Angled brackets"),
     ("[ or ]", "Square brackets"),
+    ("t", "HTML/XML tag"),
+    ("f", "Function call"),
     (" ", "... or any character"),
 ];
 
-fn surround_add(cx: &mut Context) {
-    cx.on_next_key(move |cx, event| {
-        cx.editor.autoinfo = None;
-        let (view, doc) = current!(cx.editor);
-        // surround_len is the number of new characters being added.
-        let (open, close, surround_len) = match event.char() {
-            Some(ch) => {
-                let (o, c) = match_brackets::get_pair(ch);
-                let mut open = Tendril::new();
-                open.push(o);
-                let mut close = Tendril::new();
-                close.push(c);
-                (open, close, 2)
-            }
-            None if event.code == KeyCode::Enter => (
-                doc.line_ending.as_str().into(),
-                doc.line_ending.as_str().into(),
-                2 * doc.line_ending.len_chars(),
-            ),
-            None => return,
-        };
+/// Builds the surround help text, including the surround pairs of the document's language.
+fn surround_help(doc: &Document, title: &'static str, nearest: bool) -> Info {
+    let (builtin, any_char) = SURROUND_HELP_TEXT.split_at(SURROUND_HELP_TEXT.len() - 1);
+    let builtin = if nearest { builtin } else { &builtin[1..] };
+    let mut help_text: Vec<(String, String)> = builtin
+        .iter()
+        .map(|(key, desc)| (key.to_string(), desc.to_string()))
+        .collect();
+    if let Some(config) = doc.language_config() {
+        let mut pairs: Vec<_> = config.surround_pairs.iter().collect();
+        pairs.sort_unstable_by_key(|(key, _)| **key);
+        help_text.extend(
+            pairs
+                .into_iter()
+                .map(|(key, (open, close))| (key.to_string(), format!("{open} ... {close}"))),
+        );
+    }
+    help_text.extend(
+        any_char
+            .iter()
+            .map(|(key, desc)| (key.to_string(), desc.to_string())),
+    );
+    Info::new(title, &help_text)
+}
 
-        let selection = doc.selection(view.id);
-        let mut changes = Vec::with_capacity(selection.len() * 2);
-        let mut ranges = SmallVec::with_capacity(selection.len());
-        let mut offs = 0;
+/// Returns the multi-character surround pair selected by `ch`. The surround pairs of the
+/// document's language take precedence over tags and function calls.
+fn surround_pair(doc: &Document, ch: char) -> Option<SurroundPair> {
+    if let Some((open, close)) = doc
+        .language_config()
+        .and_then(|config| config.surround_pairs.get(&ch))
+    {
+        return Some(SurroundPair::Literal {
+            open: open.clone(),
+            close: close.clone(),
+        });
+    }
+    match ch {
+        't' => Some(SurroundPair::Tag),
+        'f' => Some(SurroundPair::Function),
+        _ => None,
+    }
+}
 
-        for range in selection.iter() {
-            changes.push((range.from(), range.from(), Some(open.clone())));
-            changes.push((range.to(), range.to(), Some(close.clone())));
+/// Calls `apply` with the opening and closing delimiter of the surround pair selected by `ch`,
+/// prompting for the name of tags and function calls first.
+fn surround_delimiters(
+    cx: &mut Context,
+    ch: char,
+    apply: impl Fn(&mut Editor, Tendril, Tendril) + 'static,
+) {
+    let pair = match surround_pair(doc!(cx.editor), ch) {
+        Some(SurroundPair::Literal { open, close }) => {
+            return apply(cx.editor, open.into(), close.into());
+        }
+        Some(pair) => pair,
+        None => {
+            let (open, close) = match_brackets::get_pair(ch);
+            let mut open_tendril = Tendril::new();
+            open_tendril.push(open);
+            let mut close_tendril = Tendril::new();
+            close_tendril.push(close);
+            return apply(cx.editor, open_tendril, close_tendril);
+        }
+    };
+    let prompt = match pair {
+        SurroundPair::Tag => "tag:",
+        _ => "function:",
+    };
 
-            ranges.push(
-                Range::new(offs + range.from(), offs + range.to() + surround_len)
-                    .with_direction(range.direction()),
-            );
+    ui::prompt(
+        cx,
+        prompt.into(),
+        None,
+        ui::completers::none,
+        move |cx, input, event| {
+            let input = input.trim();
+            if event != PromptEvent::Validate || input.is_empty() {
+                return;
+            }
+            let (open, close) = match pair {
+                SurroundPair::Tag => {
+                    let name = input.split_whitespace().next().unwrap_or_default();
+                    (format!("<{input}>"), format!("</{name}>"))
+                }
+                _ => (format!("{input}("), ")".to_string()),
+            };
+            apply(cx.editor, open.into(), close.into());
+        },
+    );
+}
 
-            offs += surround_len;
+/// Finds the ranges of the opening and closing delimiters of the surround pair selected by
+/// `ch` around every selection.
+fn surround_ranges(
+    doc: &Document,
+    view_id: ViewId,
+    ch: char,
+    count: usize,
+) -> Result<Vec<surround::Delimiters>, surround::Error> {
+    let text = doc.text().slice(..);
+    let selection = doc.selection(view_id);
+    let pair = if ch == 'm' {
+        None
+    } else {
+        surround_pair(doc, ch)
+    };
+    match pair {
+        Some(pair) => surround::get_surround_ranges(doc.syntax(), text, selection, &pair, count),
+        None => {
+            let ch = (ch != 'm').then_some(ch); // m selects the closest surround pair
+            let change_pos = surround::get_surround_pos(doc.syntax(), text, selection, ch, count)?;
+            Ok(change_pos
+                .chunks(2)
+                .map(|pos| (pos[0]..pos[0] + 1, pos[1]..pos[1] + 1))
+                .collect())
         }
+    }
+}
 
-        let transaction = Transaction::change(doc.text(), changes.into_iter())
-            .with_selection(Selection::new(ranges, selection.primary_index()));
-        doc.apply(&transaction, view.id);
-        exit_select_mode(cx);
+fn surround_add(cx: &mut Context) {
+    cx.on_next_key(move |cx, event| {
+        cx.editor.autoinfo = None;
+        match event.char() {
+            Some(ch) => surround_delimiters(cx, ch, surround_add_impl),
+            None if event.code == KeyCode::Enter => {
+                let line_ending: Tendril = doc!(cx.editor).line_ending.as_str().into();
+                surround_add_impl(cx.editor, line_ending.clone(), line_ending);
+            }
+            None => (),
+        }
     });
 
-    cx.editor.autoinfo = Some(Info::new(
+    cx.editor.autoinfo = Some(surround_help(
+        doc!(cx.editor),
         "Surround selections with",
-        &SURROUND_HELP_TEXT[1..],
+        false,
     ));
 }
 
+fn surround_add_impl(editor: &mut Editor, open: Tendril, close: Tendril) {
+    let (view, doc) = current!(editor);
+    // surround_len is the number of new characters being added.
+    let surround_len = open.chars().count() + close.chars().count();
+    let selection = doc.selection(view.id);
+    let mut changes = Vec::with_capacity(selection.len() * 2);
+    let mut ranges = SmallVec::with_capacity(selection.len());
+    let mut offs = 0;
+
+    for range in selection.iter() {
+        changes.push((range.from(), range.from(), Some(open.clone())));
+        changes.push((range.to(), range.to(), Some(close.clone())));
+
+        ranges.push(
+            Range::new(offs + range.from(), offs + range.to() + surround_len)
+                .with_direction(range.direction()),
+        );
+
+        offs += surround_len;
+    }
+
+    let transaction = Transaction::change(doc.text(), changes.into_iter())
+        .with_selection(Selection::new(ranges, selection.primary_index()));
+    doc.apply(&transaction, view.id);
+    if editor.mode == Mode::Select {
+        editor.mode = Mode::Normal;
+    }
+}
+
 fn surround_replace(cx: &mut Context) {
     let count = cx.count();
     cx.on_next_key(move |cx, event| {
         cx.editor.autoinfo = None;
-        let surround_ch = match event.char() {
-            Some('m') => None, // m selects the closest surround pair
-            Some(ch) => Some(ch),
-            None => return,
+        let Some(surround_ch) = event.char() else {
+            return;
         };
         let (view, doc) = current!(cx.editor);
-        let text = doc.text().slice(..);
-        let selection = doc.selection(view.id);
-
-        let change_pos =
-            match surround::get_surround_pos(doc.syntax(), text, selection, surround_ch, count) {
-                Ok(c) => c,
-                Err(err) => {
-                    cx.editor.set_error(err.to_string());
-                    return;
-                }
-            };
+        let delimiters = match surround_ranges(doc, view.id, surround_ch, count) {
+            Ok(delimiters) => delimiters,
+            Err(err) => {
+                cx.editor.set_error(err.to_string());
+                return;
+            }
+        };
 
-        let selection = selection.clone();
-        let ranges: SmallVec<[Range; 1]> = change_pos.iter().map(|&p| Range::point(p)).collect();
+        let selection = doc.selection(view.id).clone();
+        let ranges: SmallVec<[Range; 1]> = delimiters
+            .iter()
+            .flat_map(|(open, close)| [open, close])
+            .map(|range| Range::new(range.start, range.end))
+            .collect();
         doc.set_selection(
             view.id,
             Selection::new(ranges, selection.primary_index() * 2),
@@ -6230,42 +7233,44 @@ fn surround_replace(cx: &mut Context) {
         cx.on_next_key(move |cx, event| {
             cx.editor.autoinfo = None;
             let (view, doc) = current!(cx.editor);
-            let to = match event.char() {
-                Some(to) => to,
-                None => return doc.set_selection(view.id, selection),
+            doc.set_selection(view.id, selection);
+            let Some(to) = event.char() else {
+                return;
             };
-            let (open, close) = match_brackets::get_pair(to);
 
-            // the changeset has to be sorted to allow nested surrounds
-            let mut sorted_pos: Vec<(usize, char)> = Vec::new();
-            for p in change_pos.chunks(2) {
-                sorted_pos.push((p[0], open));
-                sorted_pos.push((p[1], close));
-            }
-            sorted_pos.sort_unstable();
+            surround_delimiters(cx, to, move |editor, open, close| {
+                // the changeset has to be sorted to allow nested surrounds
+                let mut changes: Vec<_> = delimiters
+                    .iter()
+                    .flat_map(|(open_range, close_range)| {
+                        [
+                            (open_range.start, open_range.end, Some(open.clone())),
+                            (close_range.start, close_range.end, Some(close.clone())),
+                        ]
+                    })
+                    .collect();
+                changes.sort_unstable_by_key(|(from, _, _)| *from);
 
-            let transaction = Transaction::change(
-                doc.text(),
-                sorted_pos.iter().map(|&pos| {
-                    let mut t = Tendril::new();
-                    t.push(pos.1);
-                    (pos.0, pos.0 + 1, Some(t))
-                }),
-            );
-            doc.set_selection(view.id, selection);
-            doc.apply(&transaction, view.id);
-            exit_select_mode(cx);
+                let (view, doc) = current!(editor);
+                let transaction = Transaction::change(doc.text(), changes.into_iter());
+                doc.apply(&transaction, view.id);
+                if editor.mode == Mode::Select {
+                    editor.mode = Mode::Normal;
+                }
+            });
         });
 
-        cx.editor.autoinfo = Some(Info::new(
+        cx.editor.autoinfo = Some(surround_help(
+            doc!(cx.editor),
             "Replace with a pair of",
-            &SURROUND_HELP_TEXT[1..],
+            false,
         ));
     });
 
-    cx.editor.autoinfo = Some(Info::new(
+    cx.editor.autoinfo = Some(surround_help(
+        doc!(cx.editor),
         "Replace surrounding pair of",
-        &SURROUND_HELP_TEXT,
+        true,
     ));
 }
 
@@ -6273,31 +7278,37 @@ fn surround_delete(cx: &mut Context) {
     let count = cx.count();
     cx.on_next_key(move |cx, event| {
         cx.editor.autoinfo = None;
-        let surround_ch = match event.char() {
-            Some('m') => None, // m selects the closest surround pair
-            Some(ch) => Some(ch),
-            None => return,
+        let Some(surround_ch) = event.char() else {
+            return;
         };
         let (view, doc) = current!(cx.editor);
-        let text = doc.text().slice(..);
-        let selection = doc.selection(view.id);
-
-        let mut change_pos =
-            match surround::get_surround_pos(doc.syntax(), text, selection, surround_ch, count) {
-                Ok(c) => c,
-                Err(err) => {
-                    cx.editor.set_error(err.to_string());
-                    return;
-                }
-            };
-        change_pos.sort_unstable(); // the changeset has to be sorted to allow nested surrounds
-        let transaction =
-            Transaction::change(doc.text(), change_pos.into_iter().map(|p| (p, p + 1, None)));
+        let mut delimiters: Vec<_> = match surround_ranges(doc, view.id, surround_ch, count) {
+            Ok(delimiters) => delimiters
+                .into_iter()
+                .flat_map(|(open, close)| [open, close])
+                .collect(),
+            Err(err) => {
+                cx.editor.set_error(err.to_string());
+                return;
+            }
+        };
+        // the changeset has to be sorted to allow nested surrounds
+        delimiters.sort_unstable_by_key(|range| range.start);
+        let transaction = Transaction::change(
+            doc.text(),
+            delimiters
+                .into_iter()
+                .map(|range| (range.start, range.end, None)),
+        );
         doc.apply(&transaction, view.id);
         exit_select_mode(cx);
     });
 
-    cx.editor.autoinfo = Some(Info::new("Delete surrounding pair of", &SURROUND_HELP_TEXT));
+    cx.editor.autoinfo = Some(surround_help(
+        doc!(cx.editor),
+        "Delete surrounding pair of",
+        true,
+    ));
 }
 
 #[derive(Eq, PartialEq)]
@@ -6613,9 +7624,18 @@ fn increment_impl(cx: &mut Context, increment_direction: IncrementDirection) {
     // If the register is `#` then increase or decrease the `amount` by 1 per element
     let increase_by = if cx.register == Some('#') { sign } else { 0 };
 
+    let config = cx.editor.config();
+    let (editor_groups, increment_letters) = (&config.increment_groups, config.increment_letters);
     let (view, doc) = current!(cx.editor);
     let selection = doc.selection(view.id);
     let text = doc.text().slice(..);
+    let word_groups: Vec<_> = doc
+        .language_config()
+        .into_iter()
+        .flat_map(|config| config.increment_groups.iter())
+        .chain(editor_groups.iter())
+        .cloned()
+        .collect();
 
     let mut new_selection_ranges = SmallVec::new();
     let mut cumulative_length_diff: i128 = 0;
@@ -6624,9 +7644,15 @@ fn increment_impl(cx: &mut Context, increment_direction: IncrementDirection) {
     for range in selection {
         let selected_text: Cow<str> = range.fragment(text);
         let new_from = ((range.from() as i128) + cumulative_length_diff) as usize;
-        let incremented = [increment::integer, increment::date_time]
-            .iter()
-            .find_map(|incrementor| incrementor(selected_text.as_ref(), amount));
+        let cursor = range.cursor(text) - range.from();
+        let incremented =
+            increment::increment(selected_text.as_ref(), cursor, amount, &word_groups).or_else(
+                || {
+                    increment_letters
+                        .then(|| increment::letter(selected_text.as_ref(), cursor, amount))
+                        .flatten()
+                },
+            );
 
         amount += increase_by;
 
@@ -6639,8 +7665,9 @@ fn increment_impl(cx: &mut Context, increment_direction: IncrementDirection) {
                 new_selection_ranges.push(new_range);
             }
             Some(new_text) => {
-                let new_range = Range::new(new_from, new_from + new_text.len());
-                cumulative_length_diff += new_text.len() as i128 - selected_text.len() as i128;
+                let new_len = new_text.chars().count();
+                let new_range = Range::new(new_from, new_from + new_len);
+                cumulative_length_diff += new_len as i128 - range.len() as i128;
                 new_selection_ranges.push(new_range);
                 changes.push((range.from(), range.to(), Some(new_text.into())));
             }
@@ -6685,15 +7712,18 @@ fn goto_next_tabstop_impl(cx: &mut Context, direction: Direction) {
         doc.active_snippet = Some(snippet)
     }
     if cx.editor.mode() == Mode::Insert {
-        cx.on_next_key_fallback(|cx, key| {
-            if let Some(c) = key.char() {
-                let (view, doc) = current!(cx.editor);
-                if let Some(snippet) = &doc.active_snippet {
-                    doc.apply(&snippet.delete_placeholder(doc.text()), view.id);
-                }
-                insert_char(cx, c);
-            }
-        })
+        cx.on_next_key_fallback(replace_placeholder)
+    }
+}
+
+/// Replaces the selected snippet placeholder with the typed character.
+fn replace_placeholder(cx: &mut Context, key: KeyEvent) {
+    if let Some(c) = key.char() {
+        let (view, doc) = current!(cx.editor);
+        if let Some(snippet) = &doc.active_snippet {
+            doc.apply(&snippet.delete_placeholder(doc.text()), view.id);
+        }
+        insert_char(cx, c);
     }
 }
 
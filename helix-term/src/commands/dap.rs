@@ -1,17 +1,18 @@
 use super::{Context, Editor};
 use crate::{
-    compositor::{self, Compositor},
+    compositor::{self, Component, Compositor},
     job::{Callback, Jobs},
-    ui::{self, overlay::overlaid, Picker, Popup, Prompt, PromptEvent, Text},
+    ui::{self, overlay::overlaid, Picker, Popup, Prompt, PromptEvent},
 };
 use dap::{StackFrame, Thread, ThreadStates};
-use helix_core::syntax::config::{DebugConfigCompletion, DebugTemplate};
-use helix_dap::{self as dap, requests::TerminateArguments};
+use helix_core::syntax::config::{DebugAdapterConfig, DebugConfigCompletion, DebugTemplate};
+use helix_core::Rope;
+use helix_dap::{self as dap, registry::DebugAdapterId, requests::TerminateArguments};
 use helix_lsp::block_on;
-use helix_view::editor::Breakpoint;
+use helix_view::editor::{Action, Breakpoint};
+use helix_view::Document;
 
 use serde_json::{to_value, Value};
-use tui::text::Spans;
 
 use std::collections::HashMap;
 use std::future::Future;
@@ -19,7 +20,9 @@
 
 use anyhow::{anyhow, bail};
 
-use helix_view::handlers::dap::{breakpoints_changed, jump_to_stack_frame, select_thread_id};
+use helix_view::handlers::dap::{
+    breakpoints_changed, expand_launch_json_variables, jump_to_stack_frame, select_thread_id,
+};
 
 fn thread_picker(
     cx: &mut Context,
@@ -91,7 +94,7 @@ fn get_breakpoint_at_current_line(editor: &mut Editor) -> Option<(usize, Breakpo
 
 // -- DAP
 
-fn dap_callback<T, F>(
+pub(crate) fn dap_callback<T, F>(
     jobs: &mut Jobs,
     call: impl Future<Output = helix_dap::Result<serde_json::Value>> + 'static + Send,
     callback: F,
@@ -113,31 +116,118 @@ fn dap_callback<T, F>(
     jobs.callback(callback);
 }
 
+/// A debug template together with the adapter which should run it.
+#[derive(Debug, Clone)]
+pub struct DebugLaunchTemplate {
+    adapter: DebugAdapterConfig,
+    template: DebugTemplate,
+    /// Whether the template was read from `.vscode/launch.json` rather than `languages.toml`.
+    launch_json: bool,
+}
+
+/// Collects the templates of the current document's debug adapter followed by the
+/// configurations in the workspace's `.vscode/launch.json`.
+fn debug_templates(editor: &mut Editor) -> Vec<DebugLaunchTemplate> {
+    let doc = doc!(editor);
+    let mut templates: Vec<_> = doc
+        .language_config()
+        .and_then(|config| config.debugger.as_ref())
+        .map(|adapter| {
+            adapter
+                .templates
+                .iter()
+                .map(|template| DebugLaunchTemplate {
+                    adapter: adapter.clone(),
+                    template: template.clone(),
+                    launch_json: false,
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let (workspace, _) = helix_loader::find_workspace();
+    let configurations = match dap::launch_json::load(&workspace) {
+        Ok(configurations) => configurations,
+        Err(err) => {
+            editor.set_error(format!(
+                "Failed to read {}: {err}",
+                dap::launch_json::LAUNCH_JSON_PATH
+            ));
+            return templates;
+        }
+    };
+
+    let current_adapter = doc
+        .language_config()
+        .and_then(|config| config.debugger.clone());
+    let loader = editor.syn_loader.load();
+    let adapter_for_type = |ty: &str| {
+        let matches = |adapter: &DebugAdapterConfig| {
+            adapter.name == ty || adapter.vscode_types.iter().any(|t| t == ty)
+        };
+        // Prefer the adapter of the current document in case multiple languages share a type.
+        current_adapter
+            .iter()
+            .chain(
+                loader
+                    .language_configs()
+                    .filter_map(|config| config.debugger.as_ref()),
+            )
+            .find(|adapter| matches(adapter))
+            .cloned()
+    };
+
+    for configuration in configurations {
+        let Some(adapter) = adapter_for_type(&configuration.ty) else {
+            log::warn!(
+                "No debug adapter found for launch configuration '{}' of type '{}'",
+                configuration.name,
+                configuration.ty
+            );
+            continue;
+        };
+        templates.push(DebugLaunchTemplate {
+            adapter,
+            template: configuration.into_template(),
+            launch_json: true,
+        });
+    }
+
+    templates
+}
+
 pub fn dap_start_impl(
     cx: &mut compositor::Context,
     name: Option<&str>,
     socket: Option<std::net::SocketAddr>,
     params: Option<Vec<std::borrow::Cow<str>>>,
 ) -> Result<(), anyhow::Error> {
-    let doc = doc!(cx.editor);
-    let config = doc
-        .language_config()
-        .and_then(|config| config.debugger.as_ref())
-        .ok_or_else(|| anyhow!("No debug adapter available for language"))?;
-
-    let id = cx
-        .editor
-        .debug_adapters
-        .start_client(socket, config)
-        .map_err(|e| anyhow!("Failed to start debug client: {}", e))?;
+    let templates = debug_templates(cx.editor);
+    if templates.is_empty() {
+        bail!("No debug adapter available for language");
+    }
 
-    // TODO: avoid refetching all of this... pass a config in
     let template = match name {
-        Some(name) => config.templates.iter().find(|t| t.name == name),
-        None => config.templates.first(),
+        Some(name) => templates.into_iter().find(|t| t.template.name == name),
+        None => templates.into_iter().next(),
     }
     .ok_or_else(|| anyhow!("No debug config with given name"))?;
 
+    dap_start_template(cx, &template, socket, params)
+}
+
+fn dap_start_template(
+    cx: &mut compositor::Context,
+    template: &DebugLaunchTemplate,
+    socket: Option<std::net::SocketAddr>,
+    params: Option<Vec<std::borrow::Cow<str>>>,
+) -> Result<(), anyhow::Error> {
+    let DebugLaunchTemplate {
+        adapter: config,
+        template,
+        launch_json,
+    } = template;
+
     let mut args: HashMap<&str, Value> = if let Some(params) = params.as_ref() {
         let preprocessed_params = prepare_dap_params(template, params);
         template
@@ -153,10 +243,25 @@ pub fn dap_start_impl(
             .collect()
     };
 
-    args.insert("cwd", to_value(helix_stdx::env::current_working_dir())?);
+    let cwd = to_value(helix_stdx::env::current_working_dir())?;
+    if *launch_json {
+        for value in args.values_mut() {
+            *value = expand_launch_json_variables(cx.editor, value)?;
+        }
+        // Configurations from `launch.json` may set their own working directory.
+        args.entry("cwd").or_insert(cwd);
+    } else {
+        args.insert("cwd", cwd);
+    }
 
     let args = to_value(args).unwrap();
 
+    let id = cx
+        .editor
+        .debug_adapters
+        .start_client(socket, config)
+        .map_err(|e| anyhow!("Failed to start debug client: {}", e))?;
+
     let callback = |_editor: &mut Editor, _compositor: &mut Compositor, _response: Value| {
         // if let Err(e) = result {
         //     editor.set_error(format!("Failed {} target: {}", template.request, e));
@@ -169,6 +274,7 @@ pub fn dap_start_impl(
             bail!("Failed to get child debugger.");
         }
     };
+    debugger.name = template.name.clone();
 
     match &template.request[..] {
         "launch" => {
@@ -232,32 +338,25 @@ fn map_value(value: &Value, params: &[String]) -> Value {
 }
 
 pub fn dap_launch(cx: &mut Context) {
-    // TODO: Now that we support multiple Clients, we could run multiple debuggers at once but for now keep this as is
-    if cx.editor.debug_adapters.get_active_client().is_some() {
-        cx.editor.set_error("Debugger is already running");
+    let templates = debug_templates(cx.editor);
+    if templates.is_empty() {
+        cx.editor
+            .set_error("No debug adapter available for language");
         return;
     }
 
-    let doc = doc!(cx.editor);
-
-    let config = match doc
-        .language_config()
-        .and_then(|config| config.debugger.as_ref())
-    {
-        Some(c) => c,
-        None => {
-            cx.editor
-                .set_error("No debug adapter available for language");
-            return;
-        }
-    };
-
-    let templates = config.templates.clone();
-
-    let columns = [ui::PickerColumn::new(
-        "template",
-        |item: &DebugTemplate, _| item.name.as_str().into(),
-    )];
+    let columns = [
+        ui::PickerColumn::new("template", |item: &DebugLaunchTemplate, _| {
+            item.template.name.as_str().into()
+        }),
+        ui::PickerColumn::new("source", |item: &DebugLaunchTemplate, _| {
+            if item.launch_json {
+                dap::launch_json::LAUNCH_JSON_PATH.into()
+            } else {
+                "languages.toml".into()
+            }
+        }),
+    ];
 
     cx.push_layer(Box::new(overlaid(Picker::new(
         columns,
@@ -265,18 +364,18 @@ pub fn dap_launch(cx: &mut Context) {
         templates,
         (),
         |cx, template, _action| {
-            if template.completion.is_empty() {
-                if let Err(err) = dap_start_impl(cx, Some(&template.name), None, None) {
+            if template.template.completion.is_empty() {
+                if let Err(err) = dap_start_template(cx, template, None, None) {
                     cx.editor.set_error(err.to_string());
                 }
             } else {
-                let completions = template.completion.clone();
-                let name = template.name.clone();
+                let completions = template.template.completion.clone();
+                let name = template.template.name.clone();
                 let callback = Box::pin(async move {
                     let call: Callback =
                         Callback::EditorCompositor(Box::new(move |_editor, compositor| {
                             let prompt = debug_parameter_prompt(completions, name, Vec::new());
-                            compositor.push(Box::new(prompt));
+                            compositor.push(prompt);
                         }));
                     Ok(call)
                 });
@@ -319,8 +418,8 @@ pub fn dap_restart(cx: &mut Context) {
 fn debug_parameter_prompt(
     completions: Vec<DebugConfigCompletion>,
     config_name: String,
-    mut params: Vec<String>,
-) -> Prompt {
+    params: Vec<String>,
+) -> Box<dyn Component> {
     let completion = completions.get(params.len()).unwrap();
     let field_type = if let DebugConfigCompletion::Advanced(cfg) = completion {
         cfg.completion.as_deref().unwrap_or("")
@@ -337,6 +436,14 @@ fn debug_parameter_prompt(
     }
     .to_owned();
 
+    if field_type == "pid" {
+        // Fall back to typing the pid on platforms without `/proc`.
+        match dap::process::list() {
+            Ok(processes) => return process_picker(processes, completions, config_name, params),
+            Err(err) => log::warn!("Failed to list processes: {err}"),
+        }
+    }
+
     let completer = match field_type {
         "filename" => |editor: &Editor, input: &str| {
             ui::completers::filename_with_git_ignore(editor, input, false)
@@ -347,7 +454,7 @@ fn debug_parameter_prompt(
         _ => ui::completers::none,
     };
 
-    Prompt::new(
+    Box::new(Prompt::new(
         format!("{}: ", name).into(),
         None,
         completer,
@@ -360,31 +467,66 @@ fn debug_parameter_prompt(
             if value.is_empty() {
                 value = default_val.clone();
             }
+            let mut params = params.clone();
             params.push(value);
-
-            if params.len() < completions.len() {
-                let completions = completions.clone();
-                let config_name = config_name.clone();
-                let params = params.clone();
-                let callback = Box::pin(async move {
-                    let call: Callback =
-                        Callback::EditorCompositor(Box::new(move |_editor, compositor| {
-                            let prompt = debug_parameter_prompt(completions, config_name, params);
-                            compositor.push(Box::new(prompt));
-                        }));
-                    Ok(call)
-                });
-                cx.jobs.callback(callback);
-            } else if let Err(err) = dap_start_impl(
-                cx,
-                Some(&config_name),
-                None,
-                Some(params.iter().map(|x| x.into()).collect()),
-            ) {
-                cx.editor.set_error(err.to_string());
-            }
+            debug_parameter_next(cx, completions.clone(), config_name.clone(), params);
         },
-    )
+    ))
+}
+
+/// Prompts for the next parameter of the template or starts the debugger once all parameters
+/// have been provided.
+fn debug_parameter_next(
+    cx: &mut compositor::Context,
+    completions: Vec<DebugConfigCompletion>,
+    config_name: String,
+    params: Vec<String>,
+) {
+    if params.len() < completions.len() {
+        let callback = Box::pin(async move {
+            let call: Callback =
+                Callback::EditorCompositor(Box::new(move |_editor, compositor| {
+                    let prompt = debug_parameter_prompt(completions, config_name, params);
+                    compositor.push(prompt);
+                }));
+            Ok(call)
+        });
+        cx.jobs.callback(callback);
+    } else if let Err(err) = dap_start_impl(
+        cx,
+        Some(&config_name),
+        None,
+        Some(params.iter().map(|x| x.into()).collect()),
+    ) {
+        cx.editor.set_error(err.to_string());
+    }
+}
+
+/// A picker over the local processes for parameters with `completion = "pid"`. The pid of the
+/// selected process is used as the value of the parameter.
+fn process_picker(
+    processes: Vec<dap::process::Process>,
+    completions: Vec<DebugConfigCompletion>,
+    config_name: String,
+    params: Vec<String>,
+) -> Box<dyn Component> {
+    let columns = [
+        ui::PickerColumn::new("pid", |item: &dap::process::Process, _| {
+            item.pid.to_string().into()
+        }),
+        ui::PickerColumn::new("user", |item: &dap::process::Process, _| {
+            item.user.as_str().into()
+        }),
+        ui::PickerColumn::new("command", |item: &dap::process::Process, _| {
+            item.command.as_str().into()
+        }),
+    ];
+    let picker = Picker::new(columns, 2, processes, (), move |cx, process, _action| {
+        let mut params = params.clone();
+        params.push(process.pid.to_string());
+        debug_parameter_next(cx, completions.clone(), config_name.clone(), params);
+    });
+    Box::new(overlaid(picker))
 }
 
 pub fn dap_toggle_breakpoint(cx: &mut Context) {
@@ -501,6 +643,147 @@ pub fn dap_next(cx: &mut Context) {
     }
 }
 
+pub fn dap_step_back(cx: &mut Context) {
+    let debugger = debugger!(cx.editor);
+
+    if !debugger
+        .capabilities()
+        .supports_step_back
+        .unwrap_or_default()
+    {
+        cx.editor
+            .set_error("Debugger does not support stepping back");
+        return;
+    }
+
+    if let Some(thread_id) = debugger.thread_id {
+        let request = debugger.step_back(thread_id);
+        dap_callback(cx.jobs, request, |editor, _compositor, _response: ()| {
+            debugger!(editor).resume_application();
+        });
+    } else {
+        cx.editor
+            .set_error("Currently active thread is not stopped. Switch the thread.");
+    }
+}
+
+pub fn dap_reverse_continue(cx: &mut Context) {
+    let debugger = debugger!(cx.editor);
+
+    if !debugger
+        .capabilities()
+        .supports_step_back
+        .unwrap_or_default()
+    {
+        cx.editor
+            .set_error("Debugger does not support reverse execution");
+        return;
+    }
+
+    if let Some(thread_id) = debugger.thread_id {
+        let request = debugger.reverse_continue(thread_id);
+        dap_callback(cx.jobs, request, |editor, _compositor, _response: ()| {
+            debugger!(editor).resume_application();
+        });
+    } else {
+        cx.editor
+            .set_error("Currently active thread is not stopped. Switch the thread.");
+    }
+}
+
+pub fn dap_run_to_cursor(cx: &mut Context) {
+    let (view, doc) = current!(cx.editor);
+    let path = match doc.path() {
+        Some(path) => path.clone(),
+        None => {
+            cx.editor
+                .set_error("Can't run to cursor: document has no path");
+            return;
+        }
+    };
+    let text = doc.text().slice(..);
+    let line = doc.selection(view.id).primary().cursor_line(text);
+
+    if debugger!(cx.editor).thread_id.is_none() {
+        cx.editor
+            .set_error("Currently active thread is not stopped. Switch the thread.");
+        return;
+    }
+
+    // Existing breakpoints on the line already stop execution there.
+    let breakpoints = cx.editor.breakpoints.entry(path.clone()).or_default();
+    if !breakpoints.iter().any(|breakpoint| breakpoint.line == line) {
+        breakpoints.push(Breakpoint {
+            line,
+            temporary: true,
+            ..Default::default()
+        });
+
+        let debugger = debugger!(cx.editor);
+        if let Err(e) = breakpoints_changed(debugger, path, breakpoints) {
+            cx.editor
+                .set_error(format!("Failed to set breakpoints: {}", e));
+            return;
+        }
+    }
+
+    dap_continue(cx);
+}
+
+pub fn dap_goto_cursor(cx: &mut Context) {
+    let (view, doc) = current!(cx.editor);
+    let path = match doc.path() {
+        Some(path) => path.clone(),
+        None => {
+            cx.editor
+                .set_error("Can't jump to cursor: document has no path");
+            return;
+        }
+    };
+    let text = doc.text().slice(..);
+    let cursor = doc.selection(view.id).primary().cursor(text);
+    let line = text.char_to_line(cursor);
+    let column = cursor - text.line_to_char(line);
+
+    let debugger = debugger!(cx.editor);
+
+    if !debugger
+        .capabilities()
+        .supports_goto_targets_request
+        .unwrap_or_default()
+    {
+        cx.editor
+            .set_error("Debugger does not support jumping to a location");
+        return;
+    }
+
+    let Some(thread_id) = debugger.thread_id else {
+        cx.editor
+            .set_error("Currently active thread is not stopped. Switch the thread.");
+        return;
+    };
+
+    // convert from 0-indexing to 1-indexing
+    let targets = match block_on(debugger.goto_targets(path, line + 1, Some(column + 1))) {
+        Ok(targets) => targets,
+        Err(e) => {
+            cx.editor
+                .set_error(format!("Failed to get goto targets: {}", e));
+            return;
+        }
+    };
+    let Some(target) = targets.first() else {
+        cx.editor.set_error("No goto targets found at the cursor");
+        return;
+    };
+
+    let request = debugger.goto(thread_id, target.id);
+    dap_callback(cx.jobs, request, |editor, _compositor, _response: ()| {
+        // The debugger emits a "stopped" event once the instruction pointer was moved.
+        debugger!(editor).resume_application();
+    });
+}
+
 pub fn dap_variables(cx: &mut Context) {
     let debugger = debugger!(cx.editor);
 
@@ -537,57 +820,22 @@ pub fn dap_variables(cx: &mut Context) {
     };
 
     let frame_id = stack_frame.id;
-    let scopes = match block_on(debugger.scopes(frame_id)) {
-        Ok(s) => s,
+    let tree = match ui::dap::VariablesTree::new(debugger, frame_id) {
+        Ok(tree) => tree,
         Err(e) => {
             cx.editor.set_error(format!("Failed to get scopes: {}", e));
             return;
         }
     };
 
-    // TODO: allow expanding variables into sub-fields
-    let mut variables = Vec::new();
-
-    let theme = &cx.editor.theme;
-    let scope_style = theme.get("ui.linenr.selected");
-    let type_style = theme.get("ui.text");
-    let text_style = theme.get("ui.text.focus");
-
-    for scope in scopes.iter() {
-        // use helix_view::graphics::Style;
-        use tui::text::Span;
-        let response = block_on(debugger.variables(scope.variables_reference));
-
-        variables.push(Spans::from(Span::styled(
-            format!("▸ {}", scope.name),
-            scope_style,
-        )));
-
-        if let Ok(vars) = response {
-            variables.reserve(vars.len());
-            for var in vars {
-                let mut spans = Vec::with_capacity(5);
-
-                spans.push(Span::styled(var.name.to_owned(), text_style));
-                if let Some(ty) = var.ty {
-                    spans.push(Span::raw(": "));
-                    spans.push(Span::styled(ty.to_owned(), type_style));
-                }
-                spans.push(Span::raw(" = "));
-                spans.push(Span::styled(var.value.to_owned(), text_style));
-                variables.push(Spans::from(spans));
-            }
-        }
-    }
-
-    let contents = Text::from(tui::text::Text::from(variables));
-    let popup = Popup::new("dap-variables", contents);
-    cx.replace_or_push_layer("dap-variables", popup);
+    let popup = Popup::new(ui::dap::VariablesTree::ID, tree);
+    cx.replace_or_push_layer(ui::dap::VariablesTree::ID, popup);
 }
 
 pub fn dap_terminate(cx: &mut Context) {
     cx.editor.set_status("Terminating debug session...");
     let debugger = debugger!(cx.editor);
+    let id = debugger.id();
 
     if debugger
         .caps
@@ -599,12 +847,16 @@ pub fn dap_terminate(cx: &mut Context) {
         });
 
         let request = debugger.terminate(terminate_arguments);
-        dap_callback(cx.jobs, request, |editor, _compositor, _response: ()| {
-            // editor.set_error(format!("Failed to disconnect: {}", e));
-            editor.debug_adapters.unset_active_client();
-        });
+        dap_callback(
+            cx.jobs,
+            request,
+            move |editor, _compositor, _response: ()| {
+                // editor.set_error(format!("Failed to disconnect: {}", e));
+                editor.debug_adapters.unfocus_client(id);
+            },
+        );
     } else {
-        cx.editor.debug_adapters.unset_active_client();
+        cx.editor.debug_adapters.unfocus_client(id);
     }
 }
 
@@ -727,9 +979,261 @@ pub fn dap_edit_log(cx: &mut Context) {
 
 pub fn dap_switch_thread(cx: &mut Context) {
     thread_picker(cx, |editor, thread| {
-        block_on(select_thread_id(editor, thread.id, true));
+        let Some(id) = editor.debug_adapters.active_client_id() else {
+            return;
+        };
+        block_on(select_thread_id(editor, id, thread.id, true));
+    })
+}
+
+struct DebugSession {
+    id: DebugAdapterId,
+    name: String,
+    adapter: String,
+    paused: bool,
+    frame: Option<StackFrame>,
+}
+
+pub fn dap_switch_session(cx: &mut Context) {
+    let sessions: Vec<_> = cx
+        .editor
+        .debug_adapters
+        .clients()
+        .map(|client| {
+            // Indent child sessions below their parents.
+            let depth = std::iter::successors(client.parent, |&parent| {
+                cx.editor.debug_adapters.get_client(parent)?.parent
+            })
+            .count();
+            DebugSession {
+                id: client.id(),
+                name: format!("{}{}", "  ".repeat(depth), client.name),
+                adapter: client
+                    .config
+                    .as_ref()
+                    .map(|config| config.name.clone())
+                    .unwrap_or_default(),
+                paused: client.is_paused(),
+                frame: client.current_stack_frame().cloned(),
+            }
+        })
+        .collect();
+
+    if sessions.is_empty() {
+        cx.editor.set_error("Debugger is not running");
+        return;
+    }
+
+    let columns = [
+        ui::PickerColumn::new("session", |item: &DebugSession, _| {
+            item.name.as_str().into()
+        }),
+        ui::PickerColumn::new("adapter", |item: &DebugSession, _| {
+            item.adapter.as_str().into()
+        }),
+        ui::PickerColumn::new("state", |item: &DebugSession, _| {
+            if item.paused { "paused" } else { "running" }.into()
+        }),
+    ];
+    let picker = Picker::new(columns, 0, sessions, (), |cx, session, _action| {
+        cx.editor.debug_adapters.set_active_client(session.id);
+        if let Some(frame) = &session.frame {
+            jump_to_stack_frame(cx.editor, frame);
+        }
     })
+    .with_preview(|_editor, session| {
+        let frame = session.frame.as_ref()?;
+        let path = frame.source.as_ref()?.path.as_ref()?;
+        Some((
+            path.as_path().into(),
+            Some((
+                frame.line.saturating_sub(1),
+                frame.end_line.unwrap_or(frame.line).saturating_sub(1),
+            )),
+        ))
+    });
+    cx.push_layer(Box::new(picker))
 }
+
+pub fn dap_loaded_sources(cx: &mut Context) {
+    let debugger = debugger!(cx.editor);
+
+    if !debugger
+        .capabilities()
+        .supports_loaded_sources_request
+        .unwrap_or_default()
+    {
+        // Fall back to the sources announced through events.
+        let sources = debugger.loaded_sources.clone();
+        if sources.is_empty() {
+            cx.editor.set_error("Debugger has not reported any sources");
+            return;
+        }
+        cx.push_layer(Box::new(overlaid(loaded_sources_picker(sources))));
+        return;
+    }
+
+    dap_callback(
+        cx.jobs,
+        debugger.loaded_sources(),
+        |editor, compositor, response: dap::requests::LoadedSourcesResponse| {
+            if response.sources.is_empty() {
+                editor.set_error("Debugger has not loaded any sources");
+                return;
+            }
+            compositor.push(Box::new(overlaid(loaded_sources_picker(response.sources))));
+        },
+    );
+}
+
+fn loaded_sources_picker(sources: Vec<dap::Source>) -> Picker<dap::Source, ()> {
+    // Sources may group related sources, for example the original source of generated code.
+    fn flatten(sources: Vec<dap::Source>, flattened: &mut Vec<dap::Source>) {
+        for mut source in sources {
+            let children = source.sources.take().unwrap_or_default();
+            flattened.push(source);
+            flatten(children, flattened);
+        }
+    }
+    let mut flattened = Vec::new();
+    flatten(sources, &mut flattened);
+
+    let columns = [
+        ui::PickerColumn::new("name", |item: &dap::Source, _| {
+            source_name(item).unwrap_or_default().into()
+        }),
+        ui::PickerColumn::new("path", |item: &dap::Source, _| match &item.path {
+            Some(path) => path.to_string_lossy().into_owned().into(),
+            None => "".into(),
+        }),
+        ui::PickerColumn::new("origin", |item: &dap::Source, _| {
+            item.origin.as_deref().unwrap_or_default().into()
+        }),
+    ];
+    Picker::new(columns, 0, flattened, (), |cx, source, action| {
+        open_source(cx.editor, cx.jobs, source.clone(), action)
+    })
+    .with_preview(|_editor, source| {
+        let path = source.path.as_ref().filter(|path| path.is_file())?;
+        Some((path.as_path().into(), None))
+    })
+}
+
+fn source_name(source: &dap::Source) -> Option<&str> {
+    source.name.as_deref().or_else(|| {
+        source
+            .path
+            .as_ref()?
+            .file_name()
+            .and_then(|name| name.to_str())
+    })
+}
+
+/// Opens a source reported by the debugger. Sources which are not available on disk are fetched
+/// from the debugger into a read-only scratch buffer.
+fn open_source(editor: &mut Editor, jobs: &mut Jobs, source: dap::Source, action: Action) {
+    if let Some(path) = source.path.as_ref().filter(|path| path.is_file()) {
+        if let Err(err) = editor.open(path, action) {
+            editor.set_error(format!("Failed to open source: {err}"));
+        }
+        return;
+    }
+    if source.source_reference.unwrap_or_default() == 0 {
+        editor.set_error("Source is not available");
+        return;
+    }
+
+    let debugger = debugger!(editor);
+    let name = source_name(&source).map(PathBuf::from);
+    dap_callback(
+        jobs,
+        debugger.source(source),
+        move |editor, _compositor, response: dap::requests::SourceResponse| {
+            let mut doc = Document::from(
+                Rope::from(response.content),
+                None,
+                editor.config.clone(),
+                editor.syn_loader.clone(),
+            );
+            doc.readonly = true;
+            let loader = editor.syn_loader.load();
+            if let Some(language) = name
+                .as_deref()
+                .and_then(|name| loader.language_for_filename(name))
+            {
+                doc.set_language(Some(loader.language(language).config().clone()), &loader);
+            }
+            drop(loader);
+            editor.new_file_from_document(action, doc);
+        },
+    );
+}
+
+pub fn dap_modules(cx: &mut Context) {
+    let debugger = debugger!(cx.editor);
+
+    if !debugger
+        .capabilities()
+        .supports_modules_request
+        .unwrap_or_default()
+    {
+        // Fall back to the modules announced through events.
+        let modules = debugger.modules.clone();
+        if modules.is_empty() {
+            cx.editor.set_error("Debugger has not reported any modules");
+            return;
+        }
+        cx.push_layer(Box::new(overlaid(modules_picker(modules))));
+        return;
+    }
+
+    dap_callback(
+        cx.jobs,
+        debugger.modules(),
+        |editor, compositor, response: dap::requests::ModulesResponse| {
+            if response.modules.is_empty() {
+                editor.set_error("Debugger has not loaded any modules");
+                return;
+            }
+            compositor.push(Box::new(overlaid(modules_picker(response.modules))));
+        },
+    );
+}
+
+fn modules_picker(modules: Vec<dap::Module>) -> Picker<dap::Module, ()> {
+    let columns = [
+        ui::PickerColumn::new("name", |item: &dap::Module, _| item.name.as_str().into()),
+        ui::PickerColumn::new("version", |item: &dap::Module, _| {
+            item.version.as_deref().unwrap_or_default().into()
+        }),
+        ui::PickerColumn::new("symbols", |item: &dap::Module, _| {
+            item.symbol_status.as_deref().unwrap_or_default().into()
+        }),
+        ui::PickerColumn::new("user code", |item: &dap::Module, _| {
+            match item.is_user_code {
+                Some(true) => "yes",
+                Some(false) => "no",
+                None => "",
+            }
+            .into()
+        }),
+        ui::PickerColumn::new("path", |item: &dap::Module, _| match &item.path {
+            Some(path) => path.to_string_lossy().into_owned().into(),
+            None => "".into(),
+        }),
+    ];
+    Picker::new(columns, 0, modules, (), |cx, module, _action| {
+        let symbols = match &module.symbol_file_path {
+            Some(path) => format!("symbols loaded from {path}"),
+            None => module
+                .symbol_status
+                .clone()
+                .unwrap_or_else(|| "no symbol information".to_owned()),
+        };
+        cx.editor.set_status(format!("{}: {symbols}", module.name));
+    })
+}
+
 pub fn dap_switch_stack_frame(cx: &mut Context) {
     let debugger = debugger!(cx.editor);
 
@@ -6,10 +6,12 @@
 
 use super::*;
 
+use helix_core::align;
 use helix_core::command_line::{Args, Flag, Signature, Token, TokenKind};
 use helix_core::fuzzy::fuzzy_match;
 use helix_core::indent::MAX_INDENT;
 use helix_core::line_ending;
+use helix_core::sort;
 use helix_stdx::path::home_dir;
 use helix_view::document::{read_to_string, DEFAULT_LANGUAGE_NAME};
 use helix_view::editor::{CloseError, ConfigEvent};
@@ -384,6 +386,12 @@ fn write_impl(
     let jobs = &mut cx.jobs;
     let (view, doc) = current!(cx.editor);
 
+    if path.is_none() && doc.path().is_none() && doc.register().is_some() {
+        doc.append_changes_to_history(view);
+        let id = doc.id();
+        return write_register_buffer(cx.editor, id);
+    }
+
     if doc.trim_trailing_whitespace() {
         trim_trailing_whitespace(doc, view.id);
     }
@@ -422,6 +430,20 @@ fn write_impl(
     Ok(())
 }
 
+/// Writes the text of a register buffer, see [`helix_view::Document::register`], back into its
+/// register.
+fn write_register_buffer(editor: &mut Editor, doc_id: DocumentId) -> anyhow::Result<()> {
+    let doc = doc_mut!(editor, &doc_id);
+    let Some(register) = doc.register() else {
+        return Ok(());
+    };
+    let values = register::values_from_text(&doc.text().to_string());
+    editor.registers.write(register, values)?;
+    doc_mut!(editor, &doc_id).reset_modified();
+    editor.set_status(format!("Written to register {register}"));
+    Ok(())
+}
+
 /// Trim all whitespace preceding line-endings in a document.
 fn trim_trailing_whitespace(doc: &mut Document, view_id: ViewId) {
     let text = doc.text();
@@ -828,7 +850,7 @@ pub fn write_all_impl(
             if !doc.is_modified() {
                 return None;
             }
-            if doc.path().is_none() {
+            if doc.path().is_none() && doc.register().is_none() {
                 if options.write_scratch {
                     errors.push("cannot write a buffer without a filename");
                 }
@@ -845,6 +867,12 @@ pub fn write_all_impl(
         let doc = doc_mut!(cx.editor, &doc_id);
         let view = view_mut!(cx.editor, target_view);
 
+        if doc.path().is_none() {
+            doc.append_changes_to_history(view);
+            write_register_buffer(cx.editor, doc_id)?;
+            continue;
+        }
+
         if doc.trim_trailing_whitespace() {
             trim_trailing_whitespace(doc, target_view);
         }
@@ -2257,42 +2285,184 @@ fn language(cx: &mut compositor::Context, args: Args, event: PromptEvent) -> any
     Ok(())
 }
 
+fn sort_options(args: &Args) -> anyhow::Result<sort::SortOptions> {
+    let order = match (args.has_flag("numeric"), args.has_flag("natural")) {
+        (true, true) => bail!("--numeric and --natural cannot be combined"),
+        (true, false) => sort::SortOrder::Numeric,
+        (false, true) => sort::SortOrder::Natural,
+        (false, false) => sort::SortOrder::Lexical,
+    };
+    let separator = args.get_flag("separator");
+    let key = match (args.get_flag("key"), args.get_flag("field")) {
+        (Some(_), Some(_)) => bail!("--key and --field cannot be combined"),
+        (Some(regex), None) => sort::SortKey::Regex(
+            helix_core::regex::Regex::new(regex)
+                .map_err(|err| anyhow!("invalid key regex: {err}"))?,
+        ),
+        (None, Some(field)) => {
+            let index = field
+                .parse()
+                .ok()
+                .filter(|&index| index > 0)
+                .ok_or_else(|| anyhow!("invalid field '{field}', fields are numbered from 1"))?;
+            sort::SortKey::Field {
+                index,
+                separator: separator.map(ToOwned::to_owned),
+            }
+        }
+        (None, None) => {
+            ensure!(separator.is_none(), "--separator requires --field");
+            sort::SortKey::Whole
+        }
+    };
+
+    Ok(sort::SortOptions {
+        order,
+        key,
+        insensitive: args.has_flag("insensitive"),
+        reverse: args.has_flag("reverse"),
+        unique: args.has_flag("unique"),
+        stable: args.has_flag("stable"),
+    })
+}
+
 fn sort(cx: &mut compositor::Context, args: Args, event: PromptEvent) -> anyhow::Result<()> {
     if event != PromptEvent::Validate {
         return Ok(());
     }
 
+    let options = sort_options(&args)?;
     let scrolloff = cx.editor.config().scrolloff;
     let (view, doc) = current!(cx.editor);
     let text = doc.text().slice(..);
 
     let selection = doc.selection(view.id);
 
-    if selection.len() == 1 {
-        bail!("Sorting requires multiple selections. Hint: split selection first");
+    let transaction = if selection.len() == 1 {
+        // Sort the lines within a single selection.
+        let range = selection.primary();
+        let (start_line, end_line) = range.line_range(text);
+        if start_line == end_line {
+            bail!("Sorting requires multiple selections or lines. Hint: split selection first");
+        }
+
+        let fragment = range.fragment(text);
+        let (lines, line_ending) = match line_ending::get_line_ending_of_str(&fragment) {
+            Some(line_ending) => (
+                &fragment[..fragment.len() - line_ending.len_chars()],
+                line_ending.as_str(),
+            ),
+            None => (&*fragment, ""),
+        };
+        let mut sorted =
+            sort::sort(lines.lines().collect(), &options).join(doc.line_ending.as_str());
+        sorted.push_str(line_ending);
+
+        let (from, to) = (range.from(), range.to());
+        let len = sorted.chars().count();
+        let sorted_range = Range::new(from, from + len).with_direction(range.direction());
+        Transaction::change(doc.text(), [(from, to, Some(sorted.into()))].into_iter())
+            .with_selection(Selection::single(sorted_range.anchor, sorted_range.head))
+    } else {
+        let fragments: Vec<Tendril> = selection
+            .slices(text)
+            .map(|fragment| fragment.chunks().collect())
+            .collect();
+        let fragments = sort::sort(fragments, &options);
+        let kept = fragments.len();
+
+        let mut ranges = SmallVec::with_capacity(kept);
+        let mut offset = 0isize;
+        let mut changes: Vec<_> = selection
+            .iter()
+            .zip(fragments)
+            .map(|(range, fragment)| {
+                let from = range.from().saturating_add_signed(offset);
+                let len = fragment.chars().count();
+                ranges.push(Range::new(from, from + len).with_direction(range.direction()));
+                offset += len as isize - range.len() as isize;
+                (range.from(), range.to(), Some(fragment))
+            })
+            .collect();
+        // Selections which are left over after removing duplicates are deleted along with the
+        // text separating them from the previous selection.
+        if kept < selection.len() {
+            let ranges = selection.ranges();
+            changes.push((ranges[kept - 1].to(), ranges[ranges.len() - 1].to(), None));
+        }
+
+        let primary_index = selection.primary_index().min(kept - 1);
+        Transaction::change(doc.text(), changes.into_iter())
+            .with_selection(Selection::new(ranges, primary_index))
+    };
+
+    doc.apply(&transaction, view.id);
+    doc.append_changes_to_history(view);
+    view.ensure_cursor_in_view(doc, scrolloff);
+
+    Ok(())
+}
+
+fn align(cx: &mut compositor::Context, args: Args, event: PromptEvent) -> anyhow::Result<()> {
+    if event != PromptEvent::Validate {
+        return Ok(());
     }
 
-    let mut fragments: Vec<_> = selection
-        .slices(text)
-        .map(|fragment| fragment.chunks().collect())
-        .collect();
+    let markdown = args.has_flag("markdown");
+    let delimiter = match args.first() {
+        Some(regex) => regex,
+        None if markdown => r"\|",
+        None => bail!("Bad arguments. Usage: `:align <regex>`"),
+    };
+    let delimiter = helix_core::regex::Regex::new(delimiter)
+        .map_err(|err| anyhow!("invalid delimiter regex: {err}"))?;
+    let justify = args
+        .get_flag("justify")
+        .unwrap_or_default()
+        .chars()
+        .map(|c| {
+            align::Justify::from_char(c)
+                .ok_or_else(|| anyhow!("invalid justification '{c}', expected 'l', 'r' or 'c'"))
+        })
+        .collect::<anyhow::Result<_>>()?;
+    let max_columns = args
+        .get_flag("max-columns")
+        .map(|max| {
+            max.parse()
+                .ok()
+                .filter(|&max| max > 0)
+                .ok_or_else(|| anyhow!("invalid number of columns '{max}'"))
+        })
+        .transpose()?;
+    let options = align::AlignOptions {
+        justify,
+        max_columns,
+        markdown,
+    };
 
-    fragments.sort_by(
-        match (args.has_flag("insensitive"), args.has_flag("reverse")) {
-            (true, true) => |a: &Tendril, b: &Tendril| b.to_lowercase().cmp(&a.to_lowercase()),
-            (true, false) => |a: &Tendril, b: &Tendril| a.to_lowercase().cmp(&b.to_lowercase()),
-            (false, true) => |a: &Tendril, b: &Tendril| b.cmp(a),
-            (false, false) => |a: &Tendril, b: &Tendril| a.cmp(b),
-        },
-    );
+    let scrolloff = cx.editor.config().scrolloff;
+    let (view, doc) = current!(cx.editor);
+    let text = doc.text().slice(..);
 
-    let transaction = Transaction::change(
-        doc.text(),
-        selection
-            .into_iter()
-            .zip(fragments)
-            .map(|(s, fragment)| (s.from(), s.to(), Some(fragment))),
-    );
+    // All selected lines are aligned together.
+    let lines: Vec<_> = get_lines(doc, view.id)
+        .into_iter()
+        .map(|line| {
+            let start = text.line_to_char(line);
+            let end = line_ending::line_end_char_index(&text, line);
+            (start, end, Cow::from(text.slice(start..end)))
+        })
+        .collect();
+    let contents: Vec<_> = lines.iter().map(|(_, _, line)| line.as_ref()).collect();
+    let aligned = align::align(&contents, &delimiter, &options);
+
+    let changes = lines
+        .iter()
+        .zip(aligned)
+        .filter(|((_, _, line), aligned)| line != aligned)
+        .map(|(&(start, end, _), aligned)| (start, end, Some(aligned.into())))
+        .collect::<Vec<_>>();
+    let transaction = Transaction::change(doc.text(), changes.into_iter());
 
     doc.apply(&transaction, view.id);
     doc.append_changes_to_history(view);
@@ -2320,13 +2490,18 @@ fn reflow(cx: &mut compositor::Context, args: Args, event: PromptEvent) -> anyho
         .unwrap_or_else(|| doc.text_width());
 
     let rope = doc.text();
+    let comment_tokens: Vec<&str> = doc
+        .language_config()
+        .and_then(|config| config.comment_tokens.as_ref())
+        .map(|tokens| tokens.iter().map(String::as_str).collect())
+        .unwrap_or_default();
 
     let selection = doc.selection(view.id);
     let transaction = Transaction::change_by_selection(rope, selection, |range| {
         let fragment = range.fragment(rope.slice(..));
-        let reflowed_text = helix_core::wrap::reflow_hard_wrap(&fragment, text_width);
+        let reflowed_text = helix_core::wrap::reflow(&fragment, text_width, &comment_tokens);
 
-        (range.from(), range.to(), Some(reflowed_text))
+        (range.from(), range.to(), Some(reflowed_text.into()))
     });
 
     doc.apply(&transaction, view.id);
@@ -3573,7 +3748,7 @@ fn noop(_cx: &mut compositor::Context, _args: Args, _event: PromptEvent) -> anyh
     TypableCommand {
         name: "sort",
         aliases: &[],
-        doc: "Sort ranges in selection.",
+        doc: "Sort ranges in selection, or the lines of a single selection.",
         fun: sort,
         completer: CommandCompleter::none(),
         signature: Signature {
@@ -3591,6 +3766,79 @@ fn noop(_cx: &mut compositor::Context, _args: Args, _event: PromptEvent) -> anyh
                     doc: "sort ranges in reverse order",
                     ..Flag::DEFAULT
                 },
+                Flag {
+                    name: "numeric",
+                    alias: Some('n'),
+                    doc: "sort by the first number in each range",
+                    ..Flag::DEFAULT
+                },
+                Flag {
+                    name: "natural",
+                    alias: Some('V'),
+                    doc: "sort numbers within the text by value, like versions",
+                    ..Flag::DEFAULT
+                },
+                Flag {
+                    name: "key",
+                    alias: Some('k'),
+                    doc: "sort by the match of a regex or its first capture group",
+                    completions: Some(&[]),
+                },
+                Flag {
+                    name: "field",
+                    alias: Some('f'),
+                    doc: "sort by the given field, starting at 1",
+                    completions: Some(&[]),
+                },
+                Flag {
+                    name: "separator",
+                    alias: Some('t'),
+                    doc: "separate fields by the given text instead of whitespace",
+                    completions: Some(&[]),
+                },
+                Flag {
+                    name: "unique",
+                    alias: Some('u'),
+                    doc: "remove ranges with the same key as a previous range",
+                    ..Flag::DEFAULT
+                },
+                Flag {
+                    name: "stable",
+                    alias: Some('s'),
+                    doc: "keep ranges with equal keys in their original order",
+                    ..Flag::DEFAULT
+                },
+            ],
+            ..Signature::DEFAULT
+        },
+    },
+    TypableCommand {
+        name: "align",
+        aliases: &[],
+        doc: "Align the selected lines into columns on the matches of a delimiter regex.",
+        fun: align,
+        completer: CommandCompleter::none(),
+        signature: Signature {
+            positionals: (0, Some(1)),
+            flags: &[
+                Flag {
+                    name: "justify",
+                    alias: Some('j'),
+                    doc: "justify each column with 'l', 'r' or 'c', e.g. 'lrc'; the last one repeats",
+                    completions: Some(&[]),
+                },
+                Flag {
+                    name: "max-columns",
+                    alias: Some('c'),
+                    doc: "split lines into at most this many columns",
+                    completions: Some(&[]),
+                },
+                Flag {
+                    name: "markdown",
+                    alias: Some('m'),
+                    doc: "align a Markdown table, splitting on '|' and normalizing the separator row",
+                    ..Flag::DEFAULT
+                },
             ],
             ..Signature::DEFAULT
         },
@@ -3598,7 +3846,7 @@ fn noop(_cx: &mut compositor::Context, _args: Args, _event: PromptEvent) -> anyh
     TypableCommand {
         name: "reflow",
         aliases: &[],
-        doc: "Hard-wrap the current selection of lines to a given width.",
+        doc: "Hard-wrap the current selection of lines to a given width, keeping the prefixes of comments, quotes and list items.",
         fun: reflow,
         completer: CommandCompleter::none(),
         signature: Signature {
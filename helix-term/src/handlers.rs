@@ -13,14 +13,18 @@
 pub use helix_view::handlers::{word_index, Handlers};
 
 use self::document_colors::DocumentColorsHandler;
+use self::spelling::SpellingHandler;
 
 mod auto_save;
+mod block_selection;
 pub mod completion;
+mod dap;
 pub mod diagnostics;
 mod document_colors;
 mod prompt;
 mod signature_help;
 mod snippet;
+mod spelling;
 
 pub fn setup(config: Arc<ArcSwap<Config>>) -> Handlers {
     events::register();
@@ -29,6 +33,7 @@ pub fn setup(config: Arc<ArcSwap<Config>>) -> Handlers {
     let signature_hints = SignatureHelpHandler::new().spawn();
     let auto_save = AutoSaveHandler::new().spawn();
     let document_colors = DocumentColorsHandler::default().spawn();
+    let spelling = SpellingHandler::default().spawn();
     let word_index = word_index::Handler::spawn();
     let pull_diagnostics = PullDiagnosticsHandler::default().spawn();
     let pull_all_documents_diagnostics = PullAllDocumentsDiagnosticHandler::default().spawn();
@@ -38,6 +43,7 @@ pub fn setup(config: Arc<ArcSwap<Config>>) -> Handlers {
         signature_hints,
         auto_save,
         document_colors,
+        spelling,
         word_index,
         pull_diagnostics,
         pull_all_documents_diagnostics,
@@ -49,7 +55,10 @@ pub fn setup(config: Arc<ArcSwap<Config>>) -> Handlers {
     auto_save::register_hooks(&handlers);
     diagnostics::register_hooks(&handlers);
     snippet::register_hooks(&handlers);
+    block_selection::register_hooks(&handlers);
     document_colors::register_hooks(&handlers);
+    spelling::register_hooks(&handlers);
     prompt::register_hooks(&handlers);
+    dap::register_hooks(&handlers);
     handlers
 }
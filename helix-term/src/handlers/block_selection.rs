@@ -0,0 +1,80 @@
+use helix_core::line_ending::line_end_char_index;
+#[allow(deprecated)]
+use helix_core::visual_coords_at_pos;
+use helix_event::register_hook;
+use helix_view::document::Mode;
+use helix_view::handlers::Handlers;
+use helix_view::view::ActiveBlockSelection;
+
+use crate::commands::MappableCommand;
+use crate::events::{OnModeSwitch, PostCommand};
+
+/// Moves the head of the block selection along with the primary cursor whenever a movement
+/// changed the selection and converts the block back into one range per line.
+///
+/// Typed commands which change the selection, like `:goto`, move the cursor as well. Macros
+/// replay their keys as separate commands. Any other command ends the block selection, keeping
+/// its ranges, so that commands like `s`, `C` or a search which replace the selection (possibly
+/// later from a prompt) aren't undone by the next movement.
+fn block_selection_post_command_hook(
+    PostCommand { command, cx }: &mut PostCommand<'_, '_>,
+) -> anyhow::Result<()> {
+    let virtual_space = cx.editor.config().block_virtual_space;
+    let mode = cx.editor.mode;
+    let (view, doc) = current!(cx.editor);
+    let Some(active) = &view.block_selection else {
+        return Ok(());
+    };
+    if mode != Mode::Select {
+        view.block_selection = None;
+        return Ok(());
+    }
+    match command {
+        MappableCommand::Macro { .. } => return Ok(()),
+        MappableCommand::Static { .. } if !command.is_movement() => {
+            if command.name() != MappableCommand::select_block_mode.name() {
+                view.block_selection = None;
+            }
+            return Ok(());
+        }
+        _ => (),
+    }
+    let selection = doc.selection(view.id);
+    if *selection == active.selection {
+        return Ok(());
+    }
+
+    let text = doc.text().slice(..);
+    let tab_width = doc.tab_width();
+    let cursor_pos = selection.primary().cursor(text);
+    #[allow(deprecated)]
+    let cursor = visual_coords_at_pos(text, cursor_pos, tab_width);
+    let mut block = active.block;
+    // Move the head by the distance the cursor moved. Vertical movements clamp the cursor to
+    // the end of lines which are too short to reach the head, in which case the column of the
+    // head is kept.
+    block.head.row = (block.head.row + cursor.row).saturating_sub(active.cursor.row);
+    let clamped = cursor.row != active.cursor.row
+        && cursor_pos == line_end_char_index(&text, cursor.row)
+        && cursor.col < block.head.col;
+    if !clamped {
+        block.head.col = (block.head.col + cursor.col).saturating_sub(active.cursor.col);
+    }
+    let active = ActiveBlockSelection::new(block, text, tab_width, virtual_space);
+    doc.set_selection(view.id, active.selection.clone());
+    view.block_selection = Some(active);
+    Ok(())
+}
+
+pub(super) fn register_hooks(_handlers: &Handlers) {
+    register_hook!(move |event: &mut PostCommand<'_, '_>| block_selection_post_command_hook(event));
+
+    register_hook!(move |event: &mut OnModeSwitch<'_, '_>| {
+        if event.old_mode == Mode::Select {
+            for (view, _) in event.cx.editor.tree.views_mut() {
+                view.block_selection = None;
+            }
+        }
+        Ok(())
+    });
+}
@@ -30,6 +30,7 @@
 mod path;
 mod request;
 mod resolve;
+mod snippet;
 mod word;
 
 async fn handle_response(
@@ -28,7 +28,7 @@
 use crate::ui;
 use crate::ui::editor::InsertEvent;
 
-use super::word;
+use super::{snippet, word};
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub(super) enum TriggerKind {
@@ -248,6 +248,11 @@ fn request_completions(
     ) {
         requests.spawn_blocking(path_completion_request);
     }
+    if let Some(snippet_completion_response) =
+        snippet::completion(editor, trigger, savepoint.clone())
+    {
+        requests.spawn(async move { snippet_completion_response });
+    }
     if let Some(word_completion_request) =
         word::completion(editor, trigger, handle.clone(), savepoint)
     {
@@ -0,0 +1,109 @@
+use std::{borrow::Cow, sync::Arc};
+
+use helix_core::{
+    self as core, chars::char_is_word, completion::CompletionProvider,
+    snippets::library::UserSnippet,
+};
+use helix_view::{document::SavePoint, handlers::completion::ResponseContext, Editor};
+
+use super::{request::TriggerKind, CompletionItem, CompletionItems, CompletionResponse, Trigger};
+
+const COMPLETION_KIND: &str = "snippet";
+
+/// Completes the prefixes of the user-defined snippets of the document's language. The snippets
+/// are rendered here since rendering needs the document to resolve snippet variables, so only the
+/// snippets with a prefix matching the typed word are rendered.
+pub(super) fn completion(
+    editor: &Editor,
+    trigger: Trigger,
+    savepoint: Arc<SavePoint>,
+) -> Option<CompletionResponse> {
+    let (view, doc) = current_ref!(editor);
+    let mut snippets = editor
+        .snippets
+        .snippets(doc.language_name())
+        .filter(|snippet| !snippet.prefixes.is_empty())
+        .peekable();
+    snippets.peek()?;
+
+    let text = doc.text().slice(..);
+    let selection = doc.selection(view.id);
+    let pos = selection.primary().cursor(text);
+    let typed_len = text
+        .chars_at(pos)
+        .reversed()
+        .take_while(|&ch| char_is_word(ch))
+        .count();
+    if typed_len == 0 && trigger.kind != TriggerKind::Manual {
+        return None;
+    }
+    let typed: String = text.slice(pos - typed_len..pos).into();
+
+    let mut items = Vec::new();
+    for snippet in snippets {
+        let mut prefixes = snippet
+            .prefixes
+            .iter()
+            .filter(|prefix| matches_typed(prefix, &typed))
+            .peekable();
+        if prefixes.peek().is_none() {
+            continue;
+        }
+        let (transaction, mapped_selection, rendered) = snippet.snippet.render(
+            doc.text(),
+            selection,
+            |range| {
+                let cursor = range.cursor(text);
+                (cursor.saturating_sub(typed_len), cursor)
+            },
+            &mut doc.snippet_ctx(view.id),
+        );
+        let transaction = transaction.with_selection(rendered.first_selection(
+            selection.primary().direction(),
+            mapped_selection.primary_index(),
+        ));
+        let documentation = documentation(snippet);
+        items.extend(prefixes.map(|prefix| {
+            CompletionItem::Other(core::CompletionItem {
+                transaction: transaction.clone(),
+                label: prefix.clone().into(),
+                kind: Cow::Borrowed(COMPLETION_KIND),
+                documentation: Some(documentation.clone()),
+                provider: CompletionProvider::Snippet,
+                snippet: Some(rendered.clone()),
+            })
+        }));
+    }
+
+    Some(CompletionResponse {
+        items: CompletionItems::Other(items),
+        provider: CompletionProvider::Snippet,
+        context: ResponseContext {
+            is_incomplete: false,
+            priority: 0,
+            savepoint,
+        },
+    })
+}
+
+/// Whether `prefix` contains the characters of `typed` in order, ignoring case. The completion
+/// menu fuzzy matches the typed word against its items, which never matches other prefixes.
+fn matches_typed(prefix: &str, typed: &str) -> bool {
+    let mut prefix = prefix.chars().flat_map(char::to_lowercase);
+    typed
+        .chars()
+        .flat_map(char::to_lowercase)
+        .all(|ch| prefix.any(|prefix_ch| prefix_ch == ch))
+}
+
+fn documentation(snippet: &UserSnippet) -> String {
+    let mut documentation = format!("**{}**\n\n", snippet.name);
+    if let Some(description) = &snippet.description {
+        documentation.push_str(description);
+        documentation.push_str("\n\n");
+    }
+    documentation.push_str("```\n");
+    documentation.push_str(&snippet.body);
+    documentation.push_str("\n```");
+    documentation
+}
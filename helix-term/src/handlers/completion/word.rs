@@ -87,6 +87,7 @@ pub(super) fn completion(
                     kind: Cow::Borrowed(COMPLETION_KIND),
                     documentation: None,
                     provider: CompletionProvider::Word,
+                    snippet: None,
                 })
             })
             .collect();
@@ -125,7 +126,7 @@ pub(super) fn retain_valid_completions(
             !matches!(
                 item,
                 CompletionItem::Other(core::CompletionItem {
-                    provider: CompletionProvider::Word,
+                    provider: CompletionProvider::Word | CompletionProvider::Snippet,
                     ..
                 })
             )
@@ -0,0 +1,96 @@
+use std::fmt::Write;
+
+use helix_dap::{requests::ExceptionInfoResponse, ExceptionDetails};
+use helix_event::register_hook;
+use helix_view::events::DebuggerStoppedOnException;
+use helix_view::handlers::Handlers;
+
+use crate::job;
+use crate::ui::{self, Popup};
+
+const EXCEPTION_POPUP_ID: &str = "dap-exception";
+
+pub(super) fn register_hooks(_handlers: &Handlers) {
+    register_hook!(move |event: &mut DebuggerStoppedOnException<'_>| {
+        let Some(exception) = event
+            .editor
+            .debug_adapters
+            .get_client(event.debugger)
+            .and_then(|debugger| debugger.exceptions.get(&event.thread_id))
+        else {
+            return Ok(());
+        };
+        let contents = exception_markdown(exception);
+        job::dispatch_blocking(move |editor, compositor| {
+            let contents = ui::Markdown::new(contents, editor.syn_loader.clone());
+            let popup = Popup::new(EXCEPTION_POPUP_ID, contents).auto_close(true);
+            compositor.replace_or_push(EXCEPTION_POPUP_ID, popup);
+        });
+        Ok(())
+    });
+}
+
+fn exception_markdown(exception: &ExceptionInfoResponse) -> String {
+    let mut markdown = format!("**{}**", exception.exception_id);
+    if let Some(description) = &exception.description {
+        write!(markdown, "\n\n{description}").unwrap();
+    }
+    if let Some(details) = &exception.details {
+        write_exception_details(&mut markdown, details);
+    }
+    markdown
+}
+
+fn write_exception_details(markdown: &mut String, details: &ExceptionDetails) {
+    if let Some(type_name) = details
+        .full_type_name
+        .as_ref()
+        .or(details.type_name.as_ref())
+    {
+        write!(markdown, "\n\n`{type_name}`").unwrap();
+    }
+    if let Some(message) = &details.message {
+        write!(markdown, "\n\n{message}").unwrap();
+    }
+    if let Some(stack_trace) = &details.stack_trace {
+        write!(markdown, "\n\n```\n{}\n```", stack_trace.trim_end()).unwrap();
+    }
+    for inner in details.inner_exception.iter().flatten() {
+        markdown.push_str("\n\n---\n\n**Inner exception**");
+        write_exception_details(markdown, inner);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn nested_exception_markdown() {
+        let exception = ExceptionInfoResponse {
+            exception_id: "System.Exception".to_owned(),
+            description: Some("outer".to_owned()),
+            break_mode: "unhandled".to_owned(),
+            details: Some(ExceptionDetails {
+                message: None,
+                type_name: Some("Exception".to_owned()),
+                full_type_name: None,
+                evaluate_name: None,
+                stack_trace: Some("at Main()\n".to_owned()),
+                inner_exception: Some(vec![ExceptionDetails {
+                    message: Some("inner".to_owned()),
+                    type_name: None,
+                    full_type_name: Some("System.IO.IOException".to_owned()),
+                    evaluate_name: None,
+                    stack_trace: None,
+                    inner_exception: None,
+                }]),
+            }),
+        };
+        assert_eq!(
+            exception_markdown(&exception),
+            "**System.Exception**\n\nouter\n\n`Exception`\n\n```\nat Main()\n```\
+             \n\n---\n\n**Inner exception**\n\n`System.IO.IOException`\n\ninner"
+        );
+    }
+}
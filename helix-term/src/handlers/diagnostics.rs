@@ -233,6 +233,8 @@ fn request_document_diagnostics_for_language_severs(
                         retry_language_servers.insert(server_id);
                     }
                 }
+                // Diagnostics are only pulled from language servers.
+                Some(Some((Err(_), DiagnosticProvider::Spelling, _))) => continue,
                 Some(None) => break,
                 // The request was cancelled.
                 None => return,
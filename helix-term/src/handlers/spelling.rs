@@ -0,0 +1,157 @@
+use std::{collections::HashSet, time::Duration};
+
+use helix_core::{
+    diagnostic::{self, DiagnosticProvider, Severity},
+    spelling, Diagnostic,
+};
+use helix_event::register_hook;
+use helix_view::{
+    editor::load_dictionary,
+    events::{ConfigDidChange, DocumentDidChange, DocumentDidOpen},
+    handlers::{Handlers, SpellingEvent},
+    DocumentId, Editor,
+};
+use tokio::time::Instant;
+
+use crate::job;
+
+#[derive(Default)]
+pub(super) struct SpellingHandler {
+    docs: HashSet<DocumentId>,
+}
+
+const DOCUMENT_CHANGE_DEBOUNCE: Duration = Duration::from_millis(250);
+
+impl helix_event::AsyncHook for SpellingHandler {
+    type Event = SpellingEvent;
+
+    fn handle_event(&mut self, event: Self::Event, _timeout: Option<Instant>) -> Option<Instant> {
+        let SpellingEvent(doc_id) = event;
+        self.docs.insert(doc_id);
+        Some(Instant::now() + DOCUMENT_CHANGE_DEBOUNCE)
+    }
+
+    fn finish_debounce(&mut self) {
+        let docs = std::mem::take(&mut self.docs);
+
+        job::dispatch_blocking(move |editor, _compositor| {
+            for doc in docs {
+                check_spelling(editor, doc);
+            }
+        });
+    }
+}
+
+/// Replaces the spelling diagnostics of the document, or clears them if spell checking is
+/// disabled.
+///
+/// The checked ranges are found with the document's syntax tree. The words in them are checked
+/// in a blocking task on a snapshot of the text, so that loading the dictionary and checking
+/// large documents doesn't block the UI. The diagnostics are dropped if the document changed in
+/// the meantime since it's checked again.
+fn check_spelling(editor: &mut Editor, doc_id: DocumentId) {
+    let config = editor.config();
+    let dictionary_name = config.spelling.language.clone();
+    let dictionary = editor.dictionary(&dictionary_name);
+    let loader = editor.syn_loader.load();
+    let Some(doc) = editor.document_mut(doc_id) else {
+        return;
+    };
+    if !config.spelling.enable {
+        if doc
+            .diagnostics()
+            .iter()
+            .any(|diagnostic| diagnostic.provider == DiagnosticProvider::Spelling)
+        {
+            doc.replace_diagnostics([], &[], Some(&DiagnosticProvider::Spelling));
+        }
+        return;
+    }
+    // The dictionary couldn't be loaded, which was already reported.
+    if matches!(dictionary.get(), Some(None)) {
+        return;
+    }
+
+    let scopes = doc
+        .language_config()
+        .and_then(|config| config.spelling_scopes.as_deref())
+        .unwrap_or(&config.spelling.scopes);
+    let text = doc.text().clone();
+    let ranges = spelling::checked_ranges(text.slice(..), doc.syntax(), &loader, scopes);
+    let version = doc.version();
+
+    tokio::task::spawn_blocking(move || {
+        let checker = match load_dictionary(&dictionary, &dictionary_name) {
+            Ok(Some(checker)) => checker,
+            Ok(None) => return,
+            Err(err) => {
+                job::dispatch_blocking(move |editor, _| {
+                    editor.set_error(format!("Failed to load the spelling dictionary: {err}"));
+                });
+                return;
+            }
+        };
+
+        let misspellings = checker.read().misspellings(text.slice(..), &ranges);
+        let diagnostics: Vec<_> = misspellings
+            .into_iter()
+            .map(|range| Diagnostic {
+                range: diagnostic::Range {
+                    start: range.start,
+                    end: range.end,
+                },
+                ends_at_word: true,
+                starts_at_word: true,
+                zero_width: false,
+                line: text.char_to_line(range.start),
+                message: format!("Unknown word '{}'", text.slice(range)),
+                severity: Some(Severity::Info),
+                code: None,
+                provider: DiagnosticProvider::Spelling,
+                tags: Vec::new(),
+                source: Some("spelling".to_string()),
+                data: None,
+            })
+            .collect();
+
+        job::dispatch_blocking(move |editor, _| {
+            let Some(doc) = editor.document_mut(doc_id) else {
+                return;
+            };
+            if doc.version() == version {
+                doc.replace_diagnostics(diagnostics, &[], Some(&DiagnosticProvider::Spelling));
+            }
+        });
+    });
+}
+
+pub(super) fn register_hooks(handlers: &Handlers) {
+    let tx = handlers.spelling.clone();
+    register_hook!(move |event: &mut DocumentDidOpen<'_>| {
+        if event.editor.config().spelling.enable {
+            helix_event::send_blocking(&tx, SpellingEvent(event.doc));
+        }
+
+        Ok(())
+    });
+
+    let tx = handlers.spelling.clone();
+    register_hook!(move |event: &mut DocumentDidChange<'_>| {
+        helix_event::send_blocking(&tx, SpellingEvent(event.doc.id()));
+
+        Ok(())
+    });
+
+    let tx = handlers.spelling.clone();
+    register_hook!(move |event: &mut ConfigDidChange<'_>| {
+        // Check all documents again since the dictionary or the checked scopes may have changed
+        // or spell checking was turned on or off.
+        if event.old.spelling != event.new.spelling {
+            for doc in event.editor.documents() {
+                helix_event::send_blocking(&tx, SpellingEvent(doc.id()));
+            }
+        }
+
+        Ok(())
+    });
+}
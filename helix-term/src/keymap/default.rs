@@ -22,6 +22,17 @@ pub fn default() -> HashMap<Mode, KeyTrie> {
         "~" => switch_case,
         "`" => switch_to_lowercase,
         "A-`" => switch_to_uppercase,
+        "A-~" => { "Convert case"
+            "s" => switch_to_snake_case,
+            "S" => switch_to_screaming_snake_case,
+            "k" => switch_to_kebab_case,
+            "c" => switch_to_camel_case,
+            "p" => switch_to_pascal_case,
+            "t" => switch_to_title_case,
+            "d" => switch_to_dot_case,
+            "u" => switch_to_uppercase,
+            "l" => switch_to_lowercase,
+        },
 
         "home" => goto_line_start,
         "end" => goto_line_end,
@@ -35,6 +46,7 @@ pub fn default() -> HashMap<Mode, KeyTrie> {
         "E" => move_next_long_word_end,
 
         "v" => select_mode,
+        "C-v" => select_block_mode,
         "G" => goto_line,
         "g" => { "Goto"
             "g" => goto_file_start,
@@ -111,6 +123,7 @@ pub fn default() -> HashMap<Mode, KeyTrie> {
         "[" => { "Left bracket"
             "d" => goto_prev_diag,
             "D" => goto_first_diag,
+            "s" => goto_prev_misspelling,
             "g" => goto_prev_change,
             "G" => goto_first_change,
             "f" => goto_prev_function,
@@ -126,6 +139,7 @@ pub fn default() -> HashMap<Mode, KeyTrie> {
         "]" => { "Right bracket"
             "d" => goto_next_diag,
             "D" => goto_last_diag,
+            "s" => goto_next_misspelling,
             "g" => goto_next_change,
             "G" => goto_last_change,
             "f" => goto_next_function,
@@ -167,6 +181,8 @@ pub fn default() -> HashMap<Mode, KeyTrie> {
         "A-J" => join_selections_space,
         "K" => keep_selections,
         "A-K" => remove_selections,
+        "A-j" => move_lines_down,
+        "A-k" => move_lines_up,
 
         "," => keep_primary_selection,
         "A-," => remove_primary_selection,
@@ -221,6 +237,8 @@ pub fn default() -> HashMap<Mode, KeyTrie> {
         "C-i" | "tab" => jump_forward, // tab == <C-i>
         "C-o" => jump_backward,
         "C-s" => save_selection,
+        "M" => set_mark,
+        "'" => goto_mark,
 
         "space" => { "Space"
             "f" => file_picker,
@@ -229,6 +247,9 @@ pub fn default() -> HashMap<Mode, KeyTrie> {
             "E" => file_explorer_in_current_buffer_directory,
             "b" => buffer_picker,
             "j" => jumplist_picker,
+            "m" => mark_picker,
+            "\"" => register_picker,
+            "n" => snippet_picker,
             "s" => lsp_or_syntax_symbol_picker,
             "S" => lsp_or_syntax_workspace_symbol_picker,
             "d" => diagnostics_picker,
@@ -245,13 +266,20 @@ pub fn default() -> HashMap<Mode, KeyTrie> {
                 "i" => dap_step_in,
                 "o" => dap_step_out,
                 "n" => dap_next,
+                "p" => dap_step_back,
+                "C" => dap_reverse_continue,
+                "u" => dap_run_to_cursor,
+                "g" => dap_goto_cursor,
                 "v" => dap_variables,
+                "L" => dap_loaded_sources,
+                "m" => dap_modules,
                 "t" => dap_terminate,
                 "C-c" => dap_edit_condition,
                 "C-l" => dap_edit_log,
                 "s" => { "Switch"
                     "t" => dap_switch_thread,
                     "f" => dap_switch_stack_frame,
+                    "s" => dap_switch_session,
                     // sl, sb
                 },
                 "e" => dap_enable_exceptions,
@@ -290,6 +318,7 @@ pub fn default() -> HashMap<Mode, KeyTrie> {
             "h" => select_references_to_symbol_under_cursor,
             "c" => toggle_comments,
             "C" => toggle_block_comments,
+            "u" => undo_tree,
             "A-c" => toggle_line_comments,
             "?" => command_palette,
         },
@@ -245,9 +245,11 @@ macro_rules! language_server {
                                 snippet,
                             )
                         }
-                        CompletionItem::Other(core::CompletionItem { transaction, .. }) => {
-                            (transaction, None, None)
-                        }
+                        CompletionItem::Other(core::CompletionItem {
+                            transaction,
+                            snippet,
+                            ..
+                        }) => (transaction, None, snippet),
                     };
 
                     doc.apply(&transaction, view.id);
@@ -641,7 +643,7 @@ fn lsp_item_to_transaction(
             edit_offset,
             replace_mode,
             snippet,
-            &mut doc.snippet_ctx(),
+            &mut doc.snippet_ctx(view_id),
         );
         (transaction, Some(snippet))
     } else {
@@ -0,0 +1,601 @@
+use crate::{
+    commands::dap::dap_callback,
+    compositor::{Callback, Component, Context, Event, EventResult},
+    ctrl, key, shift,
+    ui::{self, Popup, Prompt, PromptEvent},
+};
+use helix_dap::{self as dap, registry::DebugAdapterId, Client};
+use helix_lsp::block_on;
+use helix_view::{graphics::Rect, Editor};
+use std::collections::{hash_map::Entry, HashMap};
+use tui::{
+    buffer::Buffer as Surface,
+    text::{Span, Spans},
+};
+
+/// A node in the variables tree: either a scope or a variable.
+struct VariableNode {
+    variable: dap::Variable,
+    /// The `variablesReference` of the scope or variable which contains this variable. `None`
+    /// for scopes.
+    container: Option<usize>,
+    depth: usize,
+    expanded: bool,
+}
+
+impl VariableNode {
+    fn is_expandable(&self) -> bool {
+        self.variable.variables_reference != 0
+    }
+}
+
+/// A tree of the variables visible from a stack frame. Nested variables are fetched from the
+/// debugger when they are expanded.
+pub struct VariablesTree {
+    debugger_id: DebugAdapterId,
+    frame_id: usize,
+    nodes: Vec<VariableNode>,
+    /// The variables fetched for each `variablesReference`, so that expanding a variable again
+    /// after collapsing it doesn't request its children again. References are only valid while
+    /// the debuggee is stopped, which outlives the tree.
+    children: HashMap<usize, Vec<dap::Variable>>,
+    cursor: usize,
+    scroll: usize,
+    size: (u16, u16),
+    viewport: (u16, u16),
+    recalculate: bool,
+}
+
+impl VariablesTree {
+    pub const ID: &'static str = "dap-variables";
+
+    pub fn new(debugger: &Client, frame_id: usize) -> dap::Result<Self> {
+        let scopes = block_on(debugger.scopes(frame_id))?;
+        let expensive: Vec<_> = scopes.iter().map(|scope| scope.expensive).collect();
+        let mut tree = Self::with_scopes(debugger.id(), frame_id, scopes);
+
+        // Expand from the last scope so that the indices of the earlier scopes don't change.
+        for (index, expensive) in expensive.into_iter().enumerate().rev() {
+            if !expensive {
+                // Failing to fetch a scope should not prevent showing the others.
+                let _ = tree.expand(debugger, index);
+            }
+        }
+
+        Ok(tree)
+    }
+
+    fn with_scopes(debugger_id: DebugAdapterId, frame_id: usize, scopes: Vec<dap::Scope>) -> Self {
+        let nodes = scopes
+            .into_iter()
+            .map(|scope| VariableNode {
+                variable: dap::Variable {
+                    name: scope.name,
+                    value: String::new(),
+                    ty: None,
+                    presentation_hint: None,
+                    evaluate_name: None,
+                    variables_reference: scope.variables_reference,
+                    named_variables: scope.named_variables,
+                    indexed_variables: scope.indexed_variables,
+                    memory_reference: None,
+                },
+                container: None,
+                depth: 0,
+                expanded: false,
+            })
+            .collect();
+
+        Self {
+            debugger_id,
+            frame_id,
+            nodes,
+            children: HashMap::new(),
+            cursor: 0,
+            scroll: 0,
+            size: (0, 0),
+            viewport: (0, 0),
+            recalculate: true,
+        }
+    }
+
+    fn expand(&mut self, debugger: &Client, index: usize) -> dap::Result<()> {
+        self.expand_with(index, |reference| block_on(debugger.variables(reference)))
+    }
+
+    /// Expands the node at `index`, using `fetch` to request the children of a
+    /// `variablesReference` which wasn't fetched before.
+    fn expand_with(
+        &mut self,
+        index: usize,
+        fetch: impl FnOnce(usize) -> dap::Result<Vec<dap::Variable>>,
+    ) -> dap::Result<()> {
+        let node = &self.nodes[index];
+        if node.expanded || !node.is_expandable() {
+            return Ok(());
+        }
+
+        let reference = node.variable.variables_reference;
+        let depth = node.depth + 1;
+        let children = match self.children.entry(reference) {
+            Entry::Occupied(entry) => entry.get().clone(),
+            Entry::Vacant(entry) => entry.insert(fetch(reference)?).clone(),
+        };
+        self.nodes.splice(
+            index + 1..index + 1,
+            children.into_iter().map(|variable| VariableNode {
+                variable,
+                container: Some(reference),
+                depth,
+                expanded: false,
+            }),
+        );
+        self.nodes[index].expanded = true;
+        self.recalculate = true;
+        Ok(())
+    }
+
+    fn collapse(&mut self, index: usize) {
+        let depth = self.nodes[index].depth;
+        let end = self.nodes[index + 1..]
+            .iter()
+            .position(|node| node.depth <= depth)
+            .map_or(self.nodes.len(), |offset| index + 1 + offset);
+        self.nodes.drain(index + 1..end);
+        self.nodes[index].expanded = false;
+        self.recalculate = true;
+    }
+
+    fn parent(&self, index: usize) -> Option<usize> {
+        let depth = self.nodes[index].depth;
+        self.nodes[..index]
+            .iter()
+            .rposition(|node| node.depth < depth)
+    }
+
+    fn move_up(&mut self) {
+        let len = self.nodes.len();
+        self.cursor = (self.cursor + len - 1) % len;
+    }
+
+    fn move_down(&mut self) {
+        self.cursor = (self.cursor + 1) % self.nodes.len();
+    }
+
+    fn toggle(&mut self, editor: &mut Editor) {
+        if self.nodes[self.cursor].expanded {
+            self.collapse(self.cursor);
+        } else {
+            self.expand_cursor(editor);
+        }
+    }
+
+    fn expand_cursor(&mut self, editor: &mut Editor) {
+        let Some(debugger) = editor.debug_adapters.get_client(self.debugger_id) else {
+            editor.set_error("Debugger is not running");
+            return;
+        };
+        if let Err(err) = self.expand(debugger, self.cursor) {
+            editor.set_error(format!("Failed to get variables: {err}"));
+        }
+    }
+
+    fn yank(&self, editor: &mut Editor, evaluate_name: bool) {
+        let variable = &self.nodes[self.cursor].variable;
+        let (value, description) = if evaluate_name {
+            match &variable.evaluate_name {
+                Some(name) => (name.clone(), "evaluate name"),
+                None => {
+                    editor.set_error(format!("Variable '{}' has no evaluate name", variable.name));
+                    return;
+                }
+            }
+        } else {
+            (variable.value.clone(), "value")
+        };
+
+        let register = editor.config().default_yank_register;
+        match editor.registers.write(register, vec![value]) {
+            Ok(_) => editor.set_status(format!(
+                "yanked {description} of '{}' to register {register}",
+                variable.name
+            )),
+            Err(err) => editor.set_error(err.to_string()),
+        }
+    }
+
+    /// Creates a prompt which sets the value of the variable under the cursor.
+    fn edit_prompt(&self, editor: &mut Editor) -> Option<Prompt> {
+        let node = &self.nodes[self.cursor];
+        let debugger = editor.debug_adapters.get_client(self.debugger_id)?;
+        let caps = debugger.capabilities();
+        let supports_set_variable =
+            caps.supports_set_variable.unwrap_or_default() && node.container.is_some();
+        let supports_set_expression = caps.supports_set_expression.unwrap_or_default()
+            && node.variable.evaluate_name.is_some();
+        if !supports_set_variable && !supports_set_expression {
+            editor.set_error(format!(
+                "Debugger does not support setting the value of '{}'",
+                node.variable.name
+            ));
+            return None;
+        }
+
+        let debugger_id = self.debugger_id;
+        let frame_id = self.frame_id;
+        let index = self.cursor;
+        let name = node.variable.name.clone();
+        let evaluate_name = node.variable.evaluate_name.clone();
+        let container = node.container;
+
+        let mut prompt = Prompt::new(
+            format!("{name} = ").into(),
+            None,
+            ui::completers::none,
+            move |cx, input: &str, event: PromptEvent| {
+                if event != PromptEvent::Validate {
+                    return;
+                }
+                let Some(debugger) = cx.editor.debug_adapters.get_client(debugger_id) else {
+                    cx.editor.set_error("Debugger is not running");
+                    return;
+                };
+
+                let variable_name = name.clone();
+                let update = move |compositor: &mut crate::compositor::Compositor,
+                                   value: String,
+                                   ty: Option<String>,
+                                   reference: Option<usize>| {
+                    if let Some(popup) = compositor.find_id::<Popup<Self>>(Self::ID) {
+                        popup.contents_mut().update_variable(
+                            index,
+                            &variable_name,
+                            value,
+                            ty,
+                            reference,
+                        );
+                    }
+                };
+
+                if let (true, Some(container)) = (supports_set_variable, container) {
+                    let request = debugger.set_variable(container, name.clone(), input.to_owned());
+                    dap_callback(
+                        cx.jobs,
+                        request,
+                        move |_editor, compositor, response: dap::requests::SetVariableResponse| {
+                            update(
+                                compositor,
+                                response.value,
+                                response.ty,
+                                response.variables_reference,
+                            )
+                        },
+                    );
+                } else if let Some(expression) = evaluate_name.clone() {
+                    let request =
+                        debugger.set_expression(expression, input.to_owned(), Some(frame_id));
+                    dap_callback(
+                        cx.jobs,
+                        request,
+                        move |_editor,
+                              compositor,
+                              response: dap::requests::SetExpressionResponse| {
+                            update(
+                                compositor,
+                                response.value,
+                                response.ty,
+                                response.variables_reference,
+                            )
+                        },
+                    );
+                }
+            },
+        );
+        prompt.insert_str(&node.variable.value, editor);
+        Some(prompt)
+    }
+
+    fn update_variable(
+        &mut self,
+        index: usize,
+        name: &str,
+        value: String,
+        ty: Option<String>,
+        reference: Option<usize>,
+    ) {
+        // The tree may have been changed while the prompt was open.
+        if self
+            .nodes
+            .get(index)
+            .is_none_or(|node| node.variable.name != name)
+        {
+            return;
+        }
+        if self.nodes[index].expanded {
+            self.collapse(index);
+        }
+        let node = &mut self.nodes[index];
+        let variable = &mut node.variable;
+        // The children of the variable changed along with its value.
+        self.children.remove(&variable.variables_reference);
+        variable.value = value;
+        variable.ty = ty.or(variable.ty.take());
+        if let Some(reference) = reference {
+            variable.variables_reference = reference;
+        }
+        if let Some(cached) = node
+            .container
+            .and_then(|container| self.children.get_mut(&container))
+            .and_then(|siblings| siblings.iter_mut().find(|sibling| sibling.name == name))
+        {
+            *cached = variable.clone();
+        }
+        self.recalculate = true;
+    }
+
+    fn format_node<'a>(&self, node: &'a VariableNode, editor: &Editor) -> Spans<'a> {
+        let theme = &editor.theme;
+        let scope_style = theme.get("ui.linenr.selected");
+        let type_style = theme.get("ui.text");
+        let text_style = theme.get("ui.text.focus");
+
+        let marker = match (node.is_expandable(), node.expanded) {
+            (false, _) => "  ",
+            (true, false) => "▸ ",
+            (true, true) => "▾ ",
+        };
+        let mut spans = Vec::with_capacity(6);
+        spans.push(Span::raw(format!("{}{marker}", "  ".repeat(node.depth))));
+
+        if node.container.is_none() {
+            spans.push(Span::styled(node.variable.name.as_str(), scope_style));
+            return Spans::from(spans);
+        }
+
+        spans.push(Span::styled(node.variable.name.as_str(), text_style));
+        if let Some(ty) = &node.variable.ty {
+            spans.push(Span::raw(": "));
+            spans.push(Span::styled(ty.as_str(), type_style));
+        }
+        spans.push(Span::raw(" = "));
+        spans.push(Span::styled(node.variable.value.as_str(), text_style));
+        Spans::from(spans)
+    }
+
+    fn adjust_scroll(&mut self, height: usize) {
+        if self.cursor < self.scroll {
+            self.scroll = self.cursor;
+        } else if self.cursor >= self.scroll + height {
+            self.scroll = self.cursor + 1 - height;
+        }
+    }
+}
+
+impl Component for VariablesTree {
+    fn handle_event(&mut self, event: &Event, cx: &mut Context) -> EventResult {
+        let Event::Key(event) = event else {
+            return EventResult::Ignored(None);
+        };
+        if self.nodes.is_empty() {
+            return EventResult::Ignored(None);
+        }
+
+        match *event {
+            key!(Up) | key!('k') | ctrl!('p') | shift!(Tab) => self.move_up(),
+            key!(Down) | key!('j') | ctrl!('n') | key!(Tab) => self.move_down(),
+            key!(Enter) => self.toggle(cx.editor),
+            key!(Right) | key!('l') => self.expand_cursor(cx.editor),
+            key!(Left) | key!('h') => {
+                if self.nodes[self.cursor].expanded {
+                    self.collapse(self.cursor);
+                } else if let Some(parent) = self.parent(self.cursor) {
+                    self.cursor = parent;
+                }
+            }
+            key!('y') => self.yank(cx.editor, false),
+            key!('Y') => self.yank(cx.editor, true),
+            key!('e') | key!('i') => {
+                if let Some(prompt) = self.edit_prompt(cx.editor) {
+                    let callback: Callback = Box::new(move |compositor, _cx| {
+                        compositor.push(Box::new(prompt));
+                    });
+                    return EventResult::Consumed(Some(callback));
+                }
+            }
+            key!('q') => {
+                let callback: Callback = Box::new(|compositor, _cx| {
+                    compositor.remove(Self::ID);
+                });
+                return EventResult::Consumed(Some(callback));
+            }
+            _ => return EventResult::Ignored(None),
+        }
+
+        EventResult::Consumed(None)
+    }
+
+    fn required_size(&mut self, viewport: (u16, u16)) -> Option<(u16, u16)> {
+        if viewport != self.viewport || self.recalculate {
+            // The width is estimated from the raw text since styles are not needed here.
+            let width = self
+                .nodes
+                .iter()
+                .map(|node| {
+                    let variable = &node.variable;
+                    node.depth * 2
+                        + 2
+                        + variable.name.len()
+                        + variable.ty.as_ref().map_or(0, |ty| ty.len() + 2)
+                        + if node.container.is_some() {
+                            variable.value.len() + 3
+                        } else {
+                            0
+                        }
+                })
+                .max()
+                .unwrap_or_default();
+            self.size = (
+                (width as u16).min(viewport.0),
+                (self.nodes.len() as u16).min(viewport.1),
+            );
+            self.viewport = viewport;
+            self.recalculate = false;
+        }
+        Some(self.size)
+    }
+
+    fn render(&mut self, area: Rect, surface: &mut Surface, cx: &mut Context) {
+        let selected = cx.editor.theme.get("ui.menu.selected");
+        let height = area.height as usize;
+        self.adjust_scroll(height);
+
+        for (row, (index, node)) in self
+            .nodes
+            .iter()
+            .enumerate()
+            .skip(self.scroll)
+            .take(height)
+            .enumerate()
+        {
+            let y = area.y + row as u16;
+            let spans = self.format_node(node, cx.editor);
+            surface.set_spans(area.x, y, &spans, area.width);
+            if index == self.cursor {
+                surface.set_style(Rect::new(area.x, y, area.width, 1), selected);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::cell::RefCell;
+
+    fn variable(name: &str, reference: usize) -> dap::Variable {
+        serde_json::from_value(json!({
+            "name": name,
+            "value": format!("{name} value"),
+            "variablesReference": reference,
+        }))
+        .unwrap()
+    }
+
+    fn tree() -> VariablesTree {
+        let scopes = serde_json::from_value(json!([
+            { "name": "Locals", "variablesReference": 1, "expensive": false },
+            { "name": "Globals", "variablesReference": 2, "expensive": true },
+        ]))
+        .unwrap();
+        VariablesTree::with_scopes(DebugAdapterId::default(), 0, scopes)
+    }
+
+    /// Returns the variables of each reference and records the requested references.
+    fn fetch<'a>(
+        requests: &'a RefCell<Vec<usize>>,
+    ) -> impl Fn(usize) -> dap::Result<Vec<dap::Variable>> + 'a {
+        move |reference| {
+            requests.borrow_mut().push(reference);
+            Ok(match reference {
+                1 => vec![variable("a", 3), variable("b", 0)],
+                2 => vec![variable("g", 0)],
+                3 => vec![variable("x", 0), variable("y", 4)],
+                4 => vec![variable("z", 0)],
+                _ => unreachable!(),
+            })
+        }
+    }
+
+    fn nodes(tree: &VariablesTree) -> Vec<(&str, usize)> {
+        tree.nodes
+            .iter()
+            .map(|node| (node.variable.name.as_str(), node.depth))
+            .collect()
+    }
+
+    #[test]
+    fn expand_and_collapse_nested_variables() {
+        let requests = RefCell::new(Vec::new());
+        let mut tree = tree();
+
+        tree.expand_with(0, fetch(&requests)).unwrap();
+        tree.expand_with(1, fetch(&requests)).unwrap();
+        tree.expand_with(3, fetch(&requests)).unwrap();
+        assert_eq!(
+            nodes(&tree),
+            [
+                ("Locals", 0),
+                ("a", 1),
+                ("x", 2),
+                ("y", 2),
+                ("z", 3),
+                ("b", 1),
+                ("Globals", 0)
+            ]
+        );
+        assert_eq!(tree.nodes[4].container, Some(4));
+        assert_eq!(tree.parent(4), Some(3));
+        assert_eq!(tree.parent(5), Some(0));
+        assert_eq!(tree.parent(0), None);
+
+        // Variables without children can't be expanded.
+        tree.expand_with(5, fetch(&requests)).unwrap();
+        assert!(!tree.nodes[5].expanded);
+
+        // Collapsing removes all descendants.
+        tree.collapse(1);
+        assert_eq!(
+            nodes(&tree),
+            [("Locals", 0), ("a", 1), ("b", 1), ("Globals", 0)]
+        );
+        tree.collapse(0);
+        assert_eq!(nodes(&tree), [("Locals", 0), ("Globals", 0)]);
+
+        tree.expand_with(1, fetch(&requests)).unwrap();
+        assert_eq!(nodes(&tree), [("Locals", 0), ("Globals", 0), ("g", 1)]);
+        assert_eq!(*requests.borrow(), [1, 3, 4, 2]);
+    }
+
+    #[test]
+    fn cache_variable_references() {
+        let requests = RefCell::new(Vec::new());
+        let mut tree = tree();
+
+        tree.expand_with(0, fetch(&requests)).unwrap();
+        tree.expand_with(1, fetch(&requests)).unwrap();
+        tree.collapse(0);
+        // Expanding again uses the fetched variables. Nested variables are collapsed.
+        tree.expand_with(0, fetch(&requests)).unwrap();
+        tree.expand_with(1, fetch(&requests)).unwrap();
+        assert_eq!(*requests.borrow(), [1, 3]);
+        assert_eq!(
+            nodes(&tree),
+            [
+                ("Locals", 0),
+                ("a", 1),
+                ("x", 2),
+                ("y", 2),
+                ("b", 1),
+                ("Globals", 0)
+            ]
+        );
+
+        // Setting a value forgets the children of the variable and updates the cached variable
+        // of its container.
+        tree.update_variable(1, "a", "new".into(), None, Some(3));
+        assert_eq!(
+            nodes(&tree),
+            [("Locals", 0), ("a", 1), ("b", 1), ("Globals", 0)]
+        );
+        tree.collapse(0);
+        tree.expand_with(0, fetch(&requests)).unwrap();
+        assert_eq!(tree.nodes[1].variable.value, "new");
+        tree.expand_with(1, fetch(&requests)).unwrap();
+        assert_eq!(*requests.borrow(), [1, 3, 3]);
+
+        // Updates of variables which moved while the value was set are dropped.
+        tree.update_variable(1, "b", "other".into(), None, None);
+        assert_eq!(tree.nodes[1].variable.value, "new");
+    }
+}
@@ -116,6 +116,33 @@ pub fn render_view(
             decorations.add_decoration(line_decoration);
         }
 
+        if let Some((frame, exception)) = editor
+            .debug_adapters
+            .get_active_client()
+            .and_then(|debugger| debugger.current_exception())
+        {
+            let is_frame_doc = frame
+                .source
+                .as_ref()
+                .and_then(|source| source.path.as_deref())
+                .is_some_and(|path| doc.path().is_some_and(|doc_path| doc_path == path));
+            if is_frame_doc {
+                let message = match exception
+                    .description
+                    .as_deref()
+                    .and_then(|d| d.lines().next())
+                {
+                    Some(description) => format!("■ {}: {}", exception.exception_id, description),
+                    None => format!("■ {}", exception.exception_id),
+                };
+                decorations.add_decoration(text_decorations::ExceptionAnnotation::new(
+                    frame.line.saturating_sub(1),
+                    message,
+                    theme,
+                ));
+            }
+        }
+
         let syntax_highlighter =
             Self::doc_syntax_highlighter(doc, view_offset.anchor, inner.height, &loader);
         let mut overlays = Vec::new();
@@ -1334,6 +1361,12 @@ fn handle_mouse_event(
             _ => EventResult::Ignored(None),
         }
     }
+
+    /// Handles the next key press with `callback` like a command's `on_next_key` callbacks.
+    pub(crate) fn set_on_next_key(&mut self, callback: OnKeyCallback, kind: OnKeyCallbackKind) {
+        self.on_next_key = Some((callback, kind));
+    }
+
     fn on_next_key(
         &mut self,
         kind: OnKeyCallbackKind,
@@ -1,4 +1,5 @@
 mod completion;
+pub mod dap;
 mod document;
 pub(crate) mod editor;
 mod info;
@@ -9,11 +10,13 @@
 pub mod picker;
 pub mod popup;
 pub mod prompt;
+mod register_picker;
 mod select;
 mod spinner;
 mod statusline;
 mod text;
 mod text_decorations;
+mod undo_tree;
 
 use crate::compositor::Compositor;
 use crate::filter_picker_entry;
@@ -27,9 +30,11 @@
 pub use picker::{Column as PickerColumn, FileLocation, Picker};
 pub use popup::Popup;
 pub use prompt::{Prompt, PromptEvent};
+pub use register_picker::register_picker;
 pub use select::Select;
 pub use spinner::{ProgressSpinners, Spinner};
 pub use text::Text;
+pub use undo_tree::UndoTree;
 
 use helix_view::Editor;
 use tui::text::{Span, Spans};
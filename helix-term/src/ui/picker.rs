@@ -47,6 +47,7 @@
 use helix_view::{
     editor::Action,
     graphics::{CursorKind, Margin, Modifier, Rect},
+    input::KeyEvent,
     theme::Style,
     view::ViewPosition,
     Document, DocumentId, Editor,
@@ -259,6 +260,8 @@ pub struct Picker<T: 'static + Send + Sync, D: 'static> {
 
     callback_fn: PickerCallback<T>,
     default_action: Action,
+    /// Keys which call a handler with the selected option and close the picker.
+    key_handlers: HashMap<KeyEvent, PickerKeyHandler<T>>,
 
     pub truncate_start: bool,
     /// Caches paths to documents
@@ -387,6 +390,7 @@ fn with(
             show_preview: true,
             callback_fn: Box::new(callback_fn),
             default_action: Action::Replace,
+            key_handlers: HashMap::new(),
             completion_height: 0,
             widths,
             preview_cache: HashMap::new(),
@@ -455,6 +459,16 @@ pub fn with_default_action(mut self, action: Action) -> Self {
         self
     }
 
+    /// Calls `handler` with the selected option and closes the picker when `key` is pressed.
+    pub fn with_key_handler(
+        mut self,
+        key: KeyEvent,
+        handler: impl Fn(&mut Context, &T) + 'static,
+    ) -> Self {
+        self.key_handlers.insert(key, Box::new(handler));
+        self
+    }
+
     /// Move the cursor by a number of lines, either down (`Forward`) or up (`Backward`)
     pub fn move_by(&mut self, amount: u32, direction: Direction) {
         let len = self.matcher.snapshot().matched_item_count();
@@ -1068,6 +1082,13 @@ fn handle_event(&mut self, event: &Event, ctx: &mut Context) -> EventResult {
             EventResult::Consumed(Some(callback))
         };
 
+        if let Some(handler) = self.key_handlers.get(&key_event) {
+            if let Some(option) = self.selection() {
+                handler(ctx, option);
+            }
+            return close_fn(self);
+        }
+
         match key_event {
             shift!(Tab) | key!(Up) | ctrl!('p') => {
                 self.move_by(1, Direction::Backward);
@@ -1187,3 +1208,4 @@ fn drop(&mut self) {
 }
 
 type PickerCallback<T> = Box<dyn Fn(&mut Context, &T, Action)>;
+type PickerKeyHandler<T> = Box<dyn Fn(&mut Context, &T)>;
@@ -0,0 +1,111 @@
+use crate::{
+    alt,
+    commands::{self, Paste},
+    ctrl,
+    ui::{Picker, PickerColumn},
+};
+use helix_core::Rope;
+use helix_view::{editor::Action, register, Document, Editor};
+
+pub type RegisterPicker = Picker<RegisterItem, ()>;
+
+pub struct RegisterItem {
+    name: char,
+    /// The values of the register, newest first.
+    values: Vec<String>,
+}
+
+/// Lists every register with a preview of all of its values. The selected register can be
+/// pasted, cleared or opened in a register buffer, which updates the register when written.
+pub fn register_picker(editor: &Editor) -> RegisterPicker {
+    let mut items: Vec<_> = editor
+        .registers
+        .iter_preview()
+        .map(|(name, _)| RegisterItem {
+            name,
+            values: editor
+                .registers
+                .read(name, editor)
+                .map(|values| values.map(|value| value.into_owned()).collect())
+                .unwrap_or_default(),
+        })
+        .collect();
+    items.sort_unstable_by_key(|item| item.name);
+
+    let columns = [
+        PickerColumn::new("register", |item: &RegisterItem, _| {
+            item.name.to_string().into()
+        }),
+        PickerColumn::new("values", |item: &RegisterItem, _| {
+            preview(&item.values).into()
+        }),
+    ];
+
+    Picker::new(columns, 0, items, (), |cx, item, action| match action {
+        Action::HorizontalSplit | Action::VerticalSplit => edit(cx.editor, item.name, action),
+        _ => commands::paste(cx.editor, item.name, Paste::After, 1),
+    })
+    .with_key_handler(alt!('p'), |cx, item| {
+        commands::paste(cx.editor, item.name, Paste::Before, 1)
+    })
+    .with_key_handler(ctrl!('x'), |cx, item| {
+        if cx.editor.registers.remove(item.name) {
+            cx.editor
+                .set_status(format!("Cleared register {}", item.name));
+        } else {
+            cx.editor
+                .set_error(format!("Register {} cannot be cleared", item.name));
+        }
+    })
+    .with_key_handler(ctrl!('o'), |cx, item| {
+        edit(cx.editor, item.name, Action::Replace)
+    })
+}
+
+/// All values of a register on a single line, newest first.
+fn preview(values: &[String]) -> String {
+    if values.is_empty() {
+        return "<empty>".to_string();
+    }
+    let values: Vec<_> = values
+        .iter()
+        .map(|value| {
+            value
+                .lines()
+                .map(|line| line.replace('\t', "    "))
+                .collect::<Vec<_>>()
+                .join("⏎")
+        })
+        .collect();
+    values.join(" ␞ ")
+}
+
+/// Opens the register `name` in a register buffer, reusing a buffer which is already open.
+fn edit(editor: &mut Editor, name: char, action: Action) {
+    if register::is_read_only(name) {
+        editor.set_error(format!("Register {name} cannot be edited"));
+        return;
+    }
+    let open = editor
+        .documents()
+        .find(|doc| doc.register() == Some(name))
+        .map(|doc| doc.id());
+    if let Some(id) = open {
+        editor.switch(id, action);
+        return;
+    }
+
+    let values: Vec<_> = editor
+        .registers
+        .read(name, editor)
+        .map(|values| values.map(|value| value.into_owned()).collect())
+        .unwrap_or_default();
+    let mut doc = Document::from(
+        Rope::from(register::values_to_text(&values)),
+        None,
+        editor.config.clone(),
+        editor.syn_loader.clone(),
+    );
+    doc.set_register(Some(name));
+    editor.new_file_from_document(action, doc);
+}
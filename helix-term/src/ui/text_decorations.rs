@@ -6,8 +6,10 @@
 
 use crate::ui::document::{LinePos, TextRenderer};
 
+pub use dap::ExceptionAnnotation;
 pub use diagnostics::InlineDiagnostics;
 
+mod dap;
 mod diagnostics;
 
 /// Decorations are the primary mechanism for extending the text rendering.
@@ -0,0 +1,59 @@
+use helix_core::Position;
+use helix_view::graphics::Style;
+use helix_view::theme::Theme;
+
+use crate::ui::document::{LinePos, TextRenderer};
+use crate::ui::text_decorations::Decoration;
+
+/// Marks the line which raised the exception the debuggee is currently stopped on
+/// by rendering the exception at the end of the line, similar to an end of line
+/// diagnostic.
+pub struct ExceptionAnnotation {
+    line: usize,
+    message: String,
+    style: Style,
+}
+
+impl ExceptionAnnotation {
+    pub fn new(line: usize, message: String, theme: &Theme) -> Self {
+        let style = theme
+            .try_get_exact("ui.debug.exception")
+            .unwrap_or_else(|| theme.get("error"));
+        Self {
+            line,
+            message,
+            style,
+        }
+    }
+}
+
+impl Decoration for ExceptionAnnotation {
+    fn render_virt_lines(
+        &mut self,
+        renderer: &mut TextRenderer,
+        pos: LinePos,
+        virt_off: Position,
+    ) -> Position {
+        if pos.doc_line != self.line {
+            return Position::new(0, 0);
+        }
+        // Soft wrapped lines call this for every visual line. Lines which are
+        // filled up to the edge of the viewport are skipped here.
+        let draw_col = virt_off.col + 1;
+        if !renderer.column_in_bounds(draw_col, 1) {
+            return Position::new(0, 0);
+        }
+        let start_col = (draw_col - renderer.offset.col) as u16;
+        let width = renderer.viewport.width.saturating_sub(start_col);
+        let (end_col, _) = renderer.set_string_truncated(
+            renderer.viewport.x + start_col,
+            pos.visual_line,
+            &self.message,
+            width as usize,
+            |_| self.style,
+            true,
+            false,
+        );
+        Position::new(0, (end_col - renderer.viewport.x - start_col) as usize + 1)
+    }
+}
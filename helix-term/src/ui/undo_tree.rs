@@ -0,0 +1,453 @@
+use std::time::{Duration, Instant};
+
+use crate::{
+    compositor::{Callback, Component, Context, Event, EventResult},
+    ctrl, key,
+};
+use helix_core::{diff::compare_lines, history::History, Operation, Rope};
+use helix_view::{
+    graphics::{Margin, Rect},
+    DocumentId, Editor, ViewId,
+};
+use tui::{
+    buffer::Buffer as Surface,
+    widgets::{Block, Widget},
+};
+
+/// Number of unchanged lines shown around each change in the preview.
+const CONTEXT_LINES: u32 = 3;
+
+/// A revision of the undo tree, in the order it is displayed.
+struct RevisionRow {
+    revision: usize,
+    /// The lines connecting the revision to its parent and siblings.
+    graph: String,
+    timestamp: Instant,
+    summary: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PreviewLineKind {
+    Context,
+    Removed,
+    Added,
+    /// Separates hunks which are not adjacent.
+    Gap,
+}
+
+/// Shows the undo tree of a document. The selected revision is previewed as a diff against the
+/// current text and jumped to on `Enter`.
+pub struct UndoTree {
+    doc_id: DocumentId,
+    view_id: ViewId,
+    rows: Vec<RevisionRow>,
+    current: usize,
+    cursor: usize,
+    scroll: usize,
+    /// The revision the preview was computed for and the preview lines.
+    preview: Option<(usize, Vec<(PreviewLineKind, String)>)>,
+}
+
+impl UndoTree {
+    pub const ID: &'static str = "undo-tree";
+
+    pub fn new(editor: &Editor, doc_id: DocumentId, view_id: ViewId) -> Self {
+        let doc = &editor.documents[&doc_id];
+        let history = doc.history.take();
+        let rows = revision_rows(&history);
+        let current = history.current_revision();
+        doc.history.set(history);
+
+        let cursor = rows
+            .iter()
+            .position(|row| row.revision == current)
+            .unwrap_or_default();
+        Self {
+            doc_id,
+            view_id,
+            rows,
+            current,
+            cursor,
+            scroll: 0,
+            preview: None,
+        }
+    }
+
+    fn move_up(&mut self, count: usize) {
+        self.cursor = self.cursor.saturating_sub(count);
+    }
+
+    fn move_down(&mut self, count: usize) {
+        self.cursor = (self.cursor + count).min(self.rows.len() - 1);
+    }
+
+    fn adjust_scroll(&mut self, height: usize) {
+        if self.cursor < self.scroll {
+            self.scroll = self.cursor;
+        } else if self.cursor >= self.scroll + height {
+            self.scroll = self.cursor + 1 - height;
+        }
+    }
+
+    fn close() -> EventResult {
+        let callback: Callback = Box::new(|compositor, _cx| {
+            compositor.pop();
+        });
+        EventResult::Consumed(Some(callback))
+    }
+
+    fn jump(&self, editor: &mut Editor) {
+        let revision = self.rows[self.cursor].revision;
+        if !editor.documents.contains_key(&self.doc_id) || !editor.tree.contains(self.view_id) {
+            return;
+        }
+        let view = editor.tree.get_mut(self.view_id);
+        let doc = editor.documents.get_mut(&self.doc_id).unwrap();
+        doc.jump_to_revision(view, revision);
+    }
+
+    fn preview_lines(&mut self, editor: &Editor) -> &[(PreviewLineKind, String)] {
+        let revision = self.rows[self.cursor].revision;
+        if self
+            .preview
+            .as_ref()
+            .is_none_or(|(rev, _)| *rev != revision)
+        {
+            let lines = match editor.documents.get(&self.doc_id) {
+                Some(doc) => {
+                    let history = doc.history.take();
+                    let txns = history.changes_to(revision);
+                    doc.history.set(history);
+
+                    let current = doc.text();
+                    let mut text = current.clone();
+                    for txn in txns {
+                        txn.apply(&mut text);
+                    }
+                    diff_lines(current, &text)
+                }
+                None => Vec::new(),
+            };
+            self.preview = Some((revision, lines));
+        }
+        &self.preview.as_ref().unwrap().1
+    }
+
+    fn render_tree(&mut self, area: Rect, surface: &mut Surface, cx: &mut Context) {
+        let theme = &cx.editor.theme;
+        let text = theme.get("ui.text");
+        let inactive = theme.get("ui.text.inactive");
+        let current = theme.get("ui.text.focus");
+        let selected = theme.get("ui.menu.selected");
+
+        const BLOCK: Block<'_> = Block::bordered();
+        let inner = BLOCK.inner(area).inner(Margin::horizontal(1));
+        BLOCK.render(area, surface);
+
+        let height = inner.height as usize;
+        self.adjust_scroll(height);
+
+        let now = Instant::now();
+        let graph_width = self
+            .rows
+            .iter()
+            .map(|row| row.graph.chars().count())
+            .max()
+            .unwrap_or_default();
+        let revision_width = self.rows.len().to_string().len();
+
+        for (i, row) in self.rows.iter().enumerate().skip(self.scroll).take(height) {
+            let y = inner.y + (i - self.scroll) as u16;
+            let style = if row.revision == self.current {
+                current
+            } else {
+                text
+            };
+            let (x, _) = surface.set_stringn(
+                inner.x,
+                y,
+                format!(
+                    "{:graph_width$} {:>revision_width$} ",
+                    row.graph, row.revision
+                ),
+                inner.width as usize,
+                style,
+            );
+            let age = format_age(now.saturating_duration_since(row.timestamp));
+            let (x, _) = surface.set_stringn(
+                x,
+                y,
+                format!("{age:>8} "),
+                inner.right().saturating_sub(x) as usize,
+                inactive,
+            );
+            surface.set_stringn(
+                x,
+                y,
+                &row.summary,
+                inner.right().saturating_sub(x) as usize,
+                style,
+            );
+            if i == self.cursor {
+                surface.set_style(Rect::new(inner.x, y, inner.width, 1), selected);
+            }
+        }
+    }
+
+    fn render_preview(&mut self, area: Rect, surface: &mut Surface, cx: &mut Context) {
+        let theme = &cx.editor.theme;
+        let text = theme.get("ui.text");
+        let inactive = theme.get("ui.text.inactive");
+        let minus = theme.get("diff.minus");
+        let plus = theme.get("diff.plus");
+
+        const BLOCK: Block<'_> = Block::bordered();
+        let inner = BLOCK.inner(area).inner(Margin::horizontal(1));
+        BLOCK.render(area, surface);
+
+        let lines = self.preview_lines(cx.editor);
+        if lines.is_empty() {
+            surface.set_stringn(
+                inner.x,
+                inner.y,
+                "No changes",
+                inner.width as usize,
+                inactive,
+            );
+            return;
+        }
+        for (i, (kind, line)) in lines.iter().take(inner.height as usize).enumerate() {
+            let (prefix, style) = match kind {
+                PreviewLineKind::Context => (' ', text),
+                PreviewLineKind::Removed => ('-', minus),
+                PreviewLineKind::Added => ('+', plus),
+                PreviewLineKind::Gap => (' ', inactive),
+            };
+            surface.set_stringn(
+                inner.x,
+                inner.y + i as u16,
+                format!("{prefix} {line}"),
+                inner.width as usize,
+                style,
+            );
+        }
+    }
+}
+
+impl Component for UndoTree {
+    fn handle_event(&mut self, event: &Event, cx: &mut Context) -> EventResult {
+        let Event::Key(event) = event else {
+            return EventResult::Ignored(None);
+        };
+
+        match *event {
+            key!(Up) | key!('k') | ctrl!('p') => self.move_up(1),
+            key!(Down) | key!('j') | ctrl!('n') => self.move_down(1),
+            key!(PageUp) | ctrl!('u') => self.move_up(10),
+            key!(PageDown) | ctrl!('d') => self.move_down(10),
+            key!(Home) | key!('g') => self.cursor = 0,
+            key!(End) | key!('G') => self.cursor = self.rows.len() - 1,
+            key!(Enter) => {
+                self.jump(cx.editor);
+                return Self::close();
+            }
+            key!(Esc) | key!('q') | ctrl!('c') => return Self::close(),
+            _ => (),
+        }
+        // The undo tree is modal: all other keys are swallowed.
+        EventResult::Consumed(None)
+    }
+
+    fn render(&mut self, area: Rect, surface: &mut Surface, cx: &mut Context) {
+        let background = cx.editor.theme.get("ui.background");
+        surface.clear_with(area, background);
+
+        let tree_width = area.width / 2;
+        self.render_tree(area.with_width(tree_width), surface, cx);
+        self.render_preview(area.clip_left(tree_width), surface, cx);
+    }
+
+    fn id(&self) -> Option<&'static str> {
+        Some(Self::ID)
+    }
+}
+
+/// Lays out the revisions of `history` as a tree. The newest child of a revision continues below
+/// its parent while older children branch off to the side.
+fn revision_rows(history: &History) -> Vec<RevisionRow> {
+    let revisions: Vec<_> = history.revisions().collect();
+    let current = history.current_revision();
+    let mut children = vec![Vec::new(); revisions.len()];
+    for (revision, info) in revisions.iter().enumerate().skip(1) {
+        children[info.parent].push(revision);
+    }
+
+    let mut rows = Vec::with_capacity(revisions.len());
+    // The revision, the graph of its own row and the graph of the rows below it.
+    let mut stack = vec![(0, String::new(), String::new())];
+    while let Some((revision, first, rest)) = stack.pop() {
+        let marker = if revision == current { '●' } else { '○' };
+        rows.push(RevisionRow {
+            revision,
+            graph: format!("{first}{marker}"),
+            timestamp: revisions[revision].timestamp,
+            summary: if revision == 0 {
+                "original".to_owned()
+            } else {
+                summarize(revisions[revision].transaction.changes().changes())
+            },
+        });
+
+        // The stack is LIFO so the newest child, which is displayed last, is pushed first.
+        let Some((newest, older)) = children[revision].split_last() else {
+            continue;
+        };
+        stack.push((*newest, rest.clone(), rest.clone()));
+        for &child in older.iter().rev() {
+            stack.push((child, format!("{rest}├─"), format!("{rest}│ ")));
+        }
+    }
+    rows
+}
+
+/// Summarizes a revision as the number of inserted and deleted characters followed by the
+/// beginning of the first insertion.
+fn summarize(operations: &[Operation]) -> String {
+    let mut inserted = 0;
+    let mut deleted = 0;
+    let mut snippet = None;
+    for operation in operations {
+        match operation {
+            Operation::Insert(text) => {
+                inserted += text.chars().count();
+                if snippet.is_none() && !text.trim().is_empty() {
+                    snippet = Some(text.trim());
+                }
+            }
+            Operation::Delete(n) => deleted += n,
+            Operation::Retain(_) => (),
+        }
+    }
+
+    let mut summary = match (inserted, deleted) {
+        (0, deleted) => format!("-{deleted}"),
+        (inserted, 0) => format!("+{inserted}"),
+        (inserted, deleted) => format!("+{inserted} -{deleted}"),
+    };
+    if let Some(snippet) = snippet {
+        const MAX_SNIPPET_LEN: usize = 24;
+        let mut snippet: String = snippet
+            .chars()
+            .map(|ch| if ch == '\n' { '⏎' } else { ch })
+            .take(MAX_SNIPPET_LEN)
+            .collect();
+        if snippet.chars().count() == MAX_SNIPPET_LEN {
+            snippet.push('…');
+        }
+        summary.push_str(&format!(" \"{snippet}\""));
+    }
+    summary
+}
+
+fn format_age(age: Duration) -> String {
+    let secs = age.as_secs();
+    match secs {
+        0..=9 => "now".to_owned(),
+        10..=59 => format!("{secs}s ago"),
+        60..=3599 => format!("{}m ago", secs / 60),
+        3600..=86399 => format!("{}h ago", secs / 3600),
+        _ => format!("{}d ago", secs / 86400),
+    }
+}
+
+/// Diffs `current` against the text of the previewed revision, keeping a few lines of context
+/// around each change.
+fn diff_lines(current: &Rope, revision: &Rope) -> Vec<(PreviewLineKind, String)> {
+    let line = |text: &Rope, i: u32| {
+        let line = text.line(i as usize).to_string();
+        line.trim_end_matches(['\n', '\r']).to_owned()
+    };
+
+    let mut lines = Vec::new();
+    let hunks = compare_lines(current, revision);
+    // Ropes have an empty line after a trailing line ending which is not worth showing.
+    let last_line = current.len_lines() - 1;
+    let len_lines = (last_line + (current.line(last_line).len_chars() > 0) as usize) as u32;
+    // The end of the context of the previous hunk in `current`.
+    let mut context_end = 0;
+    for (i, (before, after)) in hunks.iter().enumerate() {
+        let context_start = before.start.saturating_sub(CONTEXT_LINES).max(context_end);
+        if context_start > context_end || (i == 0 && context_start > 0) {
+            lines.push((PreviewLineKind::Gap, "…".to_owned()));
+        }
+        for line_idx in context_start..before.start {
+            lines.push((PreviewLineKind::Context, line(current, line_idx)));
+        }
+        for line_idx in before.clone() {
+            lines.push((PreviewLineKind::Removed, line(current, line_idx)));
+        }
+        for line_idx in after.clone() {
+            lines.push((PreviewLineKind::Added, line(revision, line_idx)));
+        }
+
+        let next_start = hunks.get(i + 1).map_or(len_lines, |(next, _)| next.start);
+        context_end = (before.end + CONTEXT_LINES).min(next_start).min(len_lines);
+        for line_idx in before.end..context_end {
+            lines.push((PreviewLineKind::Context, line(current, line_idx)));
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use helix_core::{history::State, Selection, Transaction};
+
+    fn commit(history: &mut History, state: &mut State, text: &str) {
+        let transaction = Transaction::change(&state.doc, [(0, 0, Some(text.into()))].into_iter());
+        history.commit_revision(&transaction, state);
+        transaction.apply(&mut state.doc);
+    }
+
+    #[test]
+    fn branching_tree() {
+        let mut history = History::default();
+        let mut state = State {
+            doc: Rope::from("a\n"),
+            selection: Selection::point(0),
+        };
+        commit(&mut history, &mut state, "x");
+        commit(&mut history, &mut state, "y");
+        history.undo().unwrap().apply(&mut state.doc);
+        commit(&mut history, &mut state, "z");
+
+        let rows = revision_rows(&history);
+        let graphs: Vec<_> = rows
+            .iter()
+            .map(|row| (row.revision, row.graph.as_str(), row.summary.as_str()))
+            .collect();
+        assert_eq!(
+            graphs,
+            [
+                (0, "○", "original"),
+                (1, "○", "+1 \"x\""),
+                (2, "├─○", "+1 \"y\""),
+                (3, "●", "+1 \"z\""),
+            ]
+        );
+    }
+
+    #[test]
+    fn preview_diff() {
+        let current = Rope::from("1\n2\n3\n4\n5\n6\n7\n8\n9\n");
+        let revision = Rope::from("1\n2\n3\n4\n5\n6\nseven\n8\n9\n");
+        let lines = diff_lines(&current, &revision);
+        assert_eq!(lines.len(), 8);
+        assert_eq!(lines[0], (PreviewLineKind::Gap, "…".to_owned()));
+        assert_eq!(lines[1], (PreviewLineKind::Context, "4".to_owned()));
+        assert_eq!(lines[4], (PreviewLineKind::Removed, "7".to_owned()));
+        assert_eq!(lines[5], (PreviewLineKind::Added, "seven".to_owned()));
+        assert_eq!(lines[7], (PreviewLineKind::Context, "9".to_owned()));
+    }
+}
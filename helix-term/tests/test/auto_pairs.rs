@@ -1,4 +1,4 @@
-use helix_core::{auto_pairs::DEFAULT_PAIRS, hashmap};
+use helix_core::{auto_pairs::DEFAULT_PAIRS, hashmap, syntax::config::AutoPairClose};
 
 use super::*;
 
@@ -34,7 +34,14 @@ async fn insert_configured_multi_byte_chars() -> anyhow::Result<()> {
 
     let config = Config {
         editor: helix_view::editor::Config {
-            auto_pairs: AutoPairConfig::Pairs(pairs.clone()),
+            auto_pairs: AutoPairConfig::Pairs(
+                pairs
+                    .iter()
+                    .map(|(open, close)| {
+                        (open.to_string(), AutoPairClose::Close(close.to_string()))
+                    })
+                    .collect(),
+            ),
             ..Default::default()
         },
         ..Default::default()
@@ -365,6 +372,43 @@ async fn insert_nested_open_inside_pair_multi() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn insert_multi_char_pairs() -> anyhow::Result<()> {
+    let mut pairs: std::collections::HashMap<_, _> = DEFAULT_PAIRS
+        .iter()
+        .map(|(open, close)| (open.to_string(), AutoPairClose::Close(close.to_string())))
+        .collect();
+    for (open, close) in [("\"\"\"", "\"\"\""), ("{%", "%}"), ("/*", "*/")] {
+        pairs.insert(open.to_string(), AutoPairClose::Close(close.to_string()));
+    }
+    let config = Config {
+        editor: helix_view::editor::Config {
+            auto_pairs: AutoPairConfig::Pairs(pairs),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    for (input, keys, output) in [
+        // Triple quotes are closed after typing the third quote.
+        ("#[\n|]#", "i\"\"\"", "\"\"\"#[|\"]#\"\"\n"),
+        ("#[\n|]#", "i\"\"\"\"\"\"", "\"\"\"\"\"\"#[|\n]#"),
+        // The `}` closing the `{` is replaced by `%}`.
+        ("#[\n|]#", "i{%", "{%#[|%]#}\n"),
+        ("#[\n|]#", "i{% x %}", "{% x %}#[|\n]#"),
+        ("#[\n|]#", "i/*", "/*#[|*]#/\n"),
+        ("#[\n|]#", "i/*<backspace>", "#[\n|]#"),
+    ] {
+        test_with_config(
+            AppBuilder::new().with_config(config.clone()),
+            (input, keys, output, LineFeedHandling::AsIs),
+        )
+        .await?;
+    }
+
+    Ok(())
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn append_basic() -> anyhow::Result<()> {
     for pair in DEFAULT_PAIRS {
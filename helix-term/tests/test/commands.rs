@@ -1,4 +1,6 @@
-use helix_term::application::Application;
+use helix_core::{hashmap, snippets::library::SnippetLibrary};
+use helix_term::{application::Application, keymap};
+use helix_view::doc;
 
 use super::*;
 
@@ -731,6 +733,40 @@ async fn surround_delete() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn surround_tags_and_functions() -> anyhow::Result<()> {
+    test((
+        "hello #[world|]#",
+        "mstp class=\"a\"<ret>",
+        "hello #[<p class=\"a\">world</p>|]#",
+    ))
+    .await?;
+    test((
+        "<p class=\"a\"><b>wo#[r|]#ld</b></p>",
+        "2mrttspan<ret>",
+        "<span><b>wo#[r|]#ld</b></span>",
+    ))
+    .await?;
+    test(("<p>wo#[r|]#ld</p>", "mdt", "wo#[r|]#ld")).await?;
+
+    test(("#[x|]#", "msfSome<ret>", "#[Some(x)|]#")).await?;
+    test(("x = foo(b#[a|]#r, (1))", "mdf", "x = b#[a|]#r, (1)")).await?;
+    test((
+        "let x = a.foo(b#[a|]#r);",
+        ":lang rust<ret>mrffbar<ret>",
+        "let x = a.bar(b#[a|]#r);",
+    ))
+    .await?;
+    test((
+        "let x = Some(foo(b#[a|]#r));",
+        ":lang rust<ret>2mdf",
+        "let x = foo(b#[a|]#r);",
+    ))
+    .await?;
+
+    Ok(())
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn surround_replace_ts() -> anyhow::Result<()> {
     const INPUT: &str = r#"\
@@ -844,3 +880,461 @@ async fn global_search_with_multibyte_chars() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_convert_case() -> anyhow::Result<()> {
+    test((
+        "#[parseHTTPResponse|]# #(utf8_decoder|)# #(Some Title|)#",
+        "<A-~>s",
+        "#[parse_http_response|]# #(utf8_decoder|)# #(some_title|)#",
+    ))
+    .await?;
+
+    test((
+        "#[parseHTTPResponse|]# #(utf8_decoder|)# #(Some Title|)#",
+        "<A-~>S",
+        "#[PARSE_HTTP_RESPONSE|]# #(UTF8_DECODER|)# #(SOME_TITLE|)#",
+    ))
+    .await?;
+
+    test((
+        "#[parse_http_response|]# #(IOError|)#",
+        "<A-~>c",
+        "#[parseHttpResponse|]# #(ioError|)#",
+    ))
+    .await?;
+
+    test((
+        "#[parse-http-response|]# #(ioError|)#",
+        "<A-~>p",
+        "#[ParseHttpResponse|]# #(IoError|)#",
+    ))
+    .await?;
+
+    test((
+        "#[parse_http_response|]#",
+        "<A-~>k",
+        "#[parse-http-response|]#",
+    ))
+    .await?;
+
+    test((
+        "#[parse_http_response|]#",
+        "<A-~>d",
+        "#[parse.http.response|]#",
+    ))
+    .await?;
+
+    // Whitespace around the words is kept.
+    test(("#[  some_title |]#", "<A-~>t", "#[  Some Title |]#")).await?;
+
+    // A conversion of all selections is undone at once.
+    test((
+        "#[parseHTTPResponse|]# #(utf8Decoder|)#",
+        "<A-~>su",
+        "#[parseHTTPResponse|]# #(utf8Decoder|)#",
+    ))
+    .await?;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_block_selection() -> anyhow::Result<()> {
+    test((
+        "he#[l|]#lo world\nab\nfoo bar baz\n",
+        "<C-v>jjlr-",
+        "he#(--|)#o world\nab\nfo#[--|]#bar baz\n",
+    ))
+    .await?;
+
+    // Moving the head back through a short line keeps its column.
+    test((
+        "he#[l|]#lo world\nab\nfoo bar baz\n",
+        "<C-v>jjkkjjr-",
+        "he#(-|)#lo world\nab\nfo#[-|]# bar baz\n",
+    ))
+    .await?;
+
+    test((
+        "hello world\nab\nfoo #[b|]#ar baz\n",
+        "<C-v>hkkiX<esc>",
+        "helX#[|lo]# world\nab\nfooX#(| b)#ar baz\n",
+    ))
+    .await?;
+
+    // Commands other than movements end the block and keep their own selection.
+    test((
+        "he#[l|]#lo world\nab\nfoo bar baz\n",
+        "<C-v>jjlso<ret>l",
+        "hello world\nab\nfo#[o |]#bar baz\n",
+    ))
+    .await?;
+
+    // Movements in macros and typed commands which move the cursor keep the block.
+    let config = Config {
+        keys: hashmap! {
+            helix_view::document::Mode::Select => keymap::KeyTrie::Node(keymap::KeyTrieNode::new(
+                "Select mode",
+                hashmap! {
+                    "F5".parse()? => keymap::KeyTrie::MappableCommand("@jjl".parse()?),
+                    "F6".parse()? => keymap::KeyTrie::MappableCommand(":goto 3".parse()?),
+                },
+                vec!["F5".parse()?, "F6".parse()?],
+            )),
+        },
+        ..test_config()
+    };
+    test_with_config(
+        AppBuilder::new().with_config(config.clone()),
+        (
+            "he#[l|]#lo world\nab\nfoo bar baz\n",
+            "<C-v><F5>r-",
+            "he#(--|)#o world\nab\nfo#[--|]#bar baz\n",
+        ),
+    )
+    .await?;
+    test_with_config(
+        AppBuilder::new().with_config(config),
+        (
+            "he#[l|]#lo world\nab\nfoo bar baz\n",
+            "<C-v>l<F6>r-",
+            "#(|---)#lo world\n#(|--)#\n#[|---]# bar baz\n",
+        ),
+    )
+    .await?;
+
+    let config = Config {
+        editor: helix_view::editor::Config {
+            block_virtual_space: helix_core::block_selection::VirtualSpace::Pad,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    test_with_config(
+        AppBuilder::new().with_config(config),
+        (
+            "he#[l|]#lo world\nab\nfoo bar baz\n",
+            "<C-v>jjlaX<esc>",
+            "he#(llX|)#o world\nab#(  X|)#\nfo#[o X|]#bar baz\n",
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_marks() -> anyhow::Result<()> {
+    test((
+        "one #[two|]# three\nfour\n",
+        "Mawjggifive <esc>'a",
+        "five one #[two|]# three\nfour\n",
+    ))
+    .await?;
+
+    test((
+        "#[one|]# #(two|)#\nthree\n",
+        "Mbjx'b",
+        "#[one|]# #(two|)#\nthree\n",
+    ))
+    .await?;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_register_picker() -> anyhow::Result<()> {
+    // Edit a register in a register buffer, write it and paste it from the picker.
+    test((
+        "#[one|]# two\n",
+        "\"ay<space>\"a<C-o>%cthree<esc>:w<ret>:bc<ret><space>\"a<ret>",
+        "one#[three|]# two\n",
+    ))
+    .await?;
+
+    // Clear a register from the picker.
+    test((
+        "#[one|]# two\n",
+        "\"ay<space>\"a<C-x>\"ap",
+        "#[one|]# two\n",
+    ))
+    .await?;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_snippet_picker() -> anyhow::Result<()> {
+    let dir = tempfile::tempdir()?;
+    std::fs::write(
+        dir.path().join("global.toml"),
+        indoc! {r#"
+            [header]
+            prefix = "hdr"
+            body = "${1:title} on line $TM_LINE_NUMBER$0"
+        "#},
+    )?;
+    let mut app = AppBuilder::new()
+        .with_input_text("one\n#[t|]#wo\n")
+        .build()?;
+    app.editor.snippets = SnippetLibrary::load(&[dir.path().to_path_buf()]);
+
+    // The snippet is inserted with its first placeholder selected, typing replaces it.
+    test_key_sequences(
+        &mut app,
+        vec![
+            (Some("<space>nheader<ret>"), None),
+            (
+                Some("Notes<esc>"),
+                Some(&|app| {
+                    let doc = doc!(app.editor);
+                    assert_eq!("one\nNotes on line 2two\n", doc.text());
+                }),
+            ),
+        ],
+        false,
+    )
+    .await?;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_sort() -> anyhow::Result<()> {
+    // The lines of a single selection are sorted.
+    test((
+        indoc! {"\
+            #[v1.10
+            v1.9
+            v1.2
+            |]#end
+            "},
+        ":sort --natural<ret>",
+        indoc! {"\
+            #[v1.2
+            v1.9
+            v1.10
+            |]#end
+            "},
+    ))
+    .await?;
+
+    test((
+        indoc! {"\
+            #[b,2
+            a,10
+            c,2|]#
+            "},
+        ":sort -n -u -f 2 -t ,<ret>",
+        indoc! {"\
+            #[b,2
+            a,10|]#
+            "},
+    ))
+    .await?;
+
+    // Multiple selections keep their positions.
+    test((
+        "#[a2|]# #(b10|)# #(c1|)#\n",
+        ":sort --numeric --reverse<ret>",
+        "#[b10|]# #(a2|)# #(c1|)#\n",
+    ))
+    .await?;
+
+    // Duplicate selections are deleted along with their separators.
+    test((
+        "#[b|]# #(a|)# #(b|)# #(a|)#\n",
+        ":sort --unique<ret>",
+        "#[a|]# #(b|)#\n",
+    ))
+    .await?;
+
+    test((
+        indoc! {"\
+            #[a
+            |]##(a
+            |)##(b
+            |)#end
+            "},
+        ":sort --unique<ret>",
+        indoc! {"\
+            #[a
+            |]##(b
+            |)#end
+            "},
+    ))
+    .await?;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_align() -> anyhow::Result<()> {
+    test((
+        indoc! {"\
+            #[let a = 1;
+            let long_name = 2;|]#
+            "},
+        ":align =<ret>",
+        indoc! {"\
+            #[let a         = 1;
+            let long_name = 2;|]#
+            "},
+    ))
+    .await?;
+
+    test((
+        indoc! {"\
+            #[T|]#able:
+            | a | b |
+            |-|-:|
+            | ccc | dd |
+            "},
+        "%:align --markdown<ret>",
+        indoc! {"\
+            #[Table:
+            | a   |   b |
+            | --- | --: |
+            | ccc |  dd |
+            |]#"},
+    ))
+    .await?;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_reflow() -> anyhow::Result<()> {
+    test((
+        indoc! {"\
+            #[> one two three four five six
+            > seven
+            - eight nine ten eleven twelve|]#
+            "},
+        ":reflow 20<ret>",
+        indoc! {"\
+            #[> one two three four
+            > five six seven
+            - eight nine ten
+              eleven twelve|]#
+            "},
+    ))
+    .await?;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_auto_wrap() -> anyhow::Result<()> {
+    let config = Config {
+        editor: helix_view::editor::Config {
+            text_width: 20,
+            auto_wrap: true,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    test_with_config(
+        AppBuilder::new().with_config(config),
+        (
+            "#[\n|]#",
+            "i<gt> one two three four five<esc>",
+            "> one two three four\n> five#[|\n]#",
+        ),
+    )
+    .await?;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_move_lines() -> anyhow::Result<()> {
+    test((
+        "one\ntwo\nth#[r|]#ee\nfour\n",
+        "<A-k>",
+        "one\nth#[r|]#ee\ntwo\nfour\n",
+    ))
+    .await?;
+
+    test((
+        "one\ntwo\nth#[r|]#ee\nfour\n",
+        "2<A-k>u",
+        "one\ntwo\nth#[r|]#ee\nfour\n",
+    ))
+    .await?;
+
+    // Adjacent selections move as one block and selections of whole lines keep their line
+    // ending.
+    test((
+        "#[one\n|]##(two|)#\nthree\nfour\nfive",
+        "2<A-j>",
+        "three\nfour\n#[one\n|]##(two|)#\nfive",
+    ))
+    .await?;
+
+    // The last line without a line ending moves up.
+    test((
+        "one\ntwo\n#[|three]#",
+        "<A-k><A-k><A-k>",
+        "#[|three]#\none\ntwo",
+    ))
+    .await?;
+
+    // Separate blocks move by the same count, limited by the block closest to the end.
+    test((
+        "#[o|]#ne\ntwo\n#(t|)#hree\nfour\n",
+        "5<A-j>",
+        "two\n#[o|]#ne\nfour\n#(t|)#hree\n",
+    ))
+    .await?;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_duplicate_selections() -> anyhow::Result<()> {
+    let config = Config {
+        keys: hashmap! {
+            helix_view::document::Mode::Normal => keymap!({ "Normal mode"
+                "F5" => duplicate_selections_below,
+                "F6" => duplicate_selections_above,
+            }),
+        },
+        ..test_config()
+    };
+    let test = |case| test_with_config(AppBuilder::new().with_config(config.clone()), case);
+
+    test((
+        "one\nt#[w|]#o\nthree\n",
+        "<F5>",
+        "one\ntwo\nt#[w|]#o\nthree\n",
+    ))
+    .await?;
+
+    test((
+        "one\nt#[w|]#o\nthree\n",
+        "2<F6>",
+        "one\nt#[w|]#o\ntwo\ntwo\nthree\n",
+    ))
+    .await?;
+
+    test((
+        "#[one\ntwo\n|]#three\n",
+        "<F5>",
+        "one\ntwo\n#[one\ntwo\n|]#three\n",
+    ))
+    .await?;
+
+    // Selections within a line duplicate only the selected text.
+    test((
+        "a #[foo|]# b #(bar|)#\n",
+        "<F5>",
+        "a foo#[foo|]# b bar#(bar|)#\n",
+    ))
+    .await?;
+
+    test(("a #[foo|]# b\n", "<F6>", "a #[foo|]#foo b\n")).await?;
+
+    Ok(())
+}
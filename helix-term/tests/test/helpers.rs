@@ -294,6 +294,8 @@ pub fn test_editor_config() -> helix_view::editor::Config {
             enable: false,
             ..Default::default()
         },
+        // Don't read or write the marks saved in the state directory of the user.
+        persist_marks: false,
         ..Default::default()
     }
 }
@@ -106,6 +106,7 @@ async fn surround_by_character() -> anyhow::Result<()> {
         "'so \"#[many 'good' text|]#\" here'",
     ))
     .await?;
+    test(("echo %PA#[T|]#H% done", "mi%", "echo %#[PATH|]#% done")).await?;
 
     // Selection direction is preserved
     test((
@@ -24,7 +24,7 @@
 use serde::Serialize;
 use std::borrow::Cow;
 use std::cell::Cell;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fmt::Display;
 use std::future::Future;
 use std::io;
@@ -38,7 +38,7 @@
     encoding,
     history::{History, State, UndoKind},
     indent::{auto_detect_indent_style, IndentStyle},
-    line_ending::auto_detect_line_ending,
+    line_ending::{auto_detect_line_ending, line_without_line_ending},
     syntax::{self, config::LanguageConfiguration},
     ChangeSet, Diagnostic, LineEnding, Range, Rope, RopeBuilder, Selection, Syntax, Transaction,
 };
@@ -46,7 +46,7 @@
 use crate::{
     editor::Config,
     events::{DocumentDidChange, SelectionDidChange},
-    expansion,
+    expansion, undo_file,
     view::ViewPosition,
     DocumentId, Editor, Theme, View, ViewId,
 };
@@ -144,6 +144,11 @@ pub struct Document {
     selections: HashMap<ViewId, Selection>,
     view_data: HashMap<ViewId, ViewData>,
     pub active_snippet: Option<ActiveSnippet>,
+    /// Named marks in this document, see [`crate::marks`].
+    marks: BTreeMap<char, Selection>,
+    /// The register edited in this scratch buffer. Writing the buffer without a path updates
+    /// the register instead of a file.
+    register: Option<char>,
 
     /// Inlay hints annotations for the document, by view.
     ///
@@ -697,6 +702,8 @@ pub fn from(
         Self {
             id: DocumentId::default(),
             active_snippet: None,
+            marks: BTreeMap::new(),
+            register: None,
             path: None,
             relative_path: OnceCell::new(),
             encoding,
@@ -779,11 +786,21 @@ pub fn open(
             (Rope::from(line_ending.as_str()), encoding, false)
         };
 
+        let persistent_undo = config.load().persistent_undo.clone();
         let loader = syn_loader.load();
         let mut doc = Self::from(rope, Some((encoding, has_bom)), config, syn_loader);
 
         // set the path and try detecting the language
         doc.set_path(Some(path));
+        if persistent_undo.enable {
+            if let Some(history) = doc
+                .path()
+                .and_then(|path| undo_file::load(path, doc.text(), &persistent_undo))
+            {
+                doc.last_saved_revision = history.current_revision();
+                doc.history.set(history);
+            }
+        }
         if detect_language {
             doc.detect_language(&loader);
         }
@@ -990,6 +1007,18 @@ impl Future<Output = Result<DocumentSavedEvent, anyhow::Error>> + 'static + Send
         let encoding_with_bom_info = (self.encoding, self.has_bom);
         let last_saved_time = self.last_saved_time;
 
+        // Changes which were not committed to the history yet would make the undo file
+        // inconsistent with the written text.
+        // The history is serialized along with the write since serializing large histories
+        // takes a while.
+        let persistent_undo = self.config.load().persistent_undo.clone();
+        let undo_history = (persistent_undo.enable && self.changes.is_empty()).then(|| {
+            let history = self.history.take();
+            let clone = history.clone();
+            self.history.set(history);
+            clone
+        });
+
         // We encode the file according to the `Document`'s encoding.
         let future = async move {
             use tokio::fs;
@@ -1116,6 +1145,18 @@ impl Future<Output = Result<DocumentSavedEvent, anyhow::Error>> + 'static + Send
 
             write_result?;
 
+            if let Some(history) = undo_history {
+                let path = path.clone();
+                let text = text.clone();
+                let _ = tokio::task::spawn_blocking(move || {
+                    let contents =
+                        undo_file::serialize(&path, &history, current_rev, &text, &persistent_undo);
+                    undo_file::write(&path, contents, undo_file::max_age(&persistent_undo))
+                        .map_err(|e| log::error!("Failed to write undo file: {e}"))
+                })
+                .await;
+            }
+
             let event = DocumentSavedEvent {
                 revision: current_rev,
                 save_time,
@@ -1417,6 +1458,13 @@ fn apply_impl(
                 .ensure_invariants(self.text.slice(..));
         }
 
+        for selection in self.marks.values_mut() {
+            *selection = selection
+                .clone()
+                .map(transaction.changes())
+                .ensure_invariants(self.text.slice(..));
+        }
+
         for view_data in self.view_data.values_mut() {
             view_data.view_position.anchor = transaction
                 .changes()
@@ -1706,6 +1754,26 @@ pub fn later(&mut self, view: &mut View, uk: UndoKind) -> bool {
         self.earlier_later_impl(view, uk, false)
     }
 
+    /// Jump to `revision` of the [`Document`]'s history, which may be on a different branch of
+    /// the undo tree. All changes along the way are applied as a single transaction.
+    pub fn jump_to_revision(&mut self, view: &mut View, revision: usize) -> bool {
+        self.append_changes_to_history(view);
+        let txn = self
+            .history
+            .get_mut()
+            .jump_to_revision(revision)
+            .into_iter()
+            .reduce(|acc, txn| acc.compose(txn));
+        let success = txn.is_some_and(|txn| self.apply_impl(&txn, view.id, true));
+        if success {
+            // reset changeset to fix len
+            self.changes = ChangeSet::new(self.text().slice(..));
+            // Sync with changes with the jumplist selections.
+            view.sync_changes(self);
+        }
+        success
+    }
+
     /// Commit pending changes to history
     pub fn append_changes_to_history(&mut self, view: &mut View) {
         if self.changes.is_empty() {
@@ -1967,6 +2035,28 @@ pub fn selections(&self) -> &HashMap<ViewId, Selection> {
         &self.selections
     }
 
+    /// Named marks in this document by name.
+    pub fn marks(&self) -> &BTreeMap<char, Selection> {
+        &self.marks
+    }
+
+    pub fn set_mark(&mut self, name: char, selection: Selection) {
+        self.marks.insert(name, selection);
+    }
+
+    pub fn remove_mark(&mut self, name: char) -> Option<Selection> {
+        self.marks.remove(&name)
+    }
+
+    /// The register edited in this buffer, if it is a register buffer.
+    pub fn register(&self) -> Option<char> {
+        self.register
+    }
+
+    pub fn set_register(&mut self, register: Option<char>) {
+        self.register = register;
+    }
+
     fn view_data(&self, view_id: ViewId) -> &ViewData {
         self.view_data
             .get(&view_id)
@@ -2000,8 +2090,11 @@ pub fn relative_path(&self) -> Option<&Path> {
     }
 
     pub fn display_name(&self) -> Cow<'_, str> {
-        self.relative_path()
-            .map_or_else(|| SCRATCH_BUFFER_NAME.into(), |path| path.to_string_lossy())
+        match (self.relative_path(), self.register) {
+            (Some(path), _) => path.to_string_lossy(),
+            (None, Some(register)) => format!("[register {register}]").into(),
+            (None, None) => SCRATCH_BUFFER_NAME.into(),
+        }
     }
 
     // transact(Fn) ?
@@ -2200,10 +2293,25 @@ pub fn auto_pairs<'a>(
             .or(global_config)
     }
 
-    pub fn snippet_ctx(&self) -> SnippetRenderCtx {
+    /// The context for rendering snippets at the selection of `view_id`, which resolves the
+    /// snippet variables like `TM_FILENAME` or `CURRENT_YEAR`.
+    pub fn snippet_ctx(&self, view_id: ViewId) -> SnippetRenderCtx {
+        let language_config = self.language_config();
+        let variables = SnippetVariables {
+            text: self.text.clone(),
+            range: self.selection(view_id).primary(),
+            path: self.path().cloned(),
+            relative_path: self.relative_path().map(Path::to_path_buf),
+            line_comment: language_config
+                .and_then(|config| config.comment_tokens.as_ref()?.first().cloned()),
+            block_comment: language_config.and_then(|config| {
+                let token = config.block_comment_tokens.as_ref()?.first()?;
+                Some((token.start.clone(), token.end.clone()))
+            }),
+            now: chrono::Local::now(),
+        };
         SnippetRenderCtx {
-            // TODO snippet variable resolution
-            resolve_var: Box::new(|_| None),
+            resolve_var: Box::new(move |name| variables.resolve(name).map(Cow::Owned)),
             tab_width: self.tab_width(),
             indent_style: self.indent_style,
             line_ending: self.line_ending.as_str(),
@@ -2307,6 +2415,85 @@ pub fn has_language_server_with_feature(&self, feature: LanguageServerFeature) -
     }
 }
 
+/// The values of the variables which can be used in snippets, see
+/// <https://code.visualstudio.com/docs/editing/userdefinedsnippets#_variables>.
+struct SnippetVariables {
+    text: Rope,
+    /// The primary range of the selection the snippet is inserted at.
+    range: Range,
+    path: Option<PathBuf>,
+    relative_path: Option<PathBuf>,
+    line_comment: Option<String>,
+    block_comment: Option<(String, String)>,
+    now: chrono::DateTime<chrono::Local>,
+}
+
+impl SnippetVariables {
+    fn resolve(&self, name: &str) -> Option<String> {
+        let text = self.text.slice(..);
+        let cursor = self.range.cursor(text);
+        let line = text.char_to_line(cursor);
+        let path = || self.path.as_deref();
+        let date = |format| Some(self.now.format(format).to_string());
+        match name {
+            // A single grapheme is the cursor rather than a selection.
+            "TM_SELECTED_TEXT" if self.range.len() > 1 => Some(self.range.fragment(text).into()),
+            "TM_CURRENT_LINE" => Some(line_without_line_ending(&text, line).into()),
+            "TM_CURRENT_WORD" => {
+                let start = (0..cursor)
+                    .rev()
+                    .take_while(|&i| char_is_word(text.char(i)))
+                    .last()
+                    .unwrap_or(cursor);
+                let end = (cursor..text.len_chars())
+                    .take_while(|&i| char_is_word(text.char(i)))
+                    .last()
+                    .map_or(cursor, |i| i + 1);
+                (start < end).then(|| text.slice(start..end).into())
+            }
+            "TM_LINE_INDEX" => Some(line.to_string()),
+            "TM_LINE_NUMBER" => Some((line + 1).to_string()),
+            "TM_FILENAME" => Some(path()?.file_name()?.to_string_lossy().into_owned()),
+            "TM_FILENAME_BASE" => Some(path()?.file_stem()?.to_string_lossy().into_owned()),
+            "TM_DIRECTORY" => Some(path()?.parent()?.to_string_lossy().into_owned()),
+            "TM_FILEPATH" => Some(path()?.to_string_lossy().into_owned()),
+            "RELATIVE_FILEPATH" => {
+                Some(self.relative_path.as_ref()?.to_string_lossy().into_owned())
+            }
+            "WORKSPACE_NAME" => Some(
+                helix_loader::find_workspace()
+                    .0
+                    .file_name()?
+                    .to_string_lossy()
+                    .into_owned(),
+            ),
+            "WORKSPACE_FOLDER" => Some(
+                helix_loader::find_workspace()
+                    .0
+                    .to_string_lossy()
+                    .into_owned(),
+            ),
+            "LINE_COMMENT" => self.line_comment.clone(),
+            "BLOCK_COMMENT_START" => Some(self.block_comment.as_ref()?.0.clone()),
+            "BLOCK_COMMENT_END" => Some(self.block_comment.as_ref()?.1.clone()),
+            "CURRENT_YEAR" => date("%Y"),
+            "CURRENT_YEAR_SHORT" => date("%y"),
+            "CURRENT_MONTH" => date("%m"),
+            "CURRENT_MONTH_NAME" => date("%B"),
+            "CURRENT_MONTH_NAME_SHORT" => date("%b"),
+            "CURRENT_DATE" => date("%d"),
+            "CURRENT_DAY_NAME" => date("%A"),
+            "CURRENT_DAY_NAME_SHORT" => date("%a"),
+            "CURRENT_HOUR" => date("%H"),
+            "CURRENT_MINUTE" => date("%M"),
+            "CURRENT_SECOND" => date("%S"),
+            "CURRENT_SECONDS_UNIX" => Some(self.now.timestamp().to_string()),
+            "CURRENT_TIMEZONE_OFFSET" => date("%:z"),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug, Default)]
 pub struct ViewData {
     view_position: ViewPosition,
@@ -9,13 +9,15 @@
     handlers::Handlers,
     info::Info,
     input::KeyEvent,
-    register::Registers,
+    marks::{self, Marks},
+    register::{self, Registers},
     theme::{self, Theme},
     tree::{self, Tree},
     Document, DocumentId, View, ViewId,
 };
 use helix_event::dispatch;
 use helix_vcs::DiffProviderRegistry;
+use parking_lot::RwLock;
 
 use futures_util::stream::select_all::SelectAll;
 use futures_util::{future, StreamExt};
@@ -31,7 +33,7 @@
     num::{NonZeroU8, NonZeroUsize},
     path::{Path, PathBuf},
     pin::Pin,
-    sync::Arc,
+    sync::{Arc, OnceLock},
 };
 
 use tokio::{
@@ -44,7 +46,10 @@
 pub use helix_core::diagnostic::Severity;
 use helix_core::{
     auto_pairs::AutoPairs,
+    block_selection::VirtualSpace,
     diagnostic::DiagnosticProvider,
+    snippets::library::SnippetLibrary,
+    spelling::SpellChecker,
     syntax::{
         self,
         config::{AutoPairConfig, IndentationHeuristic, LanguageServerFeature, SoftWrap},
@@ -324,6 +329,8 @@ pub struct Config {
     /// Configures completion of words from open buffers.
     /// Defaults to enabled with a trigger length of 7.
     pub word_completion: WordCompletion,
+    /// Configures the built-in spell checker. Disabled by default.
+    pub spelling: SpellingConfig,
     /// Automatic formatting on save. Defaults to true.
     pub auto_format: bool,
     /// Default register used for yank/paste. Defaults to '"'
@@ -335,6 +342,8 @@ pub struct Config {
     pub auto_save: AutoSave,
     /// Set a global text_width
     pub text_width: usize,
+    /// Automatically wrap lines at `text_width` while typing in insert mode. Defaults to false.
+    pub auto_wrap: bool,
     /// Time in milliseconds since last keypress before idle timers trigger.
     /// Used for various UI timeouts. Defaults to 250ms.
     #[serde(
@@ -428,6 +437,72 @@ pub struct Config {
     /// Whether to enable Kitty Keyboard Protocol
     pub kitty_keyboard_protocol: KittyKeyboardProtocolConfig,
     pub buffer_picker: BufferPickerConfig,
+    /// Persist the undo history of documents across editor restarts.
+    pub persistent_undo: PersistentUndoConfig,
+    /// Persist registers, including recorded macros and prompt histories, across editor
+    /// restarts.
+    pub persistent_registers: PersistentRegistersConfig,
+    /// Groups of words which `increment` and `decrement` cycle through, like `true` and `false`.
+    /// Groups configured for a language are tried first.
+    pub increment_groups: Vec<Vec<String>>,
+    /// Whether `increment` and `decrement` change single letters, like `a` to `b`.
+    pub increment_letters: bool,
+    /// How block selections treat lines which end before the block. Defaults to `skip`.
+    pub block_virtual_space: VirtualSpace,
+    /// Save marks when exiting and restore them on startup or, for the marks local to a file,
+    /// when the file is opened. Defaults to `true`.
+    pub persist_marks: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct PersistentUndoConfig {
+    /// Whether to write the undo history of a document to an undo file in the state directory
+    /// when it is written and restore it when it is opened. Defaults to `false`.
+    pub enable: bool,
+    /// The maximum size of an undo file in bytes. Larger histories are not persisted.
+    /// Defaults to 10 MiB.
+    pub max_size: u64,
+    /// Undo files which were not updated within this many days are discarded. Defaults to `30`.
+    pub max_age: u64,
+}
+
+impl Default for PersistentUndoConfig {
+    fn default() -> Self {
+        Self {
+            enable: false,
+            max_size: 10 * 1024 * 1024,
+            max_age: 30,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct PersistentRegistersConfig {
+    /// Whether to save registers to the state directory when exiting and restore them on
+    /// startup. Defaults to `false`.
+    pub enable: bool,
+    /// The registers which are persisted. Defaults to the registers `a` to `z`, the default yank
+    /// register `"`, the default macro register `@` and the command, search and shell histories
+    /// (`:`, `/` and `|`).
+    pub registers: Vec<char>,
+    /// Registers whose values are larger than this many bytes are not persisted, which keeps
+    /// large yanks out of the registers file. Defaults to 64 KiB.
+    pub max_size: usize,
+    /// The maximum number of entries persisted for each history. Defaults to `100`.
+    pub max_history: usize,
+}
+
+impl Default for PersistentRegistersConfig {
+    fn default() -> Self {
+        Self {
+            enable: false,
+            registers: ('a'..='z').chain(['"', '@', ':', '/', '|']).collect(),
+            max_size: 64 * 1024,
+            max_history: 100,
+        }
+    }
 }
 
 #[derive(Debug, Default, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize, Clone, Copy)]
@@ -1080,6 +1155,26 @@ fn default() -> Self {
     }
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+pub struct SpellingConfig {
+    pub enable: bool,
+    /// The name of the Hunspell dictionary, like `en_US`.
+    pub language: String,
+    /// The highlight scopes which are checked unless the language configures its own.
+    pub scopes: Vec<String>,
+}
+
+impl Default for SpellingConfig {
+    fn default() -> Self {
+        Self {
+            enable: false,
+            language: "en_US".to_owned(),
+            scopes: vec!["comment".to_owned(), "string".to_owned()],
+        }
+    }
+}
+
 impl Default for Config {
     fn default() -> Self {
         Self {
@@ -1100,6 +1195,7 @@ fn default() -> Self {
             auto_completion: true,
             path_completion: true,
             word_completion: WordCompletion::default(),
+            spelling: SpellingConfig::default(),
             auto_format: true,
             default_yank_register: '"',
             auto_save: AutoSave::default(),
@@ -1127,6 +1223,7 @@ fn default() -> Self {
                 ..SoftWrap::default()
             },
             text_width: 80,
+            auto_wrap: false,
             completion_replace: false,
             continue_comments: true,
             workspace_lsp_roots: Vec::new(),
@@ -1146,6 +1243,15 @@ fn default() -> Self {
             rainbow_brackets: false,
             kitty_keyboard_protocol: Default::default(),
             buffer_picker: BufferPickerConfig::default(),
+            persistent_undo: PersistentUndoConfig::default(),
+            persistent_registers: PersistentRegistersConfig::default(),
+            increment_groups: [["true", "false"], ["yes", "no"], ["on", "off"]]
+                .iter()
+                .map(|group| group.iter().map(|word| word.to_string()).collect())
+                .collect(),
+            increment_letters: false,
+            block_virtual_space: VirtualSpace::default(),
+            persist_marks: true,
         }
     }
 }
@@ -1159,6 +1265,37 @@ fn default() -> Self {
     }
 }
 
+/// A spelling dictionary which is loaded once, see [`load_dictionary`]. `None` if it couldn't
+/// be loaded.
+pub type Dictionary = Arc<OnceLock<Option<Arc<RwLock<SpellChecker>>>>>;
+
+/// Loads the dictionary `name` unless it's already loaded, waiting if another thread is loading
+/// it. The error is only returned to the caller which failed to load it, so that it's reported
+/// once.
+pub fn load_dictionary(
+    dictionary: &Dictionary,
+    name: &str,
+) -> Result<Option<Arc<RwLock<SpellChecker>>>, Error> {
+    let mut error = None;
+    let checker = dictionary.get_or_init(|| {
+        match SpellChecker::load(
+            name,
+            &helix_loader::dictionary_dirs(),
+            helix_loader::personal_dictionary_file(name),
+        ) {
+            Ok(checker) => Some(Arc::new(RwLock::new(checker))),
+            Err(err) => {
+                error = Some(err);
+                None
+            }
+        }
+    });
+    match error {
+        Some(err) => Err(err),
+        None => Ok(checker.clone()),
+    }
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct Breakpoint {
     pub id: Option<usize>,
@@ -1170,6 +1307,8 @@ pub struct Breakpoint {
     pub condition: Option<String>,
     pub hit_condition: Option<String>,
     pub log_message: Option<String>,
+    /// Temporary breakpoints are removed once the debuggee stops at them.
+    pub temporary: bool,
 }
 
 use futures_util::stream::{Flatten, Once};
@@ -1194,6 +1333,12 @@ pub struct Editor {
     pub registers: Registers,
     pub macro_recording: Option<(char, Vec<KeyEvent>)>,
     pub macro_replaying: Vec<char>,
+    /// Global marks in documents which are not open.
+    pub marks: Marks,
+    /// The user-defined snippets, see [`SnippetLibrary`].
+    pub snippets: SnippetLibrary,
+    /// The spell checkers by dictionary name, loaded on first use, see [`Editor::dictionary`].
+    pub dictionaries: HashMap<String, Dictionary>,
     pub language_servers: helix_lsp::Registry,
     pub diagnostics: Diagnostics,
     pub diff_providers: DiffProviderRegistry,
@@ -1327,6 +1472,14 @@ pub fn new(
         // HAXX: offset the render area height by 1 to account for prompt/commandline
         area.height -= 1;
 
+        let mut registers = Registers::new(Box::new(arc_swap::access::Map::new(
+            Arc::clone(&config),
+            |config: &Config| &config.clipboard_provider,
+        )));
+        if conf.persistent_registers.enable {
+            registers.restore(&register::registers_file(), &conf.persistent_registers);
+        }
+
         Self {
             mode: Mode::Normal,
             tree: Tree::new(area),
@@ -1349,10 +1502,14 @@ pub fn new(
             theme_loader,
             last_theme: None,
             last_selection: None,
-            registers: Registers::new(Box::new(arc_swap::access::Map::new(
-                Arc::clone(&config),
-                |config: &Config| &config.clipboard_provider,
-            ))),
+            registers,
+            marks: if conf.persist_marks {
+                Marks::load(&marks::marks_file())
+            } else {
+                Marks::default()
+            },
+            snippets: SnippetLibrary::load(&helix_loader::snippet_dirs()),
+            dictionaries: HashMap::new(),
             status_msg: None,
             autoinfo: None,
             idle_timer: Box::pin(sleep(conf.idle_timeout)),
@@ -1851,6 +2008,7 @@ fn new_document(&mut self, mut doc: Document) -> DocumentId {
         self.next_document_id =
             DocumentId(unsafe { NonZeroUsize::new_unchecked(self.next_document_id.0.get() + 1) });
         doc.id = id;
+        self.marks.restore(&mut doc);
         self.documents.insert(id, doc);
 
         let (save_sender, save_receiver) = tokio::sync::mpsc::unbounded_channel();
@@ -1862,7 +2020,7 @@ fn new_document(&mut self, mut doc: Document) -> DocumentId {
         id
     }
 
-    fn new_file_from_document(&mut self, action: Action, doc: Document) -> DocumentId {
+    pub fn new_file_from_document(&mut self, action: Action, doc: Document) -> DocumentId {
         let id = self.new_document(doc);
         self.switch(id, action);
         id
@@ -1895,6 +2053,38 @@ pub fn new_file_from_stdin(&mut self, action: Action) -> Result<DocumentId, Erro
         Ok(doc_id)
     }
 
+    /// Sets the mark `name` on the selection of the current view. Global marks are moved from
+    /// any other document.
+    pub fn set_mark(&mut self, name: char) {
+        let (view, doc) = current_ref!(self);
+        let (doc_id, selection) = (doc.id(), doc.selection(view.id).clone());
+        if marks::is_global(name) {
+            self.marks.set(name);
+            for doc in self.documents.values_mut() {
+                doc.remove_mark(name);
+            }
+        }
+        doc_mut!(self, &doc_id).set_mark(name, selection);
+    }
+
+    /// Saves the registers if `editor.persistent-registers` is enabled.
+    pub fn save_registers(&self) -> io::Result<()> {
+        let config = &self.config().persistent_registers;
+        if !config.enable {
+            return Ok(());
+        }
+        self.registers.save(&register::registers_file(), config)
+    }
+
+    /// Saves the global marks if `editor.persist-marks` is enabled.
+    pub fn save_marks(&self) -> io::Result<()> {
+        if !self.config().persist_marks {
+            return Ok(());
+        }
+        self.marks
+            .save(&marks::marks_file(), self.documents.values())
+    }
+
     pub fn document_id_by_path(&self, path: &Path) -> Option<DocumentId> {
         self.document_by_path(path).map(|doc| doc.id)
     }
@@ -1998,6 +2188,7 @@ enum Action {
         }
 
         let doc = self.documents.remove(&doc_id).unwrap();
+        self.marks.store(&doc);
 
         // If the document we removed was visible in all views, we will have no more views. We don't
         // want to close the editor just for a simple buffer close, so we need to create a new view
@@ -2151,6 +2342,29 @@ pub fn documents_mut(&mut self) -> impl Iterator<Item = &mut Document> {
         self.documents.values_mut()
     }
 
+    /// The dictionary `name`, which is loaded by the first [`load_dictionary`] call. Clones
+    /// share the dictionary so that it's only loaded once when it's checked concurrently.
+    pub fn dictionary(&mut self, name: &str) -> Dictionary {
+        self.dictionaries
+            .entry(name.to_string())
+            .or_default()
+            .clone()
+    }
+
+    /// The spell checker for `editor.spelling.language`, which is loaded on first use. Reports
+    /// an error if the dictionary can't be loaded.
+    pub fn spell_checker(&mut self) -> Option<Arc<RwLock<SpellChecker>>> {
+        let language = self.config().spelling.language.clone();
+        let dictionary = self.dictionary(&language);
+        match load_dictionary(&dictionary, &language) {
+            Ok(checker) => checker,
+            Err(err) => {
+                self.set_error(format!("Failed to load the spelling dictionary: {err}"));
+                None
+            }
+        }
+    }
+
     pub fn document_by_path<P: AsRef<Path>>(&self, path: P) -> Option<&Document> {
         self.documents()
             .find(|doc| doc.path().map(|p| p == path.as_ref()).unwrap_or(false))
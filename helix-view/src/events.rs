@@ -1,4 +1,5 @@
 use helix_core::{ChangeSet, Rope};
+use helix_dap::{registry::DebugAdapterId, ThreadId};
 use helix_event::events;
 use helix_lsp::LanguageServerId;
 
@@ -34,6 +35,14 @@
         server_id: LanguageServerId
     }
 
+    // called after a thread stopped because of an exception and the exception
+    // details were stored in the debugger's `exceptions`
+    DebuggerStoppedOnException<'a> {
+        editor: &'a mut Editor,
+        debugger: DebugAdapterId,
+        thread_id: ThreadId
+    }
+
     // NOTE: this event is simple for now and is expected to change as the config system evolves.
     // Ideally it would say what changed.
     ConfigDidChange<'a> {
@@ -227,7 +227,7 @@ fn expand_inner<'a>(editor: &Editor, content: Cow<'a, str>) -> Result<Cow<'a, st
 // function to return then, instead, would normally be a `String`. We can return some statically
 // known strings like the scratch buffer name or line ending strings though, so this function
 // returns a `Cow<'static, str>` instead.
-fn expand_variable(editor: &Editor, variable: Variable) -> Result<Cow<'static, str>> {
+pub fn expand_variable(editor: &Editor, variable: Variable) -> Result<Cow<'static, str>> {
     let (view, doc) = current_ref!(editor);
     let text = doc.text().slice(..);
 
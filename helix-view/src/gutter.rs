@@ -278,28 +278,37 @@ fn execution_pause_indicator<'doc>(
     is_focused: bool,
 ) -> GutterFn<'doc> {
     let style = theme.get("ui.debug.active");
-    let current_stack_frame = editor.current_stack_frame();
-    let frame_line = current_stack_frame.map(|frame| frame.line.saturating_sub(1));
-    let frame_source_path = current_stack_frame.map(|frame| {
-        frame
-            .source
-            .as_ref()
-            .and_then(|source| source.path.as_ref())
-    });
-    let should_display_for_current_doc =
-        doc.path().is_some() && frame_source_path.unwrap_or(None) == doc.path();
+    let active_client = editor.debug_adapters.active_client_id();
+    // The lines in this document at which a debugging session is paused, and whether that
+    // session is the focused one.
+    let paused_lines: Vec<(usize, bool)> = editor
+        .debug_adapters
+        .clients()
+        .filter_map(|client| {
+            let frame = client.current_stack_frame()?;
+            let path = frame.source.as_ref()?.path.as_ref()?;
+            (doc.path() == Some(path)).then(|| {
+                (
+                    frame.line.saturating_sub(1),
+                    active_client == Some(client.id()),
+                )
+            })
+        })
+        .collect();
 
     Box::new(
         move |line: usize, _selected: bool, first_visual_line: bool, out: &mut String| {
-            if !first_visual_line
-                || !is_focused
-                || line != frame_line?
-                || !should_display_for_current_doc
-            {
+            if !first_visual_line || !is_focused {
                 return None;
             }
 
-            let sym = "▶";
+            // Prefer the focused session if multiple sessions are paused on the same line.
+            let (_, focused) = paused_lines
+                .iter()
+                .filter(|(paused_line, _)| *paused_line == line)
+                .max_by_key(|(_, focused)| *focused)?;
+
+            let sym = if *focused { "▶" } else { "▷" };
             write!(out, "{}", sym).unwrap();
             Some(style)
         },
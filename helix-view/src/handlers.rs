@@ -11,6 +11,10 @@
 pub mod lsp;
 pub mod word_index;
 
+/// Requests spell checking a document once edits pause.
+#[derive(Debug)]
+pub struct SpellingEvent(pub DocumentId);
+
 #[derive(Debug)]
 pub enum AutoSaveEvent {
     DocumentChanged { save_after: u64 },
@@ -23,6 +27,7 @@ pub struct Handlers {
     pub signature_hints: Sender<lsp::SignatureHelpEvent>,
     pub auto_save: Sender<AutoSaveEvent>,
     pub document_colors: Sender<lsp::DocumentColorsEvent>,
+    pub spelling: Sender<SpellingEvent>,
     pub word_index: word_index::Handler,
     pub pull_diagnostics: Sender<lsp::PullDiagnosticsEvent>,
     pub pull_all_documents_diagnostics: Sender<lsp::PullAllDocumentsDiagnosticsEvent>,
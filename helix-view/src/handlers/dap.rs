@@ -1,4 +1,6 @@
 use crate::editor::{Action, Breakpoint};
+use crate::events::DebuggerStoppedOnException;
+use crate::expansion::{expand_variable, Variable};
 use crate::{align_view, Align, Editor};
 use dap::requests::DisconnectArguments;
 use dap::requests::ThreadsArguments;
@@ -9,8 +11,9 @@
 use helix_lsp::block_on;
 use log::{error, warn};
 use serde_json::{json, Value};
+use std::borrow::Cow;
 use std::fmt::Write;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 #[macro_export]
 macro_rules! debugger {
@@ -31,8 +34,119 @@ pub fn dap_pos_to_pos(doc: &helix_core::Rope, line: usize, column: usize) -> Opt
     Some(pos)
 }
 
-pub async fn select_thread_id(editor: &mut Editor, thread_id: ThreadId, force: bool) {
-    let debugger = debugger!(editor);
+/// Substitutes VS Code style variables such as `${workspaceFolder}`, `${file}` or `${env:HOME}`
+/// in the string values of a `.vscode/launch.json` configuration. Unsupported variables like
+/// `${command:pickProcess}` or `${input:name}` are left as they are.
+///
+/// See <https://code.visualstudio.com/docs/editor/variables-reference>.
+pub fn expand_launch_json_variables(editor: &Editor, value: &Value) -> anyhow::Result<Value> {
+    expand_launch_json_value(value, &|variable| expand_variable(editor, variable))
+}
+
+type VariableResolver<'a> = dyn Fn(Variable) -> anyhow::Result<Cow<'static, str>> + 'a;
+
+fn expand_launch_json_value(value: &Value, resolve: &VariableResolver) -> anyhow::Result<Value> {
+    Ok(match value {
+        Value::String(string) => Value::String(expand_launch_json_string(string, resolve)?),
+        Value::Array(array) => Value::Array(
+            array
+                .iter()
+                .map(|value| expand_launch_json_value(value, resolve))
+                .collect::<anyhow::Result<_>>()?,
+        ),
+        Value::Object(object) => Value::Object(
+            object
+                .iter()
+                .map(|(key, value)| Ok((key.clone(), expand_launch_json_value(value, resolve)?)))
+                .collect::<anyhow::Result<_>>()?,
+        ),
+        value => value.clone(),
+    })
+}
+
+fn expand_launch_json_string(string: &str, resolve: &VariableResolver) -> anyhow::Result<String> {
+    let mut expanded = String::with_capacity(string.len());
+    let mut rest = string;
+
+    while let Some(start) = rest.find("${") {
+        let Some(len) = rest[start..].find('}') else {
+            break;
+        };
+        expanded.push_str(&rest[..start]);
+        let name = &rest[start + 2..start + len];
+        match expand_launch_json_variable(name, resolve)? {
+            Some(value) => expanded.push_str(&value),
+            None => {
+                warn!("Unsupported variable '${{{name}}}' in launch configuration");
+                expanded.push_str(&rest[start..start + len + 1]);
+            }
+        }
+        rest = &rest[start + len + 1..];
+    }
+    expanded.push_str(rest);
+
+    Ok(expanded)
+}
+
+fn expand_launch_json_variable(
+    name: &str,
+    resolve: &VariableResolver,
+) -> anyhow::Result<Option<String>> {
+    if let Some(var) = name.strip_prefix("env:") {
+        return Ok(Some(std::env::var(var).unwrap_or_default()));
+    }
+
+    let file = || resolve(Variable::FilePathAbsolute).map(|p| PathBuf::from(&*p));
+    let workspace = || resolve(Variable::WorkspaceDirectory).map(|p| PathBuf::from(&*p));
+    let file_name = |path: &Path| {
+        path.file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default()
+    };
+
+    let value = match name {
+        "workspaceFolder" | "workspaceRoot" => resolve(Variable::WorkspaceDirectory)?.into_owned(),
+        "workspaceFolderBasename" => file_name(&workspace()?),
+        "file" => resolve(Variable::FilePathAbsolute)?.into_owned(),
+        "fileBasename" => file_name(&file()?),
+        "fileBasenameNoExtension" => file()?
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().into_owned())
+            .unwrap_or_default(),
+        "fileExtname" => file()?
+            .extension()
+            .map(|ext| format!(".{}", ext.to_string_lossy()))
+            .unwrap_or_default(),
+        "fileDirname" => file()?
+            .parent()
+            .map(|dir| dir.to_string_lossy().into_owned())
+            .unwrap_or_default(),
+        "relativeFile" => {
+            let file = file()?;
+            file.strip_prefix(workspace()?)
+                .unwrap_or(&file)
+                .to_string_lossy()
+                .into_owned()
+        }
+        "cwd" => resolve(Variable::CurrentWorkingDirectory)?.into_owned(),
+        "lineNumber" => resolve(Variable::CursorLine)?.into_owned(),
+        "selectedText" => resolve(Variable::Selection)?.into_owned(),
+        "pathSeparator" | "/" => std::path::MAIN_SEPARATOR.to_string(),
+        _ => return Ok(None),
+    };
+
+    Ok(Some(value))
+}
+
+pub async fn select_thread_id(
+    editor: &mut Editor,
+    id: DebugAdapterId,
+    thread_id: ThreadId,
+    force: bool,
+) {
+    let Some(debugger) = editor.debug_adapters.get_client_mut(id) else {
+        return;
+    };
 
     if !force && debugger.thread_id.is_some() {
         return;
@@ -42,6 +156,10 @@ pub async fn select_thread_id(editor: &mut Editor, thread_id: ThreadId, force: b
     fetch_stack_trace(debugger, thread_id).await;
 
     let frame = debugger.stack_frames[&thread_id].first().cloned();
+    // Only jump to the frames of the focused session.
+    if editor.debug_adapters.active_client_id() != Some(id) {
+        return;
+    }
     if let Some(frame) = &frame {
         jump_to_stack_frame(editor, frame);
     }
@@ -56,6 +174,45 @@ pub async fn fetch_stack_trace(debugger: &mut Client, thread_id: ThreadId) {
     debugger.active_frame = Some(0);
 }
 
+/// Whether two sources reported by a debugger refer to the same source. Sources are identified
+/// by their path or, if they are not available locally, by their source reference.
+fn is_same_source(a: &dap::Source, b: &dap::Source) -> bool {
+    match (&a.path, &b.path) {
+        (Some(a), Some(b)) => a == b,
+        (None, None) => a.source_reference.is_some() && a.source_reference == b.source_reference,
+        _ => false,
+    }
+}
+
+/// Requests the details of the exception which stopped `thread_id` and notifies the UI about it.
+async fn fetch_exception_info(editor: &mut Editor, id: DebugAdapterId, thread_id: ThreadId) {
+    let Some(debugger) = editor.debug_adapters.get_client_mut(id) else {
+        return;
+    };
+    if !debugger
+        .capabilities()
+        .supports_exception_info_request
+        .unwrap_or_default()
+    {
+        return;
+    }
+    match debugger.exception_info(thread_id).await {
+        Ok(exception) => {
+            debugger.exceptions.insert(thread_id, exception);
+        }
+        Err(err) => {
+            warn!("Failed to fetch exception info: {err}");
+            return;
+        }
+    }
+
+    helix_event::dispatch(DebuggerStoppedOnException {
+        editor,
+        debugger: id,
+        thread_id,
+    });
+}
+
 pub fn jump_to_stack_frame(editor: &mut Editor, frame: &helix_dap::StackFrame) {
     let path = if let Some(helix_dap::Source {
         path: Some(ref path),
@@ -143,6 +300,42 @@ pub fn breakpoints_changed(
     Ok(())
 }
 
+/// Removes the temporary breakpoints among `breakpoints` which were hit. Returns whether any
+/// breakpoint was removed.
+fn remove_hit_temporary_breakpoints(
+    breakpoints: &mut Vec<Breakpoint>,
+    hit_breakpoint_ids: &[usize],
+) -> bool {
+    let len = breakpoints.len();
+    breakpoints.retain(|breakpoint| {
+        !breakpoint.temporary
+            || breakpoint
+                .id
+                .is_none_or(|id| !hit_breakpoint_ids.contains(&id))
+    });
+    breakpoints.len() != len
+}
+
+/// Removes the temporary breakpoints which stopped the debuggee and notifies the debugger about
+/// the affected files.
+pub fn clear_hit_temporary_breakpoints(
+    editor: &mut Editor,
+    id: DebugAdapterId,
+    hit_breakpoint_ids: &[usize],
+) {
+    let Some(debugger) = editor.debug_adapters.get_client_mut(id) else {
+        return;
+    };
+
+    for (path, breakpoints) in &mut editor.breakpoints {
+        if remove_hit_temporary_breakpoints(breakpoints, hit_breakpoint_ids) {
+            if let Err(err) = breakpoints_changed(debugger, path.clone(), breakpoints) {
+                log::error!("{err}");
+            }
+        }
+    }
+}
+
 impl Editor {
     pub async fn handle_debugger_message(
         &mut self,
@@ -171,8 +364,17 @@ pub async fn handle_debugger_message(
                         text,
                         reason,
                         all_threads_stopped,
+                        hit_breakpoint_ids,
                         ..
                     }) => {
+                        // Focus the session which stopped so that commands act on it, unless the
+                        // session being debugged is stopped as well.
+                        let focused = self.debug_adapters.focus_stopped_client(id);
+                        if reason == "breakpoint" {
+                            if let Some(hit_breakpoint_ids) = &hit_breakpoint_ids {
+                                clear_hit_temporary_breakpoints(self, id, hit_breakpoint_ids);
+                            }
+                        }
                         let debugger = match self.debug_adapters.get_client_mut(id) {
                             Some(debugger) => debugger,
                             None => return false,
@@ -188,14 +390,21 @@ pub async fn handle_debugger_message(
                                 for thread in response.threads {
                                     fetch_stack_trace(debugger, thread.id).await;
                                 }
-                                select_thread_id(self, thread_id.unwrap_or_default(), false).await;
+                                select_thread_id(self, id, thread_id.unwrap_or_default(), false)
+                                    .await;
                             }
                         } else if let Some(thread_id) = thread_id {
                             debugger.thread_states.insert(thread_id, reason.clone()); // TODO: dap uses "type" || "reason" here
 
                             fetch_stack_trace(debugger, thread_id).await;
                             // whichever thread stops is made "current" (if no previously selected thread).
-                            select_thread_id(self, thread_id, false).await;
+                            select_thread_id(self, id, thread_id, false).await;
+                        }
+
+                        if reason == "exception" {
+                            if let Some(thread_id) = thread_id {
+                                fetch_exception_info(self, id, thread_id).await;
+                            }
                         }
 
                         let scope = match thread_id {
@@ -204,6 +413,13 @@ pub async fn handle_debugger_message(
                         };
 
                         let mut status = format!("{} stopped because of {}", scope, reason);
+                        if let Some(debugger) = self.debug_adapters.get_client(id) {
+                            if !focused {
+                                write!(status, " in background session {}", debugger.name).unwrap();
+                            } else if self.debug_adapters.clients().count() > 1 {
+                                write!(status, " in session {}", debugger.name).unwrap();
+                            }
+                        }
                         if let Some(desc) = description {
                             write!(status, " {}", desc).unwrap();
                         }
@@ -216,7 +432,10 @@ pub async fn handle_debugger_message(
 
                         self.set_status(status);
                     }
-                    Event::Continued(events::ContinuedBody { thread_id, .. }) => {
+                    Event::Continued(events::ContinuedBody {
+                        thread_id,
+                        all_threads_continued,
+                    }) => {
                         let debugger = match self.debug_adapters.get_client_mut(id) {
                             Some(debugger) => debugger,
                             None => return false,
@@ -225,6 +444,11 @@ pub async fn handle_debugger_message(
                         debugger
                             .thread_states
                             .insert(thread_id, "running".to_owned());
+                        if all_threads_continued.unwrap_or(true) {
+                            debugger.exceptions.clear();
+                        } else {
+                            debugger.exceptions.remove(&thread_id);
+                        }
                         if debugger.thread_id == Some(thread_id) {
                             debugger.resume_application();
                         }
@@ -357,17 +581,21 @@ pub async fn handle_debugger_message(
 
                         match restart_arg {
                             Some(Value::Bool(false)) | None => {
+                                // Removing the client moves the focus to another running session.
                                 self.debug_adapters.remove_client(id);
-                                self.debug_adapters.unset_active_client();
                                 self.set_status(
                                     "Terminated debugging session and disconnected debugger.",
                                 );
 
-                                // Go through all breakpoints and set verfified to false
-                                // this should update the UI to show the breakpoints are no longer connected
-                                for breakpoints in self.breakpoints.values_mut() {
-                                    for breakpoint in breakpoints.iter_mut() {
-                                        breakpoint.verified = false;
+                                // Go through all breakpoints and set verfified to false once the last
+                                // session is gone, this should update the UI to show the breakpoints
+                                // are no longer connected
+                                if self.debug_adapters.is_empty() {
+                                    for breakpoints in self.breakpoints.values_mut() {
+                                        breakpoints.retain(|breakpoint| !breakpoint.temporary);
+                                        for breakpoint in breakpoints.iter_mut() {
+                                            breakpoint.verified = false;
+                                        }
                                     }
                                 }
                             }
@@ -405,6 +633,28 @@ pub async fn handle_debugger_message(
                             ));
                         }
                     }
+                    Event::LoadedSource(events::LoadedSourceBody { reason, source }) => {
+                        let Some(debugger) = self.debug_adapters.get_client_mut(id) else {
+                            return false;
+                        };
+                        debugger
+                            .loaded_sources
+                            .retain(|loaded| !is_same_source(loaded, &source));
+                        if reason != "removed" {
+                            debugger.loaded_sources.push(source);
+                        }
+                        return false;
+                    }
+                    Event::Module(events::ModuleBody { reason, module }) => {
+                        let Some(debugger) = self.debug_adapters.get_client_mut(id) else {
+                            return false;
+                        };
+                        debugger.modules.retain(|loaded| loaded.id != module.id);
+                        if reason != "removed" {
+                            debugger.modules.push(module);
+                        }
+                        return false;
+                    }
                     ev => {
                         log::warn!("Unhandled event {:?}", ev);
                         return false; // return early to skip render
@@ -465,6 +715,11 @@ pub async fn handle_debugger_message(
                                 return true;
                             }
                         };
+                        let name = arguments
+                            .configuration
+                            .get("name")
+                            .and_then(Value::as_str)
+                            .map_or_else(|| debugger.name.clone(), str::to_owned);
 
                         let result = self.debug_adapters.start_client(Some(socket), &config);
 
@@ -486,6 +741,8 @@ pub async fn handle_debugger_message(
                                 return true;
                             }
                         };
+                        client.name = name;
+                        client.parent = Some(id);
 
                         let relaunch_resp = if let ConnectionType::Launch = arguments.request {
                             client.launch(arguments.configuration).await
@@ -515,3 +772,95 @@ pub async fn handle_debugger_message(
         true
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn expand(value: Value) -> Value {
+        let resolve = |variable| {
+            Ok(Cow::Borrowed(match variable {
+                Variable::WorkspaceDirectory => "/work",
+                Variable::FilePathAbsolute => "/work/src/main.rs",
+                Variable::CursorLine => "3",
+                _ => unreachable!(),
+            }))
+        };
+        expand_launch_json_value(&value, &resolve).unwrap()
+    }
+
+    #[test]
+    fn remove_hit_temporary_breakpoints_only() {
+        let breakpoint = |id, temporary| Breakpoint {
+            id: Some(id),
+            line: id,
+            temporary,
+            ..Default::default()
+        };
+        let mut breakpoints = vec![
+            breakpoint(1, false),
+            breakpoint(2, true),
+            breakpoint(3, true),
+            Breakpoint {
+                temporary: true,
+                ..Default::default()
+            },
+        ];
+
+        // Stopping elsewhere keeps the temporary breakpoints.
+        assert!(!remove_hit_temporary_breakpoints(&mut breakpoints, &[]));
+        assert!(!remove_hit_temporary_breakpoints(&mut breakpoints, &[1]));
+        assert_eq!(breakpoints.len(), 4);
+
+        assert!(remove_hit_temporary_breakpoints(&mut breakpoints, &[1, 3]));
+        let lines: Vec<_> = breakpoints
+            .iter()
+            .map(|breakpoint| breakpoint.line)
+            .collect();
+        assert_eq!(lines, [1, 2, 0]);
+    }
+
+    #[test]
+    fn expand_workspace_and_file_variables() {
+        assert_eq!(
+            expand(json!({
+                "cwd": "${workspaceFolder}",
+                "program": "${file}",
+                "args": ["${relativeFile}", "${fileBasenameNoExtension}${fileExtname}"],
+                "line": "${lineNumber}",
+                "stopOnEntry": true,
+            })),
+            json!({
+                "cwd": "/work",
+                "program": "/work/src/main.rs",
+                "args": ["src/main.rs", "main.rs"],
+                "line": "3",
+                "stopOnEntry": true,
+            })
+        );
+    }
+
+    #[test]
+    fn expand_env_variables() {
+        std::env::set_var("HELIX_LAUNCH_JSON_TEST", "value");
+        assert_eq!(
+            expand(json!("a ${env:HELIX_LAUNCH_JSON_TEST} b")),
+            json!("a value b")
+        );
+        assert_eq!(expand(json!("${env:HELIX_LAUNCH_JSON_UNSET}")), json!(""));
+    }
+
+    #[test]
+    fn keep_unknown_variables() {
+        assert_eq!(
+            expand(json!({
+                "processId": "${command:pickProcess}",
+                "args": ["${input:args}", "${workspaceFolder}/${unknown"],
+            })),
+            json!({
+                "processId": "${command:pickProcess}",
+                "args": ["${input:args}", "/work/${unknown"],
+            })
+        );
+    }
+}
@@ -13,9 +13,11 @@
 pub mod info;
 pub mod input;
 pub mod keyboard;
+pub mod marks;
 pub mod register;
 pub mod theme;
 pub mod tree;
+pub mod undo_file;
 pub mod view;
 
 use std::num::NonZeroUsize;
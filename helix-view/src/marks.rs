@@ -0,0 +1,418 @@
+//! Named marks.
+//!
+//! Marks `a` to `z` are local to a document and are stored on it. Marks `A` to `Z` are global:
+//! they are stored on their document while it is open and in [`Marks`] otherwise. Both kinds are
+//! mapped through changes to their document like the selections of a view. When
+//! `editor.persist-marks` is enabled the marks are saved in the state directory when the editor
+//! exits and restored on startup: global marks are restored right away and local marks when their
+//! file is opened again.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use helix_core::{Range, Rope, Selection};
+use serde::{Deserialize, Serialize};
+
+use crate::Document;
+
+/// Bumped whenever the format of the marks file changes. Marks files with a different version
+/// are ignored.
+const VERSION: u32 = 2;
+
+/// Whether `name` is a mark which is local to a document.
+pub fn is_local(name: char) -> bool {
+    name.is_ascii_lowercase()
+}
+
+/// Whether `name` is a mark which can be jumped to from any document.
+pub fn is_global(name: char) -> bool {
+    name.is_ascii_uppercase()
+}
+
+/// The file marks are persisted in.
+pub fn marks_file() -> PathBuf {
+    helix_loader::state_dir().join("marks.json")
+}
+
+/// A mark in a file which is not open.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClosedMark {
+    pub selection: Selection,
+    /// The line of the primary cursor when the file was closed, so that the mark can be listed
+    /// without reading the file.
+    pub line: usize,
+}
+
+impl ClosedMark {
+    fn new(text: &Rope, selection: &Selection) -> Self {
+        Self {
+            selection: selection.clone(),
+            line: selection.primary().cursor_line(text.slice(..)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct SerializedMark {
+    /// The `(anchor, head)` pairs of the ranges of the selection.
+    ranges: Vec<(usize, usize)>,
+    primary_index: usize,
+    line: usize,
+}
+
+impl SerializedMark {
+    fn new(mark: &ClosedMark) -> Self {
+        Self {
+            ranges: mark
+                .selection
+                .iter()
+                .map(|range| (range.anchor, range.head))
+                .collect(),
+            primary_index: mark.selection.primary_index(),
+            line: mark.line,
+        }
+    }
+
+    fn mark(&self) -> Option<ClosedMark> {
+        let ranges = self
+            .ranges
+            .iter()
+            .map(|&(anchor, head)| Range::new(anchor, head))
+            .collect();
+        (!self.ranges.is_empty() && self.primary_index < self.ranges.len()).then(|| ClosedMark {
+            selection: Selection::new(ranges, self.primary_index),
+            line: self.line,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct SerializedGlobalMark {
+    path: PathBuf,
+    mark: SerializedMark,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct MarksFile {
+    version: u32,
+    /// Global marks by name.
+    global: BTreeMap<char, SerializedGlobalMark>,
+    /// Local marks by file and name.
+    local: BTreeMap<PathBuf, BTreeMap<char, SerializedMark>>,
+}
+
+/// Marks in documents which are not open.
+#[derive(Debug, Default)]
+pub struct Marks {
+    global: BTreeMap<char, (PathBuf, ClosedMark)>,
+    local: HashMap<PathBuf, BTreeMap<char, ClosedMark>>,
+    /// The global marks as they were loaded. When saving, marks which were saved by other editor
+    /// instances in the meantime are kept unless they were set during this session.
+    loaded: BTreeMap<char, SerializedGlobalMark>,
+    /// Global marks which were set during this session.
+    changed: HashSet<char>,
+    /// Files which were opened during this session. Their local marks replace the saved ones.
+    opened: HashSet<PathBuf>,
+}
+
+impl Marks {
+    /// Loads the marks persisted in `file`.
+    pub fn load(file: &Path) -> Self {
+        let marks = read(file);
+        let global = marks
+            .global
+            .iter()
+            .filter(|(name, _)| is_global(**name))
+            .filter_map(|(name, mark)| Some((*name, (mark.path.clone(), mark.mark.mark()?))))
+            .collect();
+        let local = marks
+            .local
+            .into_iter()
+            .map(|(path, marks)| {
+                let marks = marks
+                    .into_iter()
+                    .filter(|(name, _)| is_local(*name))
+                    .filter_map(|(name, mark)| Some((name, mark.mark()?)))
+                    .collect();
+                (path, marks)
+            })
+            .collect();
+        Self {
+            global,
+            local,
+            loaded: marks.global,
+            ..Self::default()
+        }
+    }
+
+    /// Records that the global mark `name` was set. Any previous location of the mark in a
+    /// document which is not open is forgotten.
+    pub fn set(&mut self, name: char) {
+        self.global.remove(&name);
+        self.changed.insert(name);
+    }
+
+    /// The location of the global mark `name` if it is in a document which is not open.
+    pub fn get(&self, name: char) -> Option<(&Path, &ClosedMark)> {
+        self.global
+            .get(&name)
+            .map(|(path, mark)| (path.as_path(), mark))
+    }
+
+    /// Global marks in documents which are not open.
+    pub fn iter(&self) -> impl Iterator<Item = (char, &Path, &ClosedMark)> {
+        self.global
+            .iter()
+            .map(|(name, (path, mark))| (*name, path.as_path(), mark))
+    }
+
+    /// Moves the marks in the file of `doc` onto the document when it is opened.
+    pub fn restore(&mut self, doc: &mut Document) {
+        let Some(path) = doc.path().cloned() else {
+            return;
+        };
+        let names: Vec<_> = self
+            .global
+            .iter()
+            .filter(|(_, (mark_path, _))| *mark_path == path)
+            .map(|(name, _)| *name)
+            .collect();
+        let global = names
+            .into_iter()
+            .map(|name| (name, self.global.remove(&name).unwrap().1));
+        let local = self.local.remove(&path).unwrap_or_default();
+        for (name, mark) in global.chain(local) {
+            // The file may have been changed by another program.
+            let len = doc.text().len_chars();
+            let selection = mark
+                .selection
+                .transform(|range| Range::new(range.anchor.min(len), range.head.min(len)))
+                .ensure_invariants(doc.text().slice(..));
+            doc.set_mark(name, selection);
+        }
+        self.opened.insert(path);
+    }
+
+    /// Moves the marks of `doc` into the marks of closed documents when it is closed.
+    pub fn store(&mut self, doc: &Document) {
+        let Some(path) = doc.path() else {
+            return;
+        };
+        let mut local = BTreeMap::new();
+        for (name, selection) in doc.marks() {
+            let mark = ClosedMark::new(doc.text(), selection);
+            if is_global(*name) {
+                self.global.insert(*name, (path.clone(), mark));
+            } else {
+                local.insert(*name, mark);
+            }
+        }
+        self.local.insert(path.clone(), local);
+        self.opened.insert(path.clone());
+    }
+
+    /// Saves the marks of the open documents in `docs` and of the closed documents to `file`.
+    ///
+    /// Global marks which were saved by other editor instances since they were loaded are kept
+    /// unless they were set during this session. The local marks of files which were opened
+    /// during this session replace the saved ones.
+    pub fn save<'a>(
+        &self,
+        file: &Path,
+        docs: impl Iterator<Item = &'a Document>,
+    ) -> io::Result<()> {
+        let saved = read(file);
+        let mut marks = saved.clone();
+        let docs: HashMap<_, _> = docs
+            .filter_map(|doc| Some((doc.path()?.as_path(), doc)))
+            .collect();
+
+        let open = docs.iter().flat_map(|(path, doc)| {
+            doc.marks()
+                .iter()
+                .filter(|(name, _)| is_global(**name))
+                .map(|(name, selection)| (*name, *path, ClosedMark::new(doc.text(), selection)))
+        });
+        let closed = self
+            .global
+            .iter()
+            .map(|(name, (path, mark))| (*name, path.as_path(), mark.clone()));
+        for (name, path, mark) in open.chain(closed) {
+            if self.changed.contains(&name) || saved.global.get(&name) == self.loaded.get(&name) {
+                let mark = SerializedGlobalMark {
+                    path: path.to_path_buf(),
+                    mark: SerializedMark::new(&mark),
+                };
+                marks.global.insert(name, mark);
+            }
+        }
+
+        for path in &self.opened {
+            let local: BTreeMap<_, _> = match docs.get(path.as_path()) {
+                Some(doc) => doc
+                    .marks()
+                    .iter()
+                    .filter(|(name, _)| is_local(**name))
+                    .map(|(name, selection)| {
+                        let mark = ClosedMark::new(doc.text(), selection);
+                        (*name, SerializedMark::new(&mark))
+                    })
+                    .collect(),
+                None => self
+                    .local
+                    .get(path)
+                    .into_iter()
+                    .flatten()
+                    .map(|(name, mark)| (*name, SerializedMark::new(mark)))
+                    .collect(),
+            };
+            if local.is_empty() {
+                marks.local.remove(path);
+            } else {
+                marks.local.insert(path.clone(), local);
+            }
+        }
+
+        if marks == saved {
+            return Ok(());
+        }
+        marks.version = VERSION;
+        let contents = serde_json::to_vec(&marks).map_err(io::Error::other)?;
+        helix_loader::write_state_file(file, &contents)
+    }
+}
+
+fn read(file: &Path) -> MarksFile {
+    let Ok(contents) = fs::read(file) else {
+        return MarksFile::default();
+    };
+    match serde_json::from_slice::<MarksFile>(&contents) {
+        Ok(marks) if marks.version == VERSION => marks,
+        Ok(_) => MarksFile::default(),
+        Err(err) => {
+            log::warn!("Failed to parse marks file '{}': {err}", file.display());
+            MarksFile::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn mark(anchor: usize, head: usize) -> ClosedMark {
+        ClosedMark {
+            selection: Selection::single(anchor, head),
+            line: 0,
+        }
+    }
+
+    fn global(path: &str, anchor: usize, head: usize) -> SerializedGlobalMark {
+        SerializedGlobalMark {
+            path: path.into(),
+            mark: SerializedMark::new(&mark(anchor, head)),
+        }
+    }
+
+    #[test]
+    fn save_merges_with_other_instances() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("marks.json");
+
+        let mut first = Marks::default();
+        first.set('A');
+        first.global.insert('A', ("/a".into(), mark(1, 2)));
+        first.save(&file, std::iter::empty()).unwrap();
+
+        // Another instance which was started before `A` was saved sets `B` and exits later.
+        let mut second = Marks::default();
+        second.set('B');
+        second.global.insert('B', ("/b".into(), mark(3, 4)));
+        second.save(&file, std::iter::empty()).unwrap();
+
+        let marks = read(&file);
+        assert_eq!(marks.global.get(&'A'), Some(&global("/a", 1, 2)));
+        assert_eq!(marks.global.get(&'B'), Some(&global("/b", 3, 4)));
+
+        let loaded = Marks::load(&file);
+        assert_eq!(loaded.get('B'), Some((Path::new("/b"), &mark(3, 4))));
+        assert!(loaded.changed.is_empty());
+
+        // Marks which are set again replace the saved marks.
+        let mut third = Marks::load(&file);
+        third.set('A');
+        third.global.insert('A', ("/c".into(), mark(5, 6)));
+        third.save(&file, std::iter::empty()).unwrap();
+        assert_eq!(read(&file).global.get(&'A'), Some(&global("/c", 5, 6)));
+        assert_eq!(read(&file).global.get(&'B'), Some(&global("/b", 3, 4)));
+    }
+
+    #[test]
+    fn save_moved_marks() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("marks.json");
+        let mut marks = Marks::default();
+        marks.set('A');
+        marks.global.insert('A', ("/a".into(), mark(1, 2)));
+        marks.save(&file, std::iter::empty()).unwrap();
+
+        // A loaded mark which moved because its file was edited is saved at its new position.
+        let mut first = Marks::load(&file);
+        let mut second = Marks::load(&file);
+        first.global.insert('A', ("/a".into(), mark(3, 4)));
+        first.save(&file, std::iter::empty()).unwrap();
+        assert_eq!(read(&file).global.get(&'A'), Some(&global("/a", 3, 4)));
+
+        // Unless another instance saved the mark since it was loaded.
+        second.global.insert('A', ("/a".into(), mark(5, 6)));
+        second.save(&file, std::iter::empty()).unwrap();
+        assert_eq!(read(&file).global.get(&'A'), Some(&global("/a", 3, 4)));
+    }
+
+    #[test]
+    fn save_local_marks() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("marks.json");
+        let mut marks = Marks::default();
+        marks
+            .local
+            .insert("/a".into(), BTreeMap::from([('a', mark(1, 2))]));
+        marks
+            .local
+            .insert("/b".into(), BTreeMap::from([('b', mark(3, 4))]));
+        marks.opened.insert("/a".into());
+        marks.opened.insert("/b".into());
+        marks.save(&file, std::iter::empty()).unwrap();
+
+        let mut loaded = Marks::load(&file);
+        assert_eq!(loaded.local.get(Path::new("/a")).unwrap()[&'a'], mark(1, 2));
+        assert_eq!(loaded.local.get(Path::new("/b")).unwrap()[&'b'], mark(3, 4));
+
+        // Only the local marks of files which were opened replace the saved marks.
+        loaded.local.clear();
+        loaded.opened.insert("/a".into());
+        loaded.save(&file, std::iter::empty()).unwrap();
+        let marks = read(&file);
+        assert!(!marks.local.contains_key(Path::new("/a")));
+        assert!(marks.local.contains_key(Path::new("/b")));
+    }
+
+    #[test]
+    fn invalid_marks_are_ignored() {
+        assert_eq!(
+            SerializedMark {
+                ranges: Vec::new(),
+                primary_index: 0,
+                line: 0,
+            }
+            .mark(),
+            None
+        );
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("marks.json");
+        fs::write(&file, "not json").unwrap();
+        assert!(Marks::load(&file).iter().next().is_none());
+    }
+}
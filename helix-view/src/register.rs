@@ -1,14 +1,46 @@
-use std::{borrow::Cow, collections::HashMap, iter};
+use std::{
+    borrow::Cow,
+    collections::{BTreeMap, HashMap},
+    fs, io, iter,
+    path::{Path, PathBuf},
+};
 
 use anyhow::Result;
 use arc_swap::access::DynAccess;
 use helix_core::NATIVE_LINE_ENDING;
+use serde::{Deserialize, Serialize};
 
 use crate::{
     clipboard::{ClipboardError, ClipboardProvider, ClipboardType},
+    editor::PersistentRegistersConfig,
     Editor,
 };
 
+/// Bumped whenever the format of the registers file changes. Registers files with a different
+/// version are ignored.
+const VERSION: u32 = 1;
+
+/// Registers which hold the history of a prompt: commands, searches and shell commands. When
+/// persisted, their entries are merged with the entries saved by other editor instances instead
+/// of replacing them.
+const HISTORY_REGISTERS: [char; 3] = [':', '/', '|'];
+
+/// Separates the values of a register when it is edited in a buffer. It is placed on a line of
+/// its own between two values.
+pub const VALUE_SEPARATOR: &str = "\u{241e}";
+
+/// The file persisted registers are saved in.
+pub fn registers_file() -> PathBuf {
+    helix_loader::state_dir().join("registers.json")
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct RegistersFile {
+    version: u32,
+    /// The values of each register, oldest first.
+    registers: BTreeMap<char, Vec<String>>,
+}
+
 /// A key-value store for saving sets of values.
 ///
 /// Each register corresponds to a `char`. Most chars can be used to store any set of
@@ -29,6 +61,9 @@ pub struct Registers {
     inner: HashMap<char, Vec<String>>,
     clipboard_provider: Box<dyn DynAccess<ClipboardProvider>>,
     pub last_search_register: char,
+    /// The values of the registers which were restored from the registers file, used to detect
+    /// which registers were changed during this session.
+    restored: HashMap<char, Vec<String>>,
 }
 
 impl Registers {
@@ -37,9 +72,63 @@ pub fn new(clipboard_provider: Box<dyn DynAccess<ClipboardProvider>>) -> Self {
             inner: Default::default(),
             clipboard_provider,
             last_search_register: '/',
+            restored: HashMap::new(),
+        }
+    }
+
+    /// Restores the registers saved in `file` which are configured to be persisted.
+    pub fn restore(&mut self, file: &Path, config: &PersistentRegistersConfig) {
+        for (name, values) in read(file) {
+            if is_persistable(name) && config.registers.contains(&name) {
+                self.inner.insert(name, values.clone());
+                self.restored.insert(name, values);
+            }
         }
     }
 
+    /// Saves the registers which are configured to be persisted to `file`.
+    ///
+    /// Registers which were not changed during this session keep the values saved by other
+    /// editor instances in the meantime. New entries of the history registers are added to the
+    /// saved history, which is limited to `max-history` entries. Other registers which were
+    /// cleared or whose values are larger than `max-size` bytes are removed from the file.
+    pub fn save(&self, file: &Path, config: &PersistentRegistersConfig) -> io::Result<()> {
+        let mut saved = read(file);
+        for &name in &config.registers {
+            let values = self.inner.get(&name).map_or(&[][..], Vec::as_slice);
+            let restored = self.restored.get(&name).map_or(&[][..], Vec::as_slice);
+            if !is_persistable(name) || values == restored {
+                continue;
+            }
+
+            if HISTORY_REGISTERS.contains(&name) {
+                let new = values.strip_prefix(restored).unwrap_or(values);
+                let history = saved.entry(name).or_default();
+                history.retain(|entry| !new.contains(entry));
+                history.extend(
+                    new.iter()
+                        .filter(|entry| entry.len() <= config.max_size)
+                        .cloned(),
+                );
+                let excess = history.len().saturating_sub(config.max_history);
+                history.drain(..excess);
+            } else if !values.is_empty()
+                && values.iter().map(String::len).sum::<usize>() <= config.max_size
+            {
+                saved.insert(name, values.to_vec());
+            } else {
+                saved.remove(&name);
+            }
+        }
+
+        let contents = serde_json::to_vec(&RegistersFile {
+            version: VERSION,
+            registers: saved,
+        })
+        .map_err(io::Error::other)?;
+        helix_loader::write_state_file(file, &contents)
+    }
+
     pub fn read<'a>(&'a self, name: char, editor: &'a Editor) -> Option<RegisterValues<'a>> {
         match name {
             '_' => Some(RegisterValues::new(iter::empty())),
@@ -257,6 +346,53 @@ fn read_from_clipboard<'a>(
     }
 }
 
+/// Whether the values of the register cannot be changed. Values written to the black hole
+/// register are discarded.
+pub fn is_read_only(name: char) -> bool {
+    matches!(name, '_' | '#' | '.' | '%')
+}
+
+/// Joins the values of a register into the text of a register buffer, see [`VALUE_SEPARATOR`].
+pub fn values_to_text<S: AsRef<str>>(values: &[S]) -> String {
+    let separator = format!("\n{VALUE_SEPARATOR}\n");
+    values
+        .iter()
+        .map(AsRef::as_ref)
+        .collect::<Vec<_>>()
+        .join(&separator)
+}
+
+/// Splits the text of a register buffer into the values of the register. This is the inverse of
+/// [`values_to_text`].
+pub fn values_from_text(text: &str) -> Vec<String> {
+    let separator = format!("\n{VALUE_SEPARATOR}\n");
+    let crlf_separator = format!("\r\n{VALUE_SEPARATOR}\r\n");
+    text.split(&separator)
+        .flat_map(|value| value.split(&crlf_separator))
+        .map(str::to_owned)
+        .collect()
+}
+
+/// Whether the register stores its values itself. Special registers and the clipboards are
+/// never persisted.
+fn is_persistable(name: char) -> bool {
+    !matches!(name, '_' | '#' | '.' | '%' | '*' | '+')
+}
+
+fn read(file: &Path) -> BTreeMap<char, Vec<String>> {
+    let Ok(contents) = fs::read(file) else {
+        return BTreeMap::new();
+    };
+    match serde_json::from_slice::<RegistersFile>(&contents) {
+        Ok(registers) if registers.version == VERSION => registers.registers,
+        Ok(_) => BTreeMap::new(),
+        Err(err) => {
+            log::warn!("Failed to parse registers file '{}': {err}", file.display());
+            BTreeMap::new()
+        }
+    }
+}
+
 fn contents_are_saved(saved_values: &[String], mut contents: &str) -> bool {
     let line_ending = NATIVE_LINE_ENDING.as_str();
     let mut values = saved_values.iter().rev();
@@ -333,3 +469,119 @@ fn len(&self) -> usize {
 trait DoubleEndedExactSizeIterator: DoubleEndedIterator + ExactSizeIterator {}
 
 impl<I: DoubleEndedIterator + ExactSizeIterator> DoubleEndedExactSizeIterator for I {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn registers() -> Registers {
+        Registers::new(Box::new(arc_swap::access::Constant(
+            ClipboardProvider::default(),
+        )))
+    }
+
+    fn values(registers: &Registers, name: char) -> Vec<String> {
+        registers.inner.get(&name).cloned().unwrap_or_default()
+    }
+
+    fn config() -> PersistentRegistersConfig {
+        PersistentRegistersConfig {
+            enable: true,
+            registers: vec!['a', 'b', '@', ':'],
+            max_size: 10,
+            max_history: 3,
+        }
+    }
+
+    #[test]
+    fn persist_registers() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("registers.json");
+
+        let mut first = registers();
+        first.write('a', vec!["one".into(), "two".into()]).unwrap();
+        first.write('b', vec!["too large to save".into()]).unwrap();
+        first.write('c', vec!["not saved".into()]).unwrap();
+        first.write('@', vec!["ihi<esc>".into()]).unwrap();
+        first.push(':', "write".into()).unwrap();
+        first.save(&file, &config()).unwrap();
+
+        let mut second = registers();
+        second.restore(&file, &config());
+        assert_eq!(second.inner.len(), 3);
+        assert_eq!(values(&second, 'a'), ["two", "one"]);
+        assert_eq!(values(&second, '@'), ["ihi<esc>"]);
+        assert_eq!(values(&second, ':'), ["write"]);
+    }
+
+    #[test]
+    fn register_buffer_text() {
+        let values = ["ihi<esc>".to_owned()];
+        assert_eq!(values_to_text(&values), "ihi<esc>");
+        assert_eq!(values_from_text("ihi<esc>"), values);
+
+        // Line-wise values keep their line endings.
+        let values = ["one\n".to_owned(), "two".to_owned(), "".to_owned()];
+        let text = values_to_text(&values);
+        assert_eq!(text, "one\n\n\u{241e}\ntwo\n\u{241e}\n");
+        assert_eq!(values_from_text(&text), values);
+        assert_eq!(
+            values_from_text(&text.replace('\n', "\r\n")),
+            ["one\r\n", "two", ""]
+        );
+    }
+
+    #[test]
+    fn merge_with_other_instances() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("registers.json");
+
+        let mut first = registers();
+        first.push(':', "write".into()).unwrap();
+        first.write('a', vec!["first".into()]).unwrap();
+        first.save(&file, &config()).unwrap();
+
+        // Two instances are started with the saved registers.
+        let mut second = registers();
+        second.restore(&file, &config());
+        let mut third = registers();
+        third.restore(&file, &config());
+
+        second.push(':', "sort".into()).unwrap();
+        second.write('a', vec!["second".into()]).unwrap();
+        second.save(&file, &config()).unwrap();
+
+        // The third instance did not change `a` so the value of the second instance is kept.
+        third.push(':', "quit".into()).unwrap();
+        third.push(':', "write".into()).unwrap();
+        third.push(':', "reflow".into()).unwrap();
+        third.save(&file, &config()).unwrap();
+
+        let mut restored = registers();
+        restored.restore(&file, &config());
+        assert_eq!(values(&restored, 'a'), ["second"]);
+        assert_eq!(values(&restored, ':'), ["quit", "write", "reflow"]);
+    }
+
+    #[test]
+    fn remove_stale_registers() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("registers.json");
+
+        let mut first = registers();
+        first.write('a', vec!["one".into()]).unwrap();
+        first.write('b', vec!["two".into()]).unwrap();
+        first.save(&file, &config()).unwrap();
+
+        // Registers which became too large to save or were cleared don't keep their old values.
+        let mut second = registers();
+        second.restore(&file, &config());
+        second.write('a', vec!["too large to save".into()]).unwrap();
+        second.remove('b');
+        second.save(&file, &config()).unwrap();
+
+        let mut restored = registers();
+        restored.restore(&file, &config());
+        assert!(restored.inner.is_empty());
+    }
+}
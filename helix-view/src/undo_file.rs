@@ -0,0 +1,183 @@
+//! Persistent undo history.
+//!
+//! When `editor.persistent-undo` is enabled the undo history of a document is written to an undo
+//! file in the state directory whenever the document is written. The undo file stores a hash of
+//! the written text and is only restored if the file on disk still has the same contents.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Once;
+use std::time::{Duration, SystemTime};
+
+use helix_core::history::{History, SerializedHistory};
+use helix_core::Rope;
+use serde::{Deserialize, Serialize};
+
+use crate::editor::PersistentUndoConfig;
+
+/// Bumped whenever the format of undo files changes. Undo files with a different version are
+/// ignored.
+const VERSION: u32 = 2;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct UndoFile {
+    version: u32,
+    /// The path of the document, to tell apart documents whose paths have the same hash.
+    path: PathBuf,
+    /// Hash of the text of the document at the current revision of the history.
+    hash: u64,
+    history: SerializedHistory,
+}
+
+/// The directory undo files are stored in.
+pub fn undo_dir() -> PathBuf {
+    helix_loader::state_dir().join("undo")
+}
+
+/// The undo file for the document at `path`. Undo files are named after a hash of the canonical
+/// path of the document, which keeps the file name short no matter how deep the path is.
+pub fn undo_file_path(path: &Path) -> PathBuf {
+    let path = helix_stdx::path::canonicalize(path);
+    let hash = fnv1a(path.to_string_lossy().bytes());
+    undo_dir().join(format!("{hash:016x}.json"))
+}
+
+/// A hash which is stable across editor versions and platforms (FNV-1a).
+fn fnv1a(bytes: impl Iterator<Item = u8>) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+
+    bytes.fold(OFFSET_BASIS, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(PRIME)
+    })
+}
+
+fn text_hash(text: &Rope) -> u64 {
+    fnv1a(text.chunks().flat_map(str::bytes))
+}
+
+/// Serializes the history as of `revision`, whose text is `text`, for the document at `path`.
+/// Returns `None` if the undo file would be larger than the configured maximum size.
+pub fn serialize(
+    path: &Path,
+    history: &History,
+    revision: usize,
+    text: &Rope,
+    config: &PersistentUndoConfig,
+) -> Option<Vec<u8>> {
+    let undo_file = UndoFile {
+        version: VERSION,
+        path: helix_stdx::path::canonicalize(path),
+        hash: text_hash(text),
+        history: history.serialize(revision),
+    };
+    let contents = match serde_json::to_vec(&undo_file) {
+        Ok(contents) => contents,
+        Err(err) => {
+            log::error!("Failed to serialize undo history: {err}");
+            return None;
+        }
+    };
+    (contents.len() as u64 <= config.max_size).then_some(contents)
+}
+
+/// Writes the undo file of the document at `path`. If `contents` is `None` because the history
+/// was too large, any previous undo file is removed instead. Undo files which are older than the
+/// configured maximum age are removed on the first write of the session.
+pub fn write(path: &Path, contents: Option<Vec<u8>>, max_age: Duration) -> io::Result<()> {
+    static REMOVE_EXPIRED: Once = Once::new();
+
+    let undo_file = undo_file_path(path);
+    match contents {
+        Some(contents) => helix_loader::write_state_file(&undo_file, &contents)?,
+        None => match fs::remove_file(&undo_file) {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => return Err(err),
+            _ => (),
+        },
+    }
+    REMOVE_EXPIRED.call_once(|| {
+        if let Err(err) = remove_expired(max_age) {
+            log::error!("Failed to remove expired undo files: {err}");
+        }
+    });
+    Ok(())
+}
+
+/// Restores the history of the document at `path` if an undo file exists for it which matches
+/// the current `text` of the document.
+pub fn load(path: &Path, text: &Rope, config: &PersistentUndoConfig) -> Option<History> {
+    let undo_file = undo_file_path(path);
+    if is_expired(&undo_file, max_age(config)) {
+        let _ = fs::remove_file(&undo_file);
+        return None;
+    }
+    let contents = fs::read(&undo_file).ok()?;
+    let undo_file: UndoFile = match serde_json::from_slice(&contents) {
+        Ok(undo_file) => undo_file,
+        Err(err) => {
+            log::warn!("Failed to parse undo file for '{}': {err}", path.display());
+            return None;
+        }
+    };
+    if undo_file.version != VERSION
+        || undo_file.path != helix_stdx::path::canonicalize(path)
+        || undo_file.hash != text_hash(text)
+    {
+        return None;
+    }
+    History::try_from(undo_file.history)
+        .and_then(|history| history.validate(text).map(|_| history))
+        .map_err(|err| log::warn!("Invalid undo file for '{}': {err}", path.display()))
+        .ok()
+}
+
+pub fn max_age(config: &PersistentUndoConfig) -> Duration {
+    Duration::from_secs(config.max_age.saturating_mul(24 * 60 * 60))
+}
+
+fn is_expired(undo_file: &Path, max_age: Duration) -> bool {
+    fs::metadata(undo_file)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+        .is_some_and(|age| age > max_age)
+}
+
+fn remove_expired(max_age: Duration) -> io::Result<()> {
+    for entry in fs::read_dir(undo_dir())? {
+        let path = entry?.path();
+        if is_expired(&path, max_age) {
+            fs::remove_file(path)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn undo_file_names() {
+        let path = undo_file_path(Path::new("/home/user/100%/main.rs"));
+        let name = path.file_name().unwrap().to_str().unwrap();
+        assert_eq!(name.len(), "0123456789abcdef.json".len());
+        assert_ne!(path, undo_file_path(Path::new("/home/user/100%/lib.rs")));
+
+        // Deep paths don't exceed the maximum length of file names.
+        let deep = Path::new("/")
+            .join("directory/".repeat(100))
+            .join("main.rs");
+        assert_eq!(undo_file_path(&deep).file_name().unwrap().len(), name.len());
+    }
+
+    #[test]
+    fn text_hash_is_chunk_independent() {
+        let text = "a".repeat(10_000);
+        let mut rope = Rope::from(&text[..5_000]);
+        rope.insert(5_000, &text[5_000..]);
+        assert_eq!(text_hash(&rope), text_hash(&Rope::from(text.as_str())));
+        assert_ne!(text_hash(&rope), text_hash(&Rope::from("a")));
+    }
+}
@@ -8,7 +8,10 @@
     Align, Document, DocumentId, Theme, ViewId,
 };
 
+#[allow(deprecated)]
+use helix_core::visual_coords_at_pos;
 use helix_core::{
+    block_selection::{BlockSelection, VirtualSpace},
     char_idx_at_visual_offset,
     doc_formatter::TextFormat,
     text_annotations::TextAnnotations,
@@ -124,6 +127,37 @@ pub struct ViewPosition {
     pub vertical_offset: usize,
 }
 
+/// A block selection which follows the primary cursor while in select mode.
+#[derive(Debug, Clone)]
+pub struct ActiveBlockSelection {
+    pub block: BlockSelection,
+    /// The selection the block was last converted into, used to detect when a command
+    /// changed the selection.
+    pub selection: Selection,
+    /// The visual position of the primary cursor of `selection`.
+    pub cursor: Position,
+}
+
+impl ActiveBlockSelection {
+    // uses the deprecated `visual_coords_at_pos` since block selections work on the visual
+    // columns of the text and ignore softwrap and decorations
+    #[allow(deprecated)]
+    pub fn new(
+        block: BlockSelection,
+        text: RopeSlice,
+        tab_width: usize,
+        virtual_space: VirtualSpace,
+    ) -> Self {
+        let selection = block.to_selection(text, tab_width, virtual_space);
+        let cursor = visual_coords_at_pos(text, selection.primary().cursor(text), tab_width);
+        Self {
+            block,
+            selection,
+            cursor,
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct View {
     pub id: ViewId,
@@ -139,6 +173,8 @@ pub struct View {
     pub last_modified_docs: [Option<DocumentId>; 2],
     /// used to store previous selections of tree-sitter objects
     pub object_selections: Vec<Selection>,
+    /// The block selection which is kept up to date while in select mode.
+    pub block_selection: Option<ActiveBlockSelection>,
     /// all gutter-related configuration settings, used primarily for gutter rendering
     pub gutters: GutterConfig,
     /// A mapping between documents and the last history revision the view was updated at.
@@ -176,6 +212,7 @@ pub fn new(doc: DocumentId, gutters: GutterConfig) -> Self {
             docs_access_history: Vec::new(),
             last_modified_docs: [None, None],
             object_selections: Vec::new(),
+            block_selection: None,
             gutters,
             doc_revisions: HashMap::new(),
             diagnostics_handler: DiagnosticsHandler::new(),